@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use aarch64_esr_decoder::{decode, parse_number, DecodeError, FieldInfo};
+use aarch64_esr_decoder::{bits, decode, parse_number, DecodeError, FieldInfo};
 use std::convert::TryFrom;
 use std::ops::Deref;
 use wasm_bindgen::prelude::*;
@@ -112,8 +112,7 @@ fn show_decoded(
     // ESR in binary
     let row = document.create_element("tr")?;
     row.set_attribute("class", "value")?;
-    for i in (0..nr_bit).rev() {
-        let bit = esr & (1 << i) != 0;
+    for bit in bits(esr, nr_bit) {
         let cell = make_cell(&document, Some(if bit { "1" } else { "0" }), None, 1)?;
         row.append_child(&cell)?;
     }
@@ -129,6 +128,7 @@ fn show_decoded(
                 &document,
                 &row,
                 &fields,
+                0,
                 &mut last,
                 |field| Some(field.to_string()),
                 |field| field.long_name,
@@ -143,6 +143,7 @@ fn show_decoded(
                 &document,
                 &row,
                 &fields,
+                0,
                 &mut last,
                 |field| field.description.clone(),
                 |_| None,
@@ -158,6 +159,7 @@ fn show_decoded(
                     &document,
                     &row,
                     &field.subfields,
+                    field.start,
                     &mut last,
                     |field| Some(field.to_string()),
                     |field| field.long_name,
@@ -174,6 +176,7 @@ fn show_decoded(
                     &document,
                     &row,
                     &field.subfields,
+                    field.start,
                     &mut last,
                     |field| field.description.clone(),
                     |_| None,
@@ -206,10 +209,13 @@ fn make_cell(
     Ok(cell)
 }
 
+/// Adds cells to the given row for the given fields, whose bit positions are relative to the given
+/// offset, with fillers for any gaps between them.
 fn add_field_cells<F, G, S>(
     document: &Document,
     row: &Element,
     fields: &[FieldInfo],
+    offset: usize,
     last: &mut usize,
     get_contents: F,
     get_hover_title: G,
@@ -219,20 +225,63 @@ where
     G: Fn(&FieldInfo) -> Option<&str>,
     S: Deref<Target = str>,
 {
-    for field in fields {
-        if field.start + field.width != *last {
+    for (field, colspan) in field_spans(fields, offset, last) {
+        let cell = match field {
+            Some(field) => make_cell(
+                document,
+                get_contents(field).as_deref(),
+                get_hover_title(field),
+                colspan,
+            )?,
             // Add a filler
-            let cell = make_cell(document, None, None, *last - field.start - field.width)?;
-            row.append_child(&cell)?;
-        }
-        let cell = make_cell(
-            document,
-            get_contents(field).as_deref(),
-            get_hover_title(field).as_deref(),
-            field.width,
-        )?;
+            None => make_cell(document, None, None, colspan)?,
+        };
         row.append_child(&cell)?;
-        *last = field.start;
     }
     Ok(())
 }
+
+/// Returns the number of columns which each of the given fields should span, with `None` for a
+/// filler before any field which doesn't start where the previous one ended.
+///
+/// The bit positions of the fields are relative to the given offset, as subfields are relative to
+/// their parent, while `last` is the absolute position where the previous field started.
+fn field_spans<'a>(
+    fields: &'a [FieldInfo],
+    offset: usize,
+    last: &mut usize,
+) -> Vec<(Option<&'a FieldInfo>, usize)> {
+    let mut spans = vec![];
+    for field in fields {
+        let start = offset + field.start;
+        if start + field.width != *last {
+            spans.push((None, *last - start - field.width));
+        }
+        spans.push((Some(field), field.width));
+        *last = start;
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_abort_subfield_spans() {
+        let fields = decode(0x96000045).unwrap();
+        let mut last = 64;
+        let mut total = 0;
+        for field in &fields {
+            for (subfield, colspan) in field_spans(&field.subfields, field.start, &mut last) {
+                assert!(colspan <= 64);
+                if subfield.map(|subfield| subfield.name) == Some("ISV") {
+                    // ISV is bit 24, so after it there are 24 bits left.
+                    assert_eq!(total + colspan, 64 - 24);
+                }
+                total += colspan;
+            }
+        }
+        assert_eq!(total + last, 64);
+    }
+}
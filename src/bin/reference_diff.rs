@@ -0,0 +1,60 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compares the decoder against reference expectations from another source of truth, such as
+//! values extracted from Arm's machine-readable architecture specification, and reports any
+//! discrepancies.
+
+use aarch64_esr_decoder::compare_reference;
+use std::env;
+use std::fs;
+use std::process::exit;
+
+fn main() {
+    let args: Vec<_> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage:");
+        eprintln!("  {} <reference file>...", args[0]);
+        eprintln!("Each line of a reference file is a JSON expectation such as");
+        eprintln!(
+            "  {{\"esr\": \"0x96000045\", \"fields\": {{\"EC\": \"0x25\", \"ISS.WnR\": 1}}}}"
+        );
+        exit(1);
+    }
+
+    let mut checked = 0;
+    let mut discrepancies = 0;
+    for path in &args[1..] {
+        let reference = fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("Error reading {}: {}", path, e);
+            exit(1);
+        });
+        let report = compare_reference(&reference).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            exit(1);
+        });
+        for discrepancy in &report.discrepancies {
+            println!("{}: {}", path, discrepancy);
+        }
+        checked += report.checked;
+        discrepancies += report.discrepancies.len();
+    }
+    println!(
+        "Checked {} expectations, found {} discrepancies.",
+        checked, discrepancies
+    );
+    if discrepancies > 0 {
+        exit(2);
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Converts register definitions from Arm's machine-readable SysReg XML into Rust source for the
+//! decoder's tables, to be pasted in and reviewed by hand.
+
+use aarch64_esr_decoder::{field_decoder, parse_sysreg_xml, sysreg_name_arms};
+use std::env;
+use std::fs;
+use std::process::exit;
+
+fn main() {
+    let args: Vec<_> = env::args().collect();
+    let (fields, paths) = match args.get(1).map(String::as_str) {
+        Some("--fields") => (true, &args[2..]),
+        _ => (false, &args[1..]),
+    };
+    if paths.is_empty() {
+        eprintln!("Usage:");
+        eprintln!("  {} [--fields] <SysReg XML file>...", args[0]);
+        eprintln!("By default, prints match arms for the MSR and MRS system register name table.");
+        eprintln!("With --fields, prints statements to decode the fields of each register.");
+        exit(1);
+    }
+
+    let mut registers = vec![];
+    for path in paths {
+        let xml = fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("Error reading {}: {}", path, e);
+            exit(1);
+        });
+        match parse_sysreg_xml(&xml) {
+            Ok(definitions) => registers.extend(definitions),
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                exit(1);
+            }
+        }
+    }
+    registers.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if fields {
+        for register in &registers {
+            println!("{}", field_decoder(register));
+        }
+    } else {
+        print!("{}", sysreg_name_arms(&registers));
+    }
+}
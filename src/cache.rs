@@ -0,0 +1,385 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoders for the registers which describe the caches.
+
+use super::{DecodeError, FieldInfo, Unit};
+
+/// Decodes the given Cache Type Register value, giving cache line sizes in bytes, or returns an
+/// error if it is not valid.
+pub fn decode_ctr_el0(ctr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0a = FieldInfo::get(ctr, "RES0", Some("Reserved"), 38, 64).check_res0()?;
+    let tminline = with_line_size(FieldInfo::get(
+        ctr,
+        "TminLine",
+        Some("Tag minimum Line"),
+        32,
+        38,
+    ));
+    let res1 = FieldInfo::get_bit(ctr, "RES1", Some("Reserved"), 31);
+    let res0b = FieldInfo::get_bit(ctr, "RES0", Some("Reserved"), 30).check_res0()?;
+    let dic = FieldInfo::get_bit(
+        ctr,
+        "DIC",
+        Some("Instruction cache invalidation requirements for data to instruction coherence"),
+        29,
+    )
+    .describe_bit(describe_dic);
+    let idc = FieldInfo::get_bit(
+        ctr,
+        "IDC",
+        Some("Data cache clean requirements for instruction to data coherence"),
+        28,
+    )
+    .describe_bit(describe_idc);
+    let cwg = with_optional_size(FieldInfo::get(
+        ctr,
+        "CWG",
+        Some("Cache writeback granule"),
+        24,
+        28,
+    ));
+    let erg = with_optional_size(FieldInfo::get(
+        ctr,
+        "ERG",
+        Some("Exclusives reservation granule"),
+        20,
+        24,
+    ));
+    let dminline = with_line_size(FieldInfo::get(
+        ctr,
+        "DminLine",
+        Some("Smallest data cache line size"),
+        16,
+        20,
+    ));
+    let l1ip = FieldInfo::get(
+        ctr,
+        "L1Ip",
+        Some("Level 1 instruction cache policy"),
+        14,
+        16,
+    )
+    .describe(describe_l1ip)?;
+    let res0c = FieldInfo::get(ctr, "RES0", Some("Reserved"), 4, 14).check_res0()?;
+    let iminline = with_line_size(FieldInfo::get(
+        ctr,
+        "IminLine",
+        Some("Smallest instruction cache line size"),
+        0,
+        4,
+    ));
+
+    Ok(vec![
+        res0a, tminline, res1, res0b, dic, idc, cwg, erg, dminline, l1ip, res0c, iminline,
+    ])
+}
+
+/// Decodes the given Data Cache Zero ID Register value, giving the block size which DC ZVA zeroes
+/// in bytes, or returns an error if it is not valid.
+pub fn decode_dczid_el0(dczid: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(dczid, "RES0", Some("Reserved"), 5, 64).check_res0()?;
+    let dzp = FieldInfo::get_bit(dczid, "DZP", Some("Data Zero Prohibited"), 4)
+        .describe_bit(describe_dzp);
+    let bs = with_line_size(FieldInfo::get(dczid, "BS", Some("Block Size"), 0, 4));
+
+    Ok(vec![res0, dzp, bs])
+}
+
+/// Decodes the given Cache Level ID Register value, or returns an error if it is not valid.
+pub fn decode_clidr_el1(clidr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let mut fields = vec![FieldInfo::get(clidr, "RES0", Some("Reserved"), 47, 64).check_res0()?];
+    for level in (1..=7).rev() {
+        let start = 33 + 2 * (level - 1);
+        fields.push(
+            FieldInfo::get(
+                clidr,
+                TTYPE_NAMES[level - 1],
+                Some("Tag cache type"),
+                start,
+                start + 2,
+            )
+            .describe(describe_ttype)?,
+        );
+    }
+    let icb = FieldInfo::get(clidr, "ICB", Some("Inner cache boundary"), 30, 33);
+    let icb_description = if icb.value == 0 {
+        "Not disclosed".to_string()
+    } else {
+        format!("Level {} is the highest Inner Cacheable level", icb.value)
+    };
+    let icb = icb.with_description(icb_description);
+    let louu = with_cache_level(FieldInfo::get(
+        clidr,
+        "LoUU",
+        Some("Level of Unification Uniprocessor"),
+        27,
+        30,
+    ));
+    let loc = with_cache_level(FieldInfo::get(
+        clidr,
+        "LoC",
+        Some("Level of Coherence"),
+        24,
+        27,
+    ));
+    let louis = with_cache_level(FieldInfo::get(
+        clidr,
+        "LoUIS",
+        Some("Level of Unification Inner Shareable"),
+        21,
+        24,
+    ));
+    fields.extend([icb, louu, loc, louis]);
+    for level in (1..=7).rev() {
+        let start = 3 * (level - 1);
+        fields.push(
+            FieldInfo::get(
+                clidr,
+                CTYPE_NAMES[level - 1],
+                Some("Cache type"),
+                start,
+                start + 3,
+            )
+            .describe(describe_ctype)?,
+        );
+    }
+
+    Ok(fields)
+}
+
+/// Decodes the given Current Cache Size ID Register value, giving the number of sets,
+/// associativity and line size of the cache, or returns an error if it is not valid.
+///
+/// The layout depends on whether FEAT_CCIDX is implemented, as indicated by
+/// ID_AA64MMFR2_EL1.CCIDX.
+pub fn decode_ccsidr_el1(ccsidr: u64, ccidx: bool) -> Result<Vec<FieldInfo>, DecodeError> {
+    let mut fields = if ccidx {
+        let res0a = FieldInfo::get(ccsidr, "RES0", Some("Reserved"), 56, 64).check_res0()?;
+        let num_sets = FieldInfo::get(ccsidr, "NumSets", Some("Number of sets"), 32, 56);
+        let res0b = FieldInfo::get(ccsidr, "RES0", Some("Reserved"), 24, 32).check_res0()?;
+        let associativity = FieldInfo::get(ccsidr, "Associativity", None, 3, 24);
+        vec![res0a, num_sets, res0b, associativity]
+    } else {
+        let res0 = FieldInfo::get(ccsidr, "RES0", Some("Reserved"), 32, 64).check_res0()?;
+        let unknown = FieldInfo::get(ccsidr, "UNKNOWN", None, 28, 32);
+        let num_sets = FieldInfo::get(ccsidr, "NumSets", Some("Number of sets"), 13, 28);
+        let associativity = FieldInfo::get(ccsidr, "Associativity", None, 3, 13);
+        vec![res0, unknown, num_sets, associativity]
+    };
+    for field in &mut fields {
+        match field.name {
+            "NumSets" => field.description = Some(format!("{} sets", field.value + 1)),
+            "Associativity" => field.description = Some(format!("{}-way", field.value + 1)),
+            _ => {}
+        }
+    }
+    let line_size = FieldInfo::get(ccsidr, "LineSize", Some("Cache line size"), 0, 3);
+    let bytes = 16 << line_size.value;
+    fields.push(
+        line_size
+            .with_description(format!("{} bytes", bytes))
+            .with_derived(bytes, Unit::Bytes),
+    );
+
+    Ok(fields)
+}
+
+const CTYPE_NAMES: [&str; 7] = [
+    "Ctype1", "Ctype2", "Ctype3", "Ctype4", "Ctype5", "Ctype6", "Ctype7",
+];
+
+const TTYPE_NAMES: [&str; 7] = [
+    "Ttype1", "Ttype2", "Ttype3", "Ttype4", "Ttype5", "Ttype6", "Ttype7",
+];
+
+/// Attaches a description of the cache level to the given field, where 0 means that no levels of
+/// cache need maintenance.
+fn with_cache_level(field: FieldInfo) -> FieldInfo {
+    let description = if field.value == 0 {
+        "No cache levels".to_string()
+    } else {
+        format!("Level {}", field.value)
+    };
+    field.with_description(description)
+}
+
+fn describe_ctype(ctype: u64) -> Result<&'static str, DecodeError> {
+    Ok(match ctype {
+        0b000 => "No cache",
+        0b001 => "Instruction cache only",
+        0b010 => "Data cache only",
+        0b011 => "Separate instruction and data caches",
+        0b100 => "Unified cache",
+        _ => "Reserved",
+    })
+}
+
+fn describe_ttype(ttype: u64) -> Result<&'static str, DecodeError> {
+    Ok(match ttype {
+        0b00 => "No Tag cache",
+        0b01 => "Separate Allocation Tag cache",
+        0b10 => "Unified Allocation Tag and data cache, with Tags and data in unified lines",
+        0b11 => "Unified Allocation Tag and data cache, with Tags and data in separate lines",
+        _ => unreachable!(),
+    })
+}
+
+/// Attaches the size in bytes to the given field, which is the log2 of a number of 4-byte words.
+fn with_line_size(field: FieldInfo) -> FieldInfo {
+    let bytes = 4 << field.value;
+    field
+        .with_description(format!("{} bytes", bytes))
+        .with_derived(bytes, Unit::Bytes)
+}
+
+/// Attaches the size in bytes to the given field, which is the log2 of a number of 4-byte words, or
+/// 0 if the size isn't provided.
+fn with_optional_size(field: FieldInfo) -> FieldInfo {
+    if field.value == 0 {
+        field.with_description("Not provided".to_string())
+    } else {
+        with_line_size(field)
+    }
+}
+
+fn describe_dic(dic: bool) -> &'static str {
+    if dic {
+        "Instruction cache invalidation to the Point of Unification is not required"
+    } else {
+        "Instruction cache invalidation to the Point of Unification is required"
+    }
+}
+
+fn describe_idc(idc: bool) -> &'static str {
+    if idc {
+        "Data cache clean to the Point of Unification is not required"
+    } else {
+        "Data cache clean to the Point of Unification is required"
+    }
+}
+
+fn describe_l1ip(l1ip: u64) -> Result<&'static str, DecodeError> {
+    Ok(match l1ip {
+        0b00 => "VMID aware Physical Index, Physical tag (VPIPT)",
+        0b01 => "ASID-tagged Virtual Index, Virtual Tag (AIVIVT)",
+        0b10 => "Virtual Index, Physical Tag (VIPT)",
+        0b11 => "Physical Index, Physical Tag (PIPT)",
+        _ => unreachable!(),
+    })
+}
+
+fn describe_dzp(dzp: bool) -> &'static str {
+    if dzp {
+        "DC ZVA, DC GVA and DC GZVA are prohibited"
+    } else {
+        "DC ZVA, DC GVA and DC GZVA are permitted"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derived;
+
+    #[test]
+    fn ctr() {
+        // Neoverse N1.
+        let decoded = decode_ctr_el0(0x8444_c004).unwrap();
+        assert_eq!(decoded.len(), 12);
+        assert_eq!(decoded[8].name, "DminLine");
+        assert_eq!(decoded[8].description, Some("64 bytes".to_string()));
+        assert_eq!(
+            decoded[8].derived,
+            Some(Derived {
+                value: 64,
+                unit: Unit::Bytes
+            })
+        );
+        assert_eq!(decoded[11].name, "IminLine");
+        assert_eq!(decoded[11].description, Some("64 bytes".to_string()));
+        assert_eq!(decoded[6].name, "CWG");
+        assert_eq!(decoded[6].description, Some("64 bytes".to_string()));
+        assert_eq!(
+            decoded[9].description,
+            Some("Physical Index, Physical Tag (PIPT)".to_string())
+        );
+        assert_eq!(decoded[5].value, 0);
+        // CWG and ERG not provided.
+        let decoded = decode_ctr_el0(0x8000_c004).unwrap();
+        assert_eq!(decoded[7].description, Some("Not provided".to_string()));
+        assert_eq!(decoded[7].derived, None);
+    }
+
+    #[test]
+    fn dczid() {
+        let decoded = decode_dczid_el0(0x4).unwrap();
+        assert_eq!(decoded[2].description, Some("64 bytes".to_string()));
+        assert_eq!(
+            decoded[1].description,
+            Some("DC ZVA, DC GVA and DC GZVA are permitted".to_string())
+        );
+        assert!(matches!(
+            decode_dczid_el0(0x20),
+            Err(DecodeError::InvalidRes0 { res0: 1 })
+        ));
+    }
+
+    #[test]
+    fn clidr() {
+        // Separate L1 caches, unified L2 and L3.
+        let decoded = decode_clidr_el1(0x8200_0123).unwrap();
+        assert_eq!(decoded.len(), 19);
+        assert_eq!(decoded[18].name, "Ctype1");
+        assert_eq!(
+            decoded[18].description,
+            Some("Separate instruction and data caches".to_string())
+        );
+        assert_eq!(decoded[17].name, "Ctype2");
+        assert_eq!(decoded[17].description, Some("Unified cache".to_string()));
+        assert_eq!(decoded[10].name, "LoC");
+        assert_eq!(decoded[10].description, Some("Level 2".to_string()));
+        assert_eq!(decoded[9].name, "LoUU");
+        assert_eq!(decoded[9].description, Some("No cache levels".to_string()));
+        assert_eq!(decoded[8].name, "ICB");
+        assert_eq!(
+            decoded[8].description,
+            Some("Level 2 is the highest Inner Cacheable level".to_string())
+        );
+    }
+
+    #[test]
+    fn ccsidr() {
+        // 64KB 4-way L1 data cache with 64-byte lines.
+        let decoded = decode_ccsidr_el1(0x701f_e01a, false).unwrap();
+        assert_eq!(decoded[2].description, Some("256 sets".to_string()));
+        assert_eq!(decoded[3].description, Some("4-way".to_string()));
+        assert_eq!(decoded[4].description, Some("64 bytes".to_string()));
+        // The same cache with FEAT_CCIDX.
+        let decoded = decode_ccsidr_el1(0x0000_00ff_0000_001a, true).unwrap();
+        assert_eq!(decoded[1].description, Some("256 sets".to_string()));
+        assert_eq!(decoded[3].description, Some("4-way".to_string()));
+        assert_eq!(
+            decoded[4].derived,
+            Some(Derived {
+                value: 64,
+                unit: Unit::Bytes
+            })
+        );
+        assert!(matches!(
+            decode_ccsidr_el1(0x0000_00ff_0000_001a, false),
+            Err(DecodeError::InvalidRes0 { .. })
+        ));
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoders for the registers which control trapping of accesses to floating-point, SVE, SME,
+//! trace and activity monitor functionality.
+
+use super::{DecodeError, FieldInfo};
+
+/// Decodes the given Architectural Feature Access Control Register value, or returns an error if
+/// it is not valid.
+pub fn decode_cpacr_el1(cpacr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(cpacr, "RES0", Some("Reserved"), 30, 64).check_res0()?;
+    let mut fields = vec![res0];
+    fields.extend(decode_cpacr_layout(cpacr, "EL1")?);
+    Ok(fields)
+}
+
+/// Decodes the given Architectural Feature Trap Register (EL2) value, or returns an error if it is
+/// not valid.
+///
+/// When HCR_EL2.E2H is 1, CPTR_EL2 has the same layout as CPACR_EL1, with TCPAC and TAM added.
+pub fn decode_cptr_el2(cptr: u64, e2h: bool) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(cptr, "RES0", Some("Reserved"), 32, 64).check_res0()?;
+    let tcpac = FieldInfo::get_bit(cptr, "TCPAC", Some("Traps CPACR_EL1 accesses"), 31)
+        .describe_bit(describe_tcpac);
+    let tam = FieldInfo::get_bit(cptr, "TAM", Some("Trap Activity Monitor access"), 30)
+        .describe_bit(describe_tam);
+    let mut fields = vec![res0, tcpac, tam];
+    if e2h {
+        fields.extend(decode_cpacr_layout(cptr, "EL2")?);
+    } else {
+        let res0a = FieldInfo::get(cptr, "RES0", Some("Reserved"), 21, 30).check_res0()?;
+        let tta = FieldInfo::get_bit(cptr, "TTA", Some("Trap Trace Access"), 20)
+            .describe_bit(describe_tta);
+        let res0b = FieldInfo::get(cptr, "RES0", Some("Reserved"), 14, 20).check_res0()?;
+        let res1a = FieldInfo::get_bit(cptr, "RES1", Some("Reserved"), 13);
+        let tsm = FieldInfo::get_bit(cptr, "TSM", Some("Trap SME"), 12).describe_bit(describe_trap);
+        let res0c = FieldInfo::get_bit(cptr, "RES0", Some("Reserved"), 11).check_res0()?;
+        let tfp = FieldInfo::get_bit(
+            cptr,
+            "TFP",
+            Some("Trap floating-point and Advanced SIMD"),
+            10,
+        )
+        .describe_bit(describe_trap);
+        let res1b = FieldInfo::get_bit(cptr, "RES1", Some("Reserved"), 9);
+        let tz = FieldInfo::get_bit(cptr, "TZ", Some("Trap SVE"), 8).describe_bit(describe_trap);
+        let res1c = FieldInfo::get(cptr, "RES1", Some("Reserved"), 0, 8);
+        fields.extend([res0a, tta, res0b, res1a, tsm, res0c, tfp, res1b, tz, res1c]);
+    }
+    Ok(fields)
+}
+
+/// Decodes the given Architectural Feature Trap Register (EL3) value, or returns an error if it is
+/// not valid.
+pub fn decode_cptr_el3(cptr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0a = FieldInfo::get(cptr, "RES0", Some("Reserved"), 32, 64).check_res0()?;
+    let tcpac = FieldInfo::get_bit(
+        cptr,
+        "TCPAC",
+        Some("Traps CPTR_EL2 and CPACR_EL1 accesses"),
+        31,
+    )
+    .describe_bit(describe_tcpac);
+    let tam = FieldInfo::get_bit(cptr, "TAM", Some("Trap Activity Monitor access"), 30)
+        .describe_bit(describe_tam);
+    let res0b = FieldInfo::get(cptr, "RES0", Some("Reserved"), 21, 30).check_res0()?;
+    let tta =
+        FieldInfo::get_bit(cptr, "TTA", Some("Trap Trace Access"), 20).describe_bit(describe_tta);
+    let res0c = FieldInfo::get(cptr, "RES0", Some("Reserved"), 13, 20).check_res0()?;
+    let esm = FieldInfo::get_bit(cptr, "ESM", Some("Enable SME"), 12).describe_bit(describe_enable);
+    let res0d = FieldInfo::get_bit(cptr, "RES0", Some("Reserved"), 11).check_res0()?;
+    let tfp = FieldInfo::get_bit(
+        cptr,
+        "TFP",
+        Some("Trap floating-point and Advanced SIMD"),
+        10,
+    )
+    .describe_bit(describe_trap);
+    let res0e = FieldInfo::get_bit(cptr, "RES0", Some("Reserved"), 9).check_res0()?;
+    let ez = FieldInfo::get_bit(cptr, "EZ", Some("Enable SVE"), 8).describe_bit(describe_enable);
+    let res0f = FieldInfo::get(cptr, "RES0", Some("Reserved"), 0, 8).check_res0()?;
+
+    Ok(vec![
+        res0a, tcpac, tam, res0b, tta, res0c, esm, res0d, tfp, res0e, ez, res0f,
+    ])
+}
+
+/// Decodes bits 0 to 29 of CPACR_EL1, or of CPTR_EL2 when HCR_EL2.E2H is 1, where the given
+/// Exception level is the one which the register controls accesses from along with EL0.
+fn decode_cpacr_layout(value: u64, el: &str) -> Result<Vec<FieldInfo>, DecodeError> {
+    let e0poe = FieldInfo::get_bit(value, "E0POE", Some("Enable EL0 Permission Overlays"), 29);
+    let tta =
+        FieldInfo::get_bit(value, "TTA", Some("Trap Trace Access"), 28).describe_bit(describe_tta);
+    let res0a = FieldInfo::get(value, "RES0", Some("Reserved"), 26, 28).check_res0()?;
+    let smen = FieldInfo::get(value, "SMEN", Some("SME enable"), 24, 26);
+    let smen_description = describe_en(smen.value, el);
+    let smen = smen.with_description(smen_description);
+    let res0b = FieldInfo::get(value, "RES0", Some("Reserved"), 22, 24).check_res0()?;
+    let fpen = FieldInfo::get(
+        value,
+        "FPEN",
+        Some("Floating-point and Advanced SIMD enable"),
+        20,
+        22,
+    );
+    let fpen_description = describe_en(fpen.value, el);
+    let fpen = fpen.with_description(fpen_description);
+    let res0c = FieldInfo::get(value, "RES0", Some("Reserved"), 18, 20).check_res0()?;
+    let zen = FieldInfo::get(value, "ZEN", Some("SVE enable"), 16, 18);
+    let zen_description = describe_en(zen.value, el);
+    let zen = zen.with_description(zen_description);
+    let res0d = FieldInfo::get(value, "RES0", Some("Reserved"), 0, 16).check_res0()?;
+
+    Ok(vec![
+        e0poe, tta, res0a, smen, res0b, fpen, res0c, zen, res0d,
+    ])
+}
+
+/// Describes an FPEN, ZEN or SMEN field controlling accesses from EL0 and the given Exception
+/// level.
+fn describe_en(en: u64, el: &str) -> String {
+    match en {
+        0b01 if el == "EL2" => {
+            "EL0 accesses are trapped if HCR_EL2.TGE is 1, EL2 accesses are not trapped".to_string()
+        }
+        0b01 => format!("EL0 accesses are trapped, {} accesses are not trapped", el),
+        0b11 => "Accesses are not trapped".to_string(),
+        _ => format!("EL0 and {} accesses are trapped", el),
+    }
+}
+
+fn describe_tcpac(tcpac: bool) -> &'static str {
+    if tcpac {
+        "Accesses from lower Exception levels are trapped"
+    } else {
+        "Accesses are not trapped"
+    }
+}
+
+fn describe_tam(tam: bool) -> &'static str {
+    if tam {
+        "Accesses to Activity Monitor registers are trapped"
+    } else {
+        "Accesses to Activity Monitor registers are not trapped"
+    }
+}
+
+fn describe_tta(tta: bool) -> &'static str {
+    if tta {
+        "Accesses to trace registers are trapped"
+    } else {
+        "Accesses to trace registers are not trapped"
+    }
+}
+
+fn describe_trap(trap: bool) -> &'static str {
+    if trap {
+        "Accesses are trapped"
+    } else {
+        "Accesses are not trapped"
+    }
+}
+
+fn describe_enable(enable: bool) -> &'static str {
+    if enable {
+        "Accesses are not trapped"
+    } else {
+        "Accesses are trapped"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpacr_el1() {
+        // FP enabled, SVE enabled only at EL1.
+        let decoded = decode_cpacr_el1(0x0031_0000).unwrap();
+        assert_eq!(decoded.len(), 10);
+        assert_eq!(decoded[6].name, "FPEN");
+        assert_eq!(
+            decoded[6].description,
+            Some("Accesses are not trapped".to_string())
+        );
+        assert_eq!(decoded[8].name, "ZEN");
+        assert_eq!(
+            decoded[8].description,
+            Some("EL0 accesses are trapped, EL1 accesses are not trapped".to_string())
+        );
+        assert_eq!(
+            decoded[4].description,
+            Some("EL0 and EL1 accesses are trapped".to_string())
+        );
+    }
+
+    #[test]
+    fn cptr_el2() {
+        // Reset-like value with E2H 0, trapping SVE.
+        let decoded = decode_cptr_el2(0x33ff, false).unwrap();
+        assert_eq!(decoded.len(), 13);
+        let tz = decoded.iter().find(|field| field.name == "TZ").unwrap();
+        assert_eq!(tz.description, Some("Accesses are trapped".to_string()));
+        let tfp = decoded.iter().find(|field| field.name == "TFP").unwrap();
+        assert_eq!(
+            tfp.description,
+            Some("Accesses are not trapped".to_string())
+        );
+        // E2H 1.
+        let decoded = decode_cptr_el2(0x8010_0000, true).unwrap();
+        assert_eq!(decoded.len(), 12);
+        assert_eq!(decoded[1].name, "TCPAC");
+        assert_eq!(decoded[1].value, 1);
+        let fpen = decoded.iter().find(|field| field.name == "FPEN").unwrap();
+        assert_eq!(
+            fpen.description,
+            Some(
+                "EL0 accesses are trapped if HCR_EL2.TGE is 1, EL2 accesses are not trapped"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn cptr_el3() {
+        let decoded = decode_cptr_el3(0x1100).unwrap();
+        assert_eq!(decoded.len(), 12);
+        assert_eq!(decoded[6].name, "ESM");
+        assert_eq!(
+            decoded[6].description,
+            Some("Accesses are not trapped".to_string())
+        );
+        assert_eq!(decoded[10].name, "EZ");
+        assert_eq!(decoded[10].value, 1);
+        assert!(matches!(
+            decode_cptr_el3(0x1),
+            Err(DecodeError::InvalidRes0 { res0: 1 })
+        ));
+    }
+}
@@ -0,0 +1,422 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::par::describe_sh;
+use super::{DecodeError, FieldInfo};
+
+/// The granule size of a VMSAv8-64 translation regime.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TranslationGranule {
+    Size4KiB,
+    Size16KiB,
+    Size64KiB,
+}
+
+impl TranslationGranule {
+    /// Returns log2 of the granule size in bytes.
+    fn shift(self) -> usize {
+        match self {
+            Self::Size4KiB => 12,
+            Self::Size16KiB => 14,
+            Self::Size64KiB => 16,
+        }
+    }
+
+    /// Returns whether the given lookup level exists for the granule size.
+    fn has_level(self, level: u8) -> bool {
+        match self {
+            Self::Size4KiB | Self::Size16KiB => level <= 3,
+            Self::Size64KiB => (1..=3).contains(&level),
+        }
+    }
+
+    /// Returns whether a block descriptor is permitted at the given lookup level.
+    fn allows_block(self, level: u8) -> bool {
+        match self {
+            Self::Size4KiB => level == 1 || level == 2,
+            Self::Size16KiB | Self::Size64KiB => level == 2,
+        }
+    }
+}
+
+/// Decodes the given stage 1 VMSAv8-64 translation table descriptor, read from the given lookup
+/// level of a translation regime with the given granule size.
+///
+/// Output addresses are assumed to be 48 bits, so the bits used for the upper bits of 52-bit
+/// addresses with FEAT_LPA or FEAT_LPA2 are treated as RES0. For translation regimes with a single
+/// privilege level UXN is XN and PXN is RES0, and AP[1] is RES1.
+pub fn decode_descriptor(
+    descriptor: u64,
+    granule: TranslationGranule,
+    level: u8,
+) -> Result<Vec<FieldInfo>, DecodeError> {
+    if !granule.has_level(level) {
+        return Err(DecodeError::InvalidLevel {
+            level: level.into(),
+        });
+    }
+
+    let valid = FieldInfo::get_bit(descriptor, "Valid", None, 0).describe_bit(describe_valid);
+    if !valid.as_bit() {
+        let ignored = FieldInfo::get(descriptor, "IGNORED", Some("Ignored"), 1, 64);
+        return Ok(vec![ignored, valid]);
+    }
+
+    let descriptor_type = FieldInfo::get_bit(descriptor, "Type", Some("Descriptor type"), 1);
+    let (type_description, is_table) = match (descriptor_type.as_bit(), level) {
+        (true, 3) => ("Page descriptor".to_string(), false),
+        (true, _) => ("Table descriptor".to_string(), true),
+        (false, 3) => (
+            "Reserved, behaves as an invalid descriptor".to_string(),
+            false,
+        ),
+        (false, _) if granule.allows_block(level) => ("Block descriptor".to_string(), false),
+        (false, _) => (
+            format!(
+                "Block descriptor not permitted at level {}, behaves as an invalid descriptor",
+                level
+            ),
+            false,
+        ),
+    };
+    let descriptor_type = descriptor_type.with_description(type_description);
+
+    let mut fields = if is_table {
+        decode_table(descriptor, granule)?
+    } else if level == 3 || granule.allows_block(level) {
+        decode_leaf(descriptor, granule, level)?
+    } else {
+        vec![FieldInfo::get(
+            descriptor,
+            "IGNORED",
+            Some("Ignored"),
+            2,
+            64,
+        )]
+    };
+    fields.push(descriptor_type);
+    fields.push(valid);
+    Ok(fields)
+}
+
+/// Decodes bits 2 to 63 of a table descriptor.
+fn decode_table(
+    descriptor: u64,
+    granule: TranslationGranule,
+) -> Result<Vec<FieldInfo>, DecodeError> {
+    let ns_table =
+        FieldInfo::get_bit(descriptor, "NSTable", None, 63).describe_bit(describe_ns_table);
+    let ap_table = FieldInfo::get(
+        descriptor,
+        "APTable",
+        Some("Access permissions limit"),
+        61,
+        63,
+    )
+    .describe(describe_ap_table)?;
+    let uxn_table =
+        FieldInfo::get_bit(descriptor, "UXNTable", None, 60).describe_bit(describe_uxn_table);
+    let pxn_table =
+        FieldInfo::get_bit(descriptor, "PXNTable", None, 59).describe_bit(describe_pxn_table);
+    let ignored_high = FieldInfo::get(descriptor, "IGNORED", Some("Ignored"), 52, 59);
+    let res0_high = FieldInfo::get(descriptor, "RES0", Some("Reserved"), 48, 52).check_res0()?;
+    let shift = granule.shift();
+    let address = FieldInfo::get(
+        descriptor,
+        "NLTA",
+        Some("Next-level table address"),
+        shift,
+        48,
+    );
+    let address_description = format!("{:#x}", address.value << shift);
+    let address = address.with_description(address_description);
+    let mut fields = vec![
+        ns_table,
+        ap_table,
+        uxn_table,
+        pxn_table,
+        ignored_high,
+        res0_high,
+        address,
+    ];
+    if shift > 12 {
+        fields.push(FieldInfo::get(descriptor, "RES0", Some("Reserved"), 12, shift).check_res0()?);
+    }
+    fields.push(FieldInfo::get(
+        descriptor,
+        "IGNORED",
+        Some("Ignored"),
+        2,
+        12,
+    ));
+    Ok(fields)
+}
+
+/// Decodes bits 2 to 63 of a block or page descriptor.
+fn decode_leaf(
+    descriptor: u64,
+    granule: TranslationGranule,
+    level: u8,
+) -> Result<Vec<FieldInfo>, DecodeError> {
+    let ignored = FieldInfo::get_bit(descriptor, "IGNORED", Some("Ignored"), 63);
+    let pbha = FieldInfo::get(
+        descriptor,
+        "PBHA",
+        Some("Page-Based Hardware Attributes"),
+        59,
+        63,
+    );
+    let software = FieldInfo::get(descriptor, "SW", Some("Reserved for software use"), 55, 59);
+    let uxn = FieldInfo::get_bit(descriptor, "UXN", Some("Unprivileged execute-never"), 54)
+        .describe_bit(describe_uxn);
+    let pxn = FieldInfo::get_bit(descriptor, "PXN", Some("Privileged execute-never"), 53)
+        .describe_bit(describe_pxn);
+    let contiguous =
+        FieldInfo::get_bit(descriptor, "Contiguous", None, 52).describe_bit(describe_contiguous);
+    let dbm = FieldInfo::get_bit(descriptor, "DBM", Some("Dirty Bit Modifier"), 51)
+        .describe_bit(describe_dbm);
+    let gp =
+        FieldInfo::get_bit(descriptor, "GP", Some("Guarded Page"), 50).describe_bit(describe_gp);
+    let res0_high = FieldInfo::get(descriptor, "RES0", Some("Reserved"), 48, 50).check_res0()?;
+    let shift = granule.shift() + usize::from(3 - level) * (granule.shift() - 3);
+    let address = FieldInfo::get(descriptor, "OA", Some("Output address"), shift, 48);
+    let address_description = format!("{:#x}", address.value << shift);
+    let address = address.with_description(address_description);
+    let mut fields = vec![
+        ignored, pbha, software, uxn, pxn, contiguous, dbm, gp, res0_high, address,
+    ];
+    if level == 3 {
+        if shift > 12 {
+            fields.push(
+                FieldInfo::get(descriptor, "RES0", Some("Reserved"), 12, shift).check_res0()?,
+            );
+        }
+    } else {
+        fields.push(FieldInfo::get(descriptor, "RES0", Some("Reserved"), 17, shift).check_res0()?);
+        fields.push(
+            FieldInfo::get_bit(descriptor, "nT", Some("Not translated"), 16)
+                .describe_bit(describe_nt),
+        );
+        fields.push(FieldInfo::get(descriptor, "RES0", Some("Reserved"), 12, 16).check_res0()?);
+    }
+
+    let ng = FieldInfo::get_bit(descriptor, "nG", Some("Not global"), 11).describe_bit(describe_ng);
+    let af =
+        FieldInfo::get_bit(descriptor, "AF", Some("Access Flag"), 10).describe_bit(describe_af);
+    let sh = FieldInfo::get(descriptor, "SH", Some("Shareability"), 8, 10).describe(describe_sh)?;
+    let ap =
+        FieldInfo::get(descriptor, "AP", Some("Access Permissions"), 6, 8).describe(describe_ap)?;
+    let ns = FieldInfo::get_bit(descriptor, "NS", Some("Non-secure"), 5).describe_bit(describe_ns);
+    let attr_index = FieldInfo::get(descriptor, "AttrIndx", Some("Attribute index"), 2, 5);
+    let attr_index_description = format!("MAIR_ELx.Attr{}", attr_index.value);
+    let attr_index = attr_index.with_description(attr_index_description);
+    fields.extend([ng, af, sh, ap, ns, attr_index]);
+    Ok(fields)
+}
+
+fn describe_valid(valid: bool) -> &'static str {
+    if valid {
+        "Valid descriptor"
+    } else {
+        "Invalid descriptor, generates a Translation fault"
+    }
+}
+
+fn describe_ns_table(ns_table: bool) -> &'static str {
+    if ns_table {
+        "Next-level table is Non-secure, for accesses from Secure state"
+    } else {
+        "Next-level table security not restricted"
+    }
+}
+
+fn describe_ap_table(ap_table: u64) -> Result<&'static str, DecodeError> {
+    Ok(match ap_table {
+        0b00 => "No effect on permissions at subsequent levels",
+        0b01 => "Unprivileged access not permitted at subsequent levels",
+        0b10 => "Write access not permitted at subsequent levels",
+        0b11 => "Write and unprivileged access not permitted at subsequent levels",
+        _ => unreachable!(),
+    })
+}
+
+fn describe_uxn_table(uxn_table: bool) -> &'static str {
+    if uxn_table {
+        "Unprivileged execution not permitted at subsequent levels"
+    } else {
+        "No effect on unprivileged execution at subsequent levels"
+    }
+}
+
+fn describe_pxn_table(pxn_table: bool) -> &'static str {
+    if pxn_table {
+        "Privileged execution not permitted at subsequent levels"
+    } else {
+        "No effect on privileged execution at subsequent levels"
+    }
+}
+
+fn describe_uxn(uxn: bool) -> &'static str {
+    if uxn {
+        "Unprivileged execution not permitted"
+    } else {
+        "Unprivileged execution permitted, subject to other controls"
+    }
+}
+
+fn describe_pxn(pxn: bool) -> &'static str {
+    if pxn {
+        "Privileged execution not permitted"
+    } else {
+        "Privileged execution permitted, subject to other controls"
+    }
+}
+
+fn describe_contiguous(contiguous: bool) -> &'static str {
+    if contiguous {
+        "One of a contiguous set of entries which may be cached in a single TLB entry"
+    } else {
+        "Not part of a contiguous set of entries"
+    }
+}
+
+fn describe_dbm(dbm: bool) -> &'static str {
+    if dbm {
+        "Writable, the hardware may clear AP[2] on a write if enabled by TCR_ELx.HD"
+    } else {
+        "Dirty state not managed by hardware"
+    }
+}
+
+fn describe_gp(gp: bool) -> &'static str {
+    if gp {
+        "Guarded page for Branch Target Identification"
+    } else {
+        "Not a guarded page"
+    }
+}
+
+fn describe_nt(nt: bool) -> &'static str {
+    if nt {
+        "Not cached in TLBs, for changing the block size with FEAT_BBM"
+    } else {
+        "May be cached in TLBs"
+    }
+}
+
+fn describe_ng(ng: bool) -> &'static str {
+    if ng {
+        "Not global, only valid for the current ASID"
+    } else {
+        "Global, valid for all ASIDs"
+    }
+}
+
+fn describe_af(af: bool) -> &'static str {
+    if af {
+        "Accessed"
+    } else {
+        "Not accessed, so an access generates an Access flag fault unless the hardware updates it"
+    }
+}
+
+fn describe_ap(ap: u64) -> Result<&'static str, DecodeError> {
+    Ok(match ap {
+        0b00 => "Privileged read/write, no unprivileged access",
+        0b01 => "Read/write at all privilege levels",
+        0b10 => "Privileged read-only, no unprivileged access",
+        0b11 => "Read-only at all privilege levels",
+        _ => unreachable!(),
+    })
+}
+
+fn describe_ns(ns: bool) -> &'static str {
+    if ns {
+        "Output address is Non-secure, for accesses from Secure state"
+    } else {
+        "Output address is Secure, for accesses from Secure state"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page() {
+        let decoded =
+            decode_descriptor(0x0060_0000_8123_4f43, TranslationGranule::Size4KiB, 3).unwrap();
+        let describe = |name: &str| {
+            decoded
+                .iter()
+                .find(|field| field.name == name)
+                .unwrap()
+                .description
+                .clone()
+                .unwrap()
+        };
+        assert_eq!(describe("Type"), "Page descriptor");
+        assert_eq!(describe("OA"), "0x81234000");
+        assert_eq!(describe("UXN"), "Unprivileged execution not permitted");
+        assert_eq!(describe("PXN"), "Privileged execution not permitted");
+        assert_eq!(describe("SH"), "Inner Shareable");
+        assert_eq!(describe("AP"), "Read/write at all privilege levels");
+        assert_eq!(describe("AttrIndx"), "MAIR_ELx.Attr0");
+        assert_eq!(describe("AF"), "Accessed");
+    }
+
+    #[test]
+    fn block_and_table() {
+        let block = decode_descriptor(0x4020_0701, TranslationGranule::Size4KiB, 2).unwrap();
+        assert_eq!(
+            block[block.len() - 2].description.as_deref(),
+            Some("Block descriptor")
+        );
+        assert!(block
+            .iter()
+            .any(|field| field.name == "OA" && field.description.as_deref() == Some("0x40200000")));
+
+        let table =
+            decode_descriptor(0x8000_0000_4001_0003, TranslationGranule::Size64KiB, 1).unwrap();
+        assert_eq!(table[0].name, "NSTable");
+        assert!(table[0].as_bit());
+        assert!(table.iter().any(
+            |field| field.name == "NLTA" && field.description.as_deref() == Some("0x40010000")
+        ));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            decode_descriptor(0x1234, TranslationGranule::Size4KiB, 3).unwrap()[1]
+                .description
+                .as_deref(),
+            Some("Invalid descriptor, generates a Translation fault")
+        );
+        assert_eq!(
+            decode_descriptor(0x1, TranslationGranule::Size64KiB, 1).unwrap()[1]
+                .description
+                .as_deref(),
+            Some("Block descriptor not permitted at level 1, behaves as an invalid descriptor")
+        );
+        assert!(matches!(
+            decode_descriptor(0x3, TranslationGranule::Size64KiB, 0),
+            Err(DecodeError::InvalidLevel { level: 0 })
+        ));
+        assert!(matches!(
+            decode_descriptor(0x0001_0000_0000_0003, TranslationGranule::Size4KiB, 0),
+            Err(DecodeError::InvalidRes0 { .. })
+        ));
+    }
+}
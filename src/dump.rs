@@ -0,0 +1,324 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing and analysis of register dumps.
+
+use crate::esr::far_valid;
+use crate::instrument::Stage;
+use crate::{
+    decode_ccsidr_el1, decode_cptr_el2, decode_midr, decode_sctlr_el2, decode_ttbr,
+    decode_with_options, id_register_features, midr_core_name, parse_number, register_decoder,
+    DecodeError, DecodeOptions, RegisterDecoder, REGISTER_DECODERS,
+};
+use crate::{ExecutionState, FieldInfo};
+use thiserror::Error;
+
+/// An error parsing a register dump.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum DumpError {
+    /// A line had a register name but the value could not be parsed.
+    #[error("Invalid value {value:?} for {name} on line {line}")]
+    InvalidValue {
+        line: usize,
+        name: String,
+        value: String,
+    },
+}
+
+/// Parses a register dump consisting of lines of the form `NAME=VALUE`.
+///
+/// Register names are converted to upper case. Values are parsed with [`parse_number`], so must
+/// have a `0x` prefix if they are hexadecimal. Lines without an `=` are ignored, as are lines
+/// starting with `#`.
+pub fn parse_register_dump(dump: &str) -> Result<Vec<(String, u64)>, DumpError> {
+    let _stage = Stage::enter("parse dump");
+    let mut registers = vec![];
+    for (index, line) in dump.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            trace!(line = index + 1, "Skipped comment");
+            continue;
+        }
+        if let Some((name, value)) = line.split_once('=') {
+            let name = name.trim().to_ascii_uppercase();
+            let value = value.trim();
+            let value = parse_number(value).map_err(|_| {
+                debug!(line = index + 1, %name, value, "Invalid register value");
+                DumpError::InvalidValue {
+                    line: index + 1,
+                    name: name.clone(),
+                    value: value.to_owned(),
+                }
+            })?;
+            debug!(line = index + 1, %name, value = %format_args!("{:#x}", value), "Found register");
+            registers.push((name, value));
+        } else {
+            trace!(line = index + 1, "Skipped line without '='");
+        }
+    }
+    Ok(registers)
+}
+
+/// A register from a dump, and its decoded value if it is a register which this crate knows how to
+/// decode.
+#[derive(Debug)]
+pub struct DumpedRegister {
+    /// The name of the register, in upper case.
+    pub name: String,
+    /// The raw value of the register.
+    pub value: u64,
+    /// The decoded fields of the register, or `None` if it is not a register which can be decoded.
+    pub decoded: Option<Result<Vec<FieldInfo>, DecodeError>>,
+}
+
+/// The result of analysing a register dump.
+#[derive(Debug)]
+pub struct DumpReport {
+    /// All the registers in the dump, in the order they appeared.
+    pub registers: Vec<DumpedRegister>,
+    /// Notes from analysing registers in combination with each other.
+    pub notes: Vec<String>,
+}
+
+/// Decodes all the known registers in the given register dump, and analyses how they relate to
+/// each other.
+pub fn analyze_register_dump(registers: &[(String, u64)]) -> DumpReport {
+    let _stage = Stage::enter("analyze dump");
+    let get = |name: &str| {
+        registers
+            .iter()
+            .find(|(register, _)| register == name)
+            .map(|&(_, value)| value)
+    };
+
+    let e2h = get("HCR_EL2").is_some_and(|hcr| hcr & 1 << 34 != 0);
+    let mut notes = vec![];
+    let registers = registers
+        .iter()
+        .map(|(name, value)| {
+            let (base, suffix) = split_el_suffix(name);
+            let decoded = match base {
+                "ESR" => {
+                    let spsr_name = format!("SPSR{}", suffix);
+                    let execution_state = get(&spsr_name).map(ExecutionState::from_spsr);
+                    if execution_state == Some(ExecutionState::AArch32) {
+                        notes.push(format!(
+                            "{} is for an exception taken from AArch32 state, according to {}.",
+                            name, spsr_name
+                        ));
+                    }
+                    let decoded = decode_with_options(
+                        *value,
+                        &DecodeOptions {
+                            execution_state,
+                            ..Default::default()
+                        },
+                    );
+                    if let Ok(fields) = &decoded {
+                        notes.extend(far_note(fields, suffix, get(&format!("FAR{}", suffix))));
+                    }
+                    Some(decoded)
+                }
+                "MIDR" => {
+                    if let Some(core) = midr_core_name(*value) {
+                        notes.push(format!("{} identifies the core as {}.", name, core));
+                    }
+                    Some(decode_midr(*value))
+                }
+                "CCSIDR" if suffix == "_EL1" => {
+                    // The layout depends on whether ID_AA64MMFR2_EL1.CCIDX indicates FEAT_CCIDX.
+                    let ccidx = get("ID_AA64MMFR2_EL1").is_some_and(|mmfr2| mmfr2 >> 20 & 0xf != 0);
+                    Some(decode_ccsidr_el1(*value, ccidx))
+                }
+                "CPTR" if suffix == "_EL2" => Some(decode_cptr_el2(*value, e2h)),
+                "TTBR0" | "TTBR1" if suffix == "_EL1" || suffix == "_EL2" => {
+                    Some(decode_ttbr(*value, get(&format!("TCR{}", suffix))))
+                }
+                "SCTLR" if suffix == "_EL2" => {
+                    // SCTLR_EL2 has the same layout as SCTLR_EL1 when E2H is set, which isn't
+                    // decoded.
+                    if e2h {
+                        notes.push(format!(
+                            "{} is not decoded, as HCR_EL2.E2H is set so it has the layout of \
+                             SCTLR_EL1.",
+                            name
+                        ));
+                        None
+                    } else {
+                        Some(decode_sctlr_el2(*value))
+                    }
+                }
+                // Other registers don't depend on the rest of the dump.
+                _ => find_decoder(base, suffix).map(|decoder| (decoder.decode)(*value)),
+            };
+            if let Some(features) = id_register_features(name, *value) {
+                if !features.is_empty() {
+                    notes.push(format!("{} indicates {}.", name, features.join(", ")));
+                }
+            }
+            if decoded.is_none() {
+                debug!(%name, "Not decoding unknown register");
+            }
+            DumpedRegister {
+                name: name.clone(),
+                value: *value,
+                decoded,
+            }
+        })
+        .collect();
+
+    DumpReport { registers, notes }
+}
+
+/// Splits a register name like `ESR_EL1` into the base name and Exception level suffix, e.g.
+/// `("ESR", "_EL1")`.
+fn split_el_suffix(name: &str) -> (&str, &str) {
+    match name.rfind("_EL") {
+        Some(index) => name.split_at(index),
+        None => (name, ""),
+    }
+}
+
+/// Finds the decoder for the register with the given base name and Exception level suffix.
+///
+/// As well as the exact name, this accepts the `_EL12` and `_EL02` aliases used to access EL1 and
+/// EL0 registers from EL2 when HCR_EL2.E2H is set, and names without a suffix for registers which
+/// only exist at one Exception level, such as `PAR` for PAR_EL1.
+fn find_decoder(base: &str, suffix: &str) -> Option<&'static RegisterDecoder> {
+    match suffix {
+        "" => {
+            let mut decoders = REGISTER_DECODERS
+                .iter()
+                .filter(|decoder| split_el_suffix(decoder.name).0 == base);
+            match (decoders.next(), decoders.next()) {
+                (Some(decoder), None) => Some(decoder),
+                _ => None,
+            }
+        }
+        "_EL12" => register_decoder(&format!("{}_EL1", base)),
+        "_EL02" => register_decoder(&format!("{}_EL0", base)),
+        _ => register_decoder(&format!("{}{}", base, suffix)),
+    }
+}
+
+/// Returns a note about whether the FAR for the given decoded ESR is valid, if it was included in
+/// the dump.
+fn far_note(esr: &[FieldInfo], suffix: &str, far: Option<u64>) -> Option<String> {
+    let far = far?;
+    Some(match far_valid(esr) {
+        Some(true) => format!(
+            "FAR{} {:#018x} is the faulting virtual address.",
+            suffix, far
+        ),
+        Some(false) => format!("FAR{} is not valid, as ESR{}.FnV is set.", suffix, suffix),
+        None => format!(
+            "FAR{} is UNKNOWN for this exception class, so its value is not meaningful.",
+            suffix
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            parse_register_dump("# Comment\nesr_el1 = 0x96000045\nFAR_EL1=4096\n\njunk\n"),
+            Ok(vec![
+                ("ESR_EL1".to_string(), 0x96000045),
+                ("FAR_EL1".to_string(), 4096)
+            ])
+        );
+        assert_eq!(
+            parse_register_dump("ESR_EL1=0x96000045\nFAR_EL1=zzz"),
+            Err(DumpError::InvalidValue {
+                line: 2,
+                name: "FAR_EL1".to_string(),
+                value: "zzz".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn analyze() {
+        let report = analyze_register_dump(&[
+            ("ESR_EL1".to_string(), 0x92000047),
+            ("FAR_EL1".to_string(), 0x1234),
+            ("SPSR_EL1".to_string(), 0x10),
+            ("X0".to_string(), 42),
+            ("MIDR_EL1".to_string(), 0x413fd0c1),
+            ("SCTLR_EL3".to_string(), 0x30c50830),
+            ("ID_AA64MMFR1_EL1".to_string(), 0x100),
+        ]);
+        assert_eq!(report.registers.len(), 7);
+        assert!(report.registers[5].decoded.is_some());
+        assert!(report.registers[6].decoded.is_some());
+        assert!(report.registers[0].decoded.is_some());
+        assert!(report.registers[3].decoded.is_none());
+        assert_eq!(
+            report.notes,
+            vec![
+                "ESR_EL1 is for an exception taken from AArch32 state, according to SPSR_EL1."
+                    .to_string(),
+                "FAR_EL1 0x0000000000001234 is the faulting virtual address.".to_string(),
+                "MIDR_EL1 identifies the core as Arm Neoverse N1 r3p1.".to_string(),
+                "ID_AA64MMFR1_EL1 indicates FEAT_VHE.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        let report = analyze_register_dump(&[
+            ("ID_AA64MMFR0".to_string(), 0x5),
+            ("PAR".to_string(), 0x800),
+            ("ZCR_EL12".to_string(), 0x3),
+            ("TFSR_EL12".to_string(), 0x1),
+            ("DFSR".to_string(), 0x5),
+            ("ZCR".to_string(), 0x3),
+            ("SCTLR_EL12".to_string(), 0),
+        ]);
+        let decoded = report
+            .registers
+            .iter()
+            .map(|register| register.decoded.is_some())
+            .collect::<Vec<_>>();
+        assert_eq!(decoded, vec![true, true, true, true, true, false, false]);
+        #[cfg(feature = "profiling")]
+        assert!(
+            analyze_register_dump(&[("TRBSR".to_string(), 0)]).registers[0]
+                .decoded
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn sctlr_vhe() {
+        // SCTLR_EL2 isn't decoded for a VHE host, but the reason is noted.
+        let report = analyze_register_dump(&[
+            ("HCR_EL2".to_string(), 1 << 34),
+            ("SCTLR_EL2".to_string(), 0x30c50830),
+        ]);
+        assert!(report.registers[1].decoded.is_none());
+        assert_eq!(
+            report.notes,
+            vec![
+                "SCTLR_EL2 is not decoded, as HCR_EL2.E2H is set so it has the layout of SCTLR_EL1."
+                    .to_string()
+            ]
+        );
+    }
+}
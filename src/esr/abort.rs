@@ -12,19 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{DecodeError, FieldInfo};
+use super::common::register_name;
+use super::ExecutionState;
+use crate::{DecodeError, FieldInfo, Unit};
 use std::fmt::{self, Debug, Display, Formatter};
 
 /// Decodes the ISS value for an Instruction Abort.
+///
+/// For fault status codes which encode a translation table lookup level, the IFSC field has the
+/// level as its derived value, with [`Unit::Level`].
 pub fn decode_iss_instruction_abort(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     let res0a = FieldInfo::get(iss, "RES0", Some("Reserved"), 13, 25).check_res0()?;
     let fnv = FieldInfo::get_bit(iss, "FnV", Some("FAR not Valid"), 10).describe_bit(describe_fnv);
-    let ea = FieldInfo::get_bit(iss, "EA", Some("External abort type"), 9);
+    let ea = FieldInfo::get_bit(iss, "EA", Some("External abort type"), 9)
+        .with_description("IMPLEMENTATION DEFINED classification of external aborts".to_string());
     let res0b = FieldInfo::get_bit(iss, "RES0", Some("Reserved"), 8).check_res0()?;
-    let s1ptw = FieldInfo::get_bit(iss, "S1PTW", Some("Stage-1 translation table walk"), 7);
+    let s1ptw = FieldInfo::get_bit(iss, "S1PTW", Some("Stage-1 translation table walk"), 7)
+        .describe_bit(describe_s1ptw);
     let res0c = FieldInfo::get_bit(iss, "RES0", Some("Reserved"), 6).check_res0()?;
-    let ifsc = FieldInfo::get(iss, "IFSC", Some("Instruction Fault Status Code"), 0, 6)
-        .describe(describe_fsc)?;
+    let ifsc = with_stage2_note(
+        with_fsc_level(
+            FieldInfo::get(iss, "IFSC", Some("Instruction Fault Status Code"), 0, 6)
+                .describe(describe_fsc)?,
+        ),
+        s1ptw.as_bit(),
+    );
+    let fnv = with_fnv_note(fnv, ifsc.value);
 
     let set = if ifsc.value == 0b010000 {
         FieldInfo::get(iss, "SET", Some("Synchronous Error Type"), 11, 13).describe(describe_set)?
@@ -35,8 +48,16 @@ pub fn decode_iss_instruction_abort(iss: u64) -> Result<Vec<FieldInfo>, DecodeEr
     Ok(vec![res0a, set, fnv, ea, res0b, s1ptw, res0c, ifsc])
 }
 
-/// Decodes the ISS value for a Data Abort.
-pub fn decode_iss_data_abort(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+/// Decodes the ISS value for a Data Abort, and summarises the access if the instruction syndrome is
+/// valid.
+///
+/// The execution state is used to name the register in the instruction syndrome; if it is not
+/// known then AArch64 is assumed. For fault status codes which encode a translation table lookup
+/// level, the DFSC field has the level as its derived value, with [`Unit::Level`].
+pub fn decode_iss_data_abort(
+    iss: u64,
+    state: Option<ExecutionState>,
+) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
     let isv = FieldInfo::get_bit(iss, "ISV", Some("Instruction Syndrome Valid"), 24)
         .describe_bit(describe_isv);
 
@@ -50,10 +71,19 @@ pub fn decode_iss_data_abort(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
             0b11 => SyndromeAccessSize::Doubleword,
             _ => unreachable!(),
         };
-        let sas = sas.with_description(sas_value.to_string());
+        let bytes = 1 << sas.value;
+        let sas = sas
+            .with_description(sas_value.to_string())
+            .with_derived(bytes, Unit::Bytes);
         let sse = FieldInfo::get_bit(iss, "SSE", Some("Syndrome Sign Extend"), 21);
         let srt = FieldInfo::get(iss, "SRT", Some("Syndrome Register Transfer"), 16, 21);
         let sf = FieldInfo::get_bit(iss, "SF", Some("Sixty-Four"), 15).describe_bit(describe_sf);
+        let srt_name = register_name(
+            srt.value,
+            state.unwrap_or(ExecutionState::AArch64),
+            sf.as_bit(),
+        );
+        let srt = srt.with_description(srt_name);
         let ar =
             FieldInfo::get_bit(iss, "AR", Some("Acquire/Release"), 14).describe_bit(describe_ar);
         vec![sas, sse, srt, sf, ar]
@@ -62,28 +92,145 @@ pub fn decode_iss_data_abort(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
         vec![res0]
     };
 
-    let vncr = FieldInfo::get_bit(iss, "VNCR", None, 13);
+    let vncr = FieldInfo::get_bit(iss, "VNCR", None, 13).describe_bit(describe_vncr);
     let fnv = FieldInfo::get_bit(iss, "FnV", Some("FAR not Valid"), 10).describe_bit(describe_fnv);
-    let ea = FieldInfo::get_bit(iss, "EA", Some("External abort type"), 9);
-    let cm = FieldInfo::get_bit(iss, "CM", Some("Cache Maintenance"), 8);
-    let s1ptw = FieldInfo::get_bit(iss, "S1PTW", Some("Stage-1 translation table walk"), 7);
-    let wnr = FieldInfo::get_bit(iss, "WnR", Some("Write not Read"), 6).describe_bit(describe_wnr);
-    let dfsc =
-        FieldInfo::get(iss, "DFSC", Some("Data Fault Status Code"), 0, 6).describe(describe_fsc)?;
-    let set = if dfsc.value == 0b010000 {
-        FieldInfo::get(iss, "SET", Some("Synchronous Error Type"), 11, 13).describe(describe_set)?
+    let ea = FieldInfo::get_bit(iss, "EA", Some("External abort type"), 9)
+        .with_description("IMPLEMENTATION DEFINED classification of external aborts".to_string());
+    let cm = FieldInfo::get_bit(iss, "CM", Some("Cache Maintenance"), 8).describe_bit(describe_cm);
+    let s1ptw = FieldInfo::get_bit(iss, "S1PTW", Some("Stage-1 translation table walk"), 7)
+        .describe_bit(describe_s1ptw);
+    let wnr = FieldInfo::get_bit(iss, "WnR", Some("Write not Read"), 6);
+    let wnr = if cm.as_bit() {
+        wnr.with_description(
+            "Always set for aborts caused by cache maintenance and address translation \
+             instructions"
+                .to_string(),
+        )
     } else {
-        FieldInfo::get(iss, "RES0", Some("Reserved"), 11, 13)
+        wnr.describe_bit(describe_wnr)
+    };
+    let dfsc = with_stage2_note(
+        with_fsc_level(
+            FieldInfo::get(iss, "DFSC", Some("Data Fault Status Code"), 0, 6)
+                .describe(describe_fsc)?,
+        ),
+        s1ptw.as_bit(),
+    );
+    let fnv = with_fnv_note(fnv, dfsc.value);
+    // Bits 11 and 12 are only meaningful for some fault status codes.
+    let set = match dfsc.value {
+        0b010000 => FieldInfo::get(iss, "SET", Some("Synchronous Error Type"), 11, 13)
+            .describe(describe_set)?,
+        0b110101 => FieldInfo::get(iss, "LST", Some("Load/Store Type"), 11, 13)
+            .with_description(describe_lst(iss >> 11 & 0b11).to_string()),
+        _ => FieldInfo::get(iss, "RES0", Some("Reserved"), 11, 13),
+    };
+
+    let description = if isv.as_bit() {
+        Some(describe_access(&intruction_syndrome_fields, wnr.as_bit()))
+    } else {
+        None
     };
 
     let mut fields = vec![isv];
     fields.extend(intruction_syndrome_fields);
     fields.extend(vec![vncr, set, fnv, ea, cm, s1ptw, wnr, dfsc]);
-    Ok(fields)
+    Ok((fields, description))
 }
 
+/// Summarises the access described by the SAS, SSE, SRT, SF and AR fields of a valid instruction
+/// syndrome, e.g. "32-bit sign-extended halfword load into w18, no acquire/release".
+fn describe_access(instruction_syndrome: &[FieldInfo], write: bool) -> String {
+    let [sas, sse, srt, sf, ar] = instruction_syndrome else {
+        unreachable!("Instruction syndrome should have 5 fields");
+    };
+    let width = if sf.as_bit() { 64 } else { 32 };
+    let size = sas.description.as_deref().unwrap_or_default();
+    let register = srt.description.as_deref().unwrap_or_default();
+    let access = if write {
+        format!("{}-bit {} store from {}", width, size, register)
+    } else if sse.as_bit() {
+        format!(
+            "{}-bit sign-extended {} load into {}",
+            width, size, register
+        )
+    } else {
+        format!("{}-bit {} load into {}", width, size, register)
+    };
+    let ordering = if ar.as_bit() {
+        "with acquire/release semantics"
+    } else {
+        "no acquire/release"
+    };
+    format!("{}, {}", access, ordering)
+}
+
+/// Decodes the ISS2 value for a Data Abort.
+pub fn decode_iss2_data_abort(iss2: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(iss2, "RES0", Some("Reserved"), 12, 24).check_res0()?;
+    let hdbssf = describe_if_set(
+        FieldInfo::get_bit(iss2, "HDBSSF", Some("HDBSS Full"), 11),
+        "Fault because the Hardware Dirty state tracking Structure (HDBSS) is full",
+    );
+    let tnd = describe_if_set(
+        FieldInfo::get_bit(iss2, "TnD", Some("Tag not Data"), 10),
+        "Fault on an access to Allocation Tags rather than data",
+    );
+    let tag_access = describe_if_set(
+        FieldInfo::get_bit(iss2, "TagAccess", Some("Tag Access"), 9),
+        "Permission fault on an access to Allocation Tags in a page without tag access \
+         permission (NoTagAccess)",
+    );
+    let gcs = describe_if_set(
+        FieldInfo::get_bit(iss2, "GCS", Some("Guarded Control Stack data access"), 8),
+        "Fault on a Guarded Control Stack data access",
+    );
+    let assured_only = describe_if_set(
+        FieldInfo::get_bit(iss2, "AssuredOnly", None, 7),
+        "Permission fault due to the AssuredOnly attribute of stage 2 translation",
+    );
+    let overlay = describe_if_set(
+        FieldInfo::get_bit(iss2, "Overlay", Some("Permission Overlay"), 6),
+        "Permission fault due to a Permission Overlay",
+    );
+    let dirty_bit = describe_if_set(
+        FieldInfo::get_bit(iss2, "DirtyBit", None, 5),
+        "Permission fault due to the dirty state of the stage 2 descriptor, which may be resolved \
+         by setting it",
+    );
+    let xs = FieldInfo::get(
+        iss2,
+        "Xs",
+        Some("Register for the status result of ST64BV or ST64BV0"),
+        0,
+        5,
+    );
+
+    Ok(vec![
+        res0,
+        hdbssf,
+        tnd,
+        tag_access,
+        gcs,
+        assured_only,
+        overlay,
+        dirty_bit,
+        xs,
+    ])
+}
+
+/// Adds the given description to a single-bit field if it is set.
+fn describe_if_set(field: FieldInfo, description: &str) -> FieldInfo {
+    if field.as_bit() {
+        field.with_description(description.to_string())
+    } else {
+        field
+    }
+}
+
+/// The size of the access reported in a data abort instruction syndrome.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum SyndromeAccessSize {
+pub enum SyndromeAccessSize {
     Byte = 0b00,
     Halfword = 0b01,
     Word = 0b10,
@@ -126,6 +273,71 @@ fn describe_ar(ar: bool) -> &'static str {
     }
 }
 
+fn describe_cm(cm: bool) -> &'static str {
+    if cm {
+        "Abort caused by a cache maintenance or address translation instruction, so WnR is set \
+         regardless of the access"
+    } else {
+        "Abort not caused by a cache maintenance or address translation instruction"
+    }
+}
+
+fn describe_s1ptw(s1ptw: bool) -> &'static str {
+    if s1ptw {
+        "Fault on the stage 2 translation for a stage 1 translation table walk, so the fault \
+         status code is for stage 2"
+    } else {
+        "Fault not on a stage 2 translation for a stage 1 translation table walk"
+    }
+}
+
+/// Describes the VNCR bit, which is set when a fault came from a System register access which
+/// FEAT_NV2 redirected to memory at the address in VNCR_EL2.
+pub(crate) fn describe_vncr(vncr: bool) -> &'static str {
+    if vncr {
+        "Fault from a System register access redirected to memory by VNCR_EL2, for a guest \
+         hypervisor under nested virtualization (FEAT_NV2)"
+    } else {
+        "Fault not from a System register access redirected by VNCR_EL2"
+    }
+}
+
+/// Returns whether FnV may be set for the given DFSC or IFSC value. It is only valid for external
+/// aborts not on a translation table walk, and RES0 otherwise.
+pub(crate) fn fnv_allowed(fsc: u64) -> bool {
+    fsc == 0b010000
+}
+
+/// Returns whether the FAR holds the faulting virtual address for the given decoded ESR, or `None`
+/// if the FAR is UNKNOWN for its exception class.
+///
+/// The FAR is set for Instruction Aborts, PC alignment faults, Data Aborts and Watchpoints, but for
+/// aborts it is not valid if FnV is set.
+pub(crate) fn far_valid(fields: &[FieldInfo]) -> Option<bool> {
+    match FieldInfo::find(fields, "EC")?.value {
+        0b100000 | 0b100001 | 0b100100 | 0b100101 => Some(
+            !FieldInfo::find(fields, "ISS")
+                .and_then(|iss| FieldInfo::find(&iss.subfields, "FnV"))
+                .is_some_and(FieldInfo::as_bit),
+        ),
+        0b100010 | 0b110100 | 0b110101 => Some(true),
+        _ => None,
+    }
+}
+
+/// Notes in the description of the given FnV field that it is inconsistent with the fault status
+/// code, if it is set for a fault which isn't an external abort.
+fn with_fnv_note(fnv: FieldInfo, fsc: u64) -> FieldInfo {
+    if fnv.as_bit() && !fnv_allowed(fsc) {
+        fnv.with_description(format!(
+            "FAR is not valid, but FSC {:#x} is not an external abort so FnV should be 0",
+            fsc
+        ))
+    } else {
+        fnv
+    }
+}
+
 fn describe_fnv(fnv: bool) -> &'static str {
     if fnv {
         "FAR is not valid, it holds an unknown value"
@@ -142,7 +354,7 @@ fn describe_wnr(wnr: bool) -> &'static str {
     }
 }
 
-fn describe_fsc(fsc: u64) -> Result<&'static str, DecodeError> {
+pub(crate) fn describe_fsc(fsc: u64) -> Result<&'static str, DecodeError> {
     let description = match fsc {
         0b000000 => {
             "Address size fault, level 0 of translation or translation table base register."
@@ -166,7 +378,10 @@ fn describe_fsc(fsc: u64) -> Result<&'static str, DecodeError> {
             "Synchronous External abort, not on translation table walk or hardware update of \
              translation table."
         }
-        0b010001 => "Synchronous Tag Check Fault.",
+        0b010001 => {
+            "Synchronous Tag Check Fault, the Logical Address Tag of the address didn't match the \
+             Allocation Tag of the memory accessed."
+        }
         0b010011 => {
             "Synchronous External abort on translation table walk or hardware update of \
              translation table, level -1."
@@ -211,17 +426,85 @@ fn describe_fsc(fsc: u64) -> Result<&'static str, DecodeError> {
              hardware update of translation table, level 3."
         }
         0b100001 => "Alignment fault.",
+        0b100011 => {
+            "Granule Protection Fault on translation table walk or hardware update of \
+             translation table, level -1."
+        }
+        0b100100 => {
+            "Granule Protection Fault on translation table walk or hardware update of \
+             translation table, level 0."
+        }
+        0b100101 => {
+            "Granule Protection Fault on translation table walk or hardware update of \
+             translation table, level 1."
+        }
+        0b100110 => {
+            "Granule Protection Fault on translation table walk or hardware update of \
+             translation table, level 2."
+        }
+        0b100111 => {
+            "Granule Protection Fault on translation table walk or hardware update of \
+             translation table, level 3."
+        }
+        0b101000 => {
+            "Granule Protection Fault, not on translation table walk or hardware update \
+             of translation table."
+        }
         0b101001 => "Address size fault, level -1.",
         0b101011 => "Translation fault, level -1.",
         0b110000 => "TLB conflict abort.",
         0b110001 => "Unsupported atomic hardware update fault.",
         0b110100 => "IMPLEMENTATION DEFINED fault (Lockdown).",
-        0b110101 => "IMPLEMENTATION DEFINED fault (Unsupported Exclusive or Atomic access).",
+        0b110101 => {
+            "IMPLEMENTATION DEFINED fault (Unsupported Exclusive or Atomic access), commonly caused \
+             by an exclusive or atomic access to Device or Non-cacheable memory, or to memory \
+             which the interconnect doesn't support atomics for."
+        }
         _ => return Err(DecodeError::InvalidFsc { fsc }),
     };
     Ok(description)
 }
 
+/// Notes in the description of the given DFSC or IFSC field that it is a stage 2 fault code, if
+/// S1PTW is set.
+fn with_stage2_note(fsc: FieldInfo, s1ptw: bool) -> FieldInfo {
+    match &fsc.description {
+        Some(description) if s1ptw => {
+            let description = format!(
+                "{}, on stage 2 translation for a stage 1 translation table walk.",
+                description.trim_end_matches('.')
+            );
+            fsc.with_description(description)
+        }
+        _ => fsc,
+    }
+}
+
+/// Attaches the level of translation table lookup at which the fault occurred to the given DFSC or
+/// IFSC field as its derived value, if the fault status code includes one.
+///
+/// This is a derived value rather than a subfield, as level -1 isn't encoded in any particular
+/// bits of the fault status code.
+fn with_fsc_level(fsc: FieldInfo) -> FieldInfo {
+    let level = match fsc.value {
+        0b000000..=0b001111 | 0b010100..=0b010111 | 0b011100..=0b011111 | 0b100100..=0b100111 => {
+            fsc.value as i64 & 0b11
+        }
+        0b010011 | 0b011011 | 0b100011 | 0b101001 | 0b101011 => -1,
+        _ => return fsc,
+    };
+    fsc.with_derived(level, Unit::Level)
+}
+
+fn describe_lst(lst: u64) -> &'static str {
+    match lst {
+        0b01 => "ST64BV instruction",
+        0b10 => "LD64B or ST64B instruction",
+        0b11 => "ST64BV0 instruction",
+        _ => "Not an LD64B, ST64B, ST64BV or ST64BV0 instruction",
+    }
+}
+
 fn describe_set(set: u64) -> Result<&'static str, DecodeError> {
     Ok(match set {
         0b00 => "Recoverable state (UER)",
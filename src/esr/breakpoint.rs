@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::abort::describe_vncr;
 use crate::{DecodeError, FieldInfo};
 
 /// Decodes the ISS value for a Breakpoint or Vector Catch debug exception.
@@ -41,17 +42,42 @@ pub fn decode_iss_software_step(iss: u64) -> Result<Vec<FieldInfo>, DecodeError>
 
 /// Decodes the ISS value for a Watchpoint exception.
 pub fn decode_iss_watchpoint(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
-    let res0a = FieldInfo::get(iss, "RES0", Some("Reserved"), 15, 25).check_res0()?;
+    let res0a = FieldInfo::get_bit(iss, "RES0", Some("Reserved"), 24).check_res0()?;
+    let wptv = FieldInfo::get_bit(iss, "WPTV", Some("Watchpoint number Valid"), 17)
+        .describe_bit(describe_wptv);
+    let wpt = FieldInfo::get(iss, "WPT", Some("Watchpoint number"), 18, 24);
+    let wpt = if wptv.as_bit() {
+        let description = format!("Watchpoint {} was hit", wpt.value);
+        wpt.with_description(description)
+    } else {
+        wpt
+    };
+    let wpf = FieldInfo::get_bit(iss, "WPF", Some("Watchpoint might be false-positive"), 16)
+        .describe_bit(describe_wpf);
+    let fnp =
+        FieldInfo::get_bit(iss, "FnP", Some("FAR not Precise"), 15).describe_bit(describe_fnp);
     let res0b = FieldInfo::get_bit(iss, "RES0", Some("Reserved"), 14).check_res0()?;
-    let vncr = FieldInfo::get_bit(iss, "VNCR", None, 13);
+    let vncr = FieldInfo::get_bit(iss, "VNCR", None, 13).describe_bit(describe_vncr);
     let res0c = FieldInfo::get(iss, "RES0", Some("Reserved"), 9, 13).check_res0()?;
-    let cm = FieldInfo::get_bit(iss, "CM", Some("Cache Maintenance"), 8);
+    let cm = FieldInfo::get_bit(iss, "CM", Some("Cache Maintenance"), 8).describe_bit(describe_cm);
     let res0d = FieldInfo::get_bit(iss, "RES0", Some("Reserved"), 7).check_res0()?;
     let wnr = FieldInfo::get_bit(iss, "WnR", Some("Write not Read"), 6).describe_bit(describe_wnr);
     let dfsc =
         FieldInfo::get(iss, "DFSC", Some("Data Fault Status Code"), 0, 6).describe(describe_fsc)?;
 
-    Ok(vec![res0a, res0b, vncr, res0c, cm, res0d, wnr, dfsc])
+    Ok(vec![
+        res0a, wpt, wptv, wpf, fnp, res0b, vncr, res0c, cm, res0d, wnr, dfsc,
+    ])
+}
+
+/// Decodes the ISS value for a BRK instruction, noting what well-known immediate values are used
+/// for.
+pub fn decode_iss_brk(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let mut fields = decode_iss_breakpoint(iss)?;
+    if let Some(description) = describe_brk_immediate(fields[1].value) {
+        fields[1] = fields[1].clone().with_description(description);
+    }
+    Ok(fields)
 }
 
 /// Decodes the ISS value for a Breakpoint instruction.
@@ -68,6 +94,51 @@ pub fn decode_iss_breakpoint(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     Ok(vec![res0, comment])
 }
 
+/// Describes what the given BRK immediate is used for, if it is one of the values used by Linux or
+/// by compilers.
+fn describe_brk_immediate(immediate: u64) -> Option<String> {
+    let description = match immediate {
+        0x0001 => "Compiler trap, e.g. __builtin_trap() or a Rust abort",
+        0x0004 => "Linux kprobe",
+        0x0005 => "Linux uprobe",
+        0x0006 => "Linux kprobe single-step",
+        0x0007 => "Linux kretprobe",
+        0x0100 => "Linux BPF exception table fault",
+        0x0400 => "Linux kgdb dynamic breakpoint",
+        0x0401 => "Linux kgdb compiled breakpoint",
+        0x0800 => "Linux BUG() or WARN()",
+        0x0900..=0x09ff => return Some(describe_kasan(immediate)),
+        0x5500..=0x55ff => {
+            return Some(format!(
+                "Linux UBSAN check failure, check type {}",
+                immediate & 0xff
+            ))
+        }
+        0x8000..=0x83ff => "Linux kCFI type check failure",
+        0xf000 => "Compiler debug trap, e.g. __builtin_debugtrap() or __debugbreak()",
+        _ => return None,
+    };
+    Some(description.to_string())
+}
+
+/// Describes a Linux KASAN BRK immediate, which encodes the size and direction of the bad access.
+fn describe_kasan(immediate: u64) -> String {
+    format!(
+        "Linux KASAN tag check failure, on {} of {} bytes{}",
+        if immediate & 0x10 != 0 {
+            "write"
+        } else {
+            "read"
+        },
+        1 << (immediate & 0xf),
+        if immediate & 0x20 != 0 {
+            ", recoverable"
+        } else {
+            ""
+        }
+    )
+}
+
 fn describe_fsc(fsc: u64) -> Result<&'static str, DecodeError> {
     match fsc {
         0b100010 => Ok("Debug exception"),
@@ -91,6 +162,40 @@ fn describe_ex(ex: bool) -> &'static str {
     }
 }
 
+fn describe_wptv(wptv: bool) -> &'static str {
+    if wptv {
+        "WPT is valid"
+    } else {
+        "WPT is not valid, so which watchpoint was hit is not known"
+    }
+}
+
+fn describe_wpf(wpf: bool) -> &'static str {
+    if wpf {
+        "The watchpoint matched an access rounded out to a 16-byte boundary, but might not have \
+         matched the original access"
+    } else {
+        "The watchpoint matched the original access"
+    }
+}
+
+fn describe_fnp(fnp: bool) -> &'static str {
+    if fnp {
+        "FAR holds an address within the naturally-aligned granule containing the watchpointed \
+         address, rather than the exact address"
+    } else {
+        "FAR holds the address of the access which was watchpointed"
+    }
+}
+
+fn describe_cm(cm: bool) -> &'static str {
+    if cm {
+        "Watchpoint caused by a cache maintenance instruction"
+    } else {
+        "Watchpoint not caused by a cache maintenance instruction"
+    }
+}
+
 fn describe_wnr(wnr: bool) -> &'static str {
     if wnr {
         "Watchpoint caused by writing to memory"
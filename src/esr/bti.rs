@@ -17,7 +17,23 @@ use crate::{DecodeError, FieldInfo};
 /// Decodes the ISS value for a Branch Target Exception.
 pub fn decode_iss_bti(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 2, 25).check_res0()?;
-    let btype = FieldInfo::get(iss, "BTYPE", Some("PSTATE.BTYPE value"), 0, 2);
+    let btype =
+        FieldInfo::get(iss, "BTYPE", Some("PSTATE.BTYPE value"), 0, 2).describe(describe_btype)?;
 
     Ok(vec![res0, btype])
 }
+
+fn describe_btype(btype: u64) -> Result<&'static str, DecodeError> {
+    Ok(match btype {
+        0b00 => "Not a branch",
+        0b01 => {
+            "Indirect branch with BR* using X16 or X17 or from a non-guarded page, which may only \
+             land on BTI j, BTI c, BTI jc, PACIASP or PACIBSP"
+        }
+        0b10 => "Indirect call with BLR*, which may only land on BTI c, BTI jc, PACIASP or PACIBSP",
+        _ => {
+            "Indirect branch with BR* using a register other than X16 or X17, which may only land \
+             on BTI j or BTI jc"
+        }
+    })
+}
@@ -14,6 +14,9 @@
 
 //! Description functions shared between multiple modules.
 
+use super::ExecutionState;
+use crate::FieldInfo;
+
 pub fn describe_cv(cv: bool) -> &'static str {
     if cv {
         "COND is valid"
@@ -21,3 +24,86 @@ pub fn describe_cv(cv: bool) -> &'static str {
         "COND is not valid"
     }
 }
+
+/// Describes the given COND field with the AArch32 condition mnemonic it encodes, if CV indicates
+/// that it is valid.
+pub fn with_cond_mnemonic(cond: FieldInfo, cv: bool) -> FieldInfo {
+    if !cv {
+        return cond;
+    }
+    let mnemonic = match cond.value {
+        0b0000 => "EQ",
+        0b0001 => "NE",
+        0b0010 => "CS",
+        0b0011 => "CC",
+        0b0100 => "MI",
+        0b0101 => "PL",
+        0b0110 => "VS",
+        0b0111 => "VC",
+        0b1000 => "HI",
+        0b1001 => "LS",
+        0b1010 => "GE",
+        0b1011 => "LT",
+        0b1100 => "GT",
+        0b1101 => "LE",
+        0b1110 => "AL",
+        _ => "NV",
+    };
+    cond.with_description(mnemonic.to_string())
+}
+
+/// Returns the COND field of the given ISS, described according to the execution state from which
+/// the exception was taken, if known.
+pub fn get_cond(iss: u64, state: Option<ExecutionState>) -> FieldInfo {
+    let cond = FieldInfo::get(
+        iss,
+        "COND",
+        Some("Condition code of the trapped instruction"),
+        20,
+        24,
+    );
+    if state == Some(ExecutionState::AArch64) {
+        cond.with_description("Not used for exceptions taken from AArch64 state".to_string())
+    } else {
+        cond
+    }
+}
+
+/// Returns the name of the given general-purpose register, as used by an instruction executed in
+/// the given execution state.
+///
+/// For AArch64 state `sixty_four` selects between the X and W forms of the register, and register
+/// 31 is taken to be the zero register.
+///
+/// For AArch32 state the number is in the AArch64 view of the AArch32 registers, so numbers 16 to
+/// 30 name the banked registers of the various modes.
+pub fn register_name(number: u64, state: ExecutionState, sixty_four: bool) -> String {
+    match (state, number, sixty_four) {
+        (ExecutionState::AArch32, 13, _) => "r13 (SP)".to_string(),
+        (ExecutionState::AArch32, 14, _) => "r14 (LR)".to_string(),
+        (ExecutionState::AArch32, 15, _) => "r15 (PC)".to_string(),
+        (ExecutionState::AArch32, 16, _) => "LR_irq".to_string(),
+        (ExecutionState::AArch32, 17, _) => "SP_irq".to_string(),
+        (ExecutionState::AArch32, 18, _) => "LR_svc".to_string(),
+        (ExecutionState::AArch32, 19, _) => "SP_svc".to_string(),
+        (ExecutionState::AArch32, 20, _) => "LR_abt".to_string(),
+        (ExecutionState::AArch32, 21, _) => "SP_abt".to_string(),
+        (ExecutionState::AArch32, 22, _) => "LR_und".to_string(),
+        (ExecutionState::AArch32, 23, _) => "SP_und".to_string(),
+        (ExecutionState::AArch32, 24..=28, _) => format!("R{}_fiq", number - 16),
+        (ExecutionState::AArch32, 29, _) => "SP_fiq".to_string(),
+        (ExecutionState::AArch32, 30, _) => "LR_fiq".to_string(),
+        (ExecutionState::AArch32, 31, _) => "r31 (reserved)".to_string(),
+        (ExecutionState::AArch32, _, _) => format!("r{}", number),
+        (ExecutionState::AArch64, 31, true) => "xzr".to_string(),
+        (ExecutionState::AArch64, 31, false) => "wzr".to_string(),
+        (ExecutionState::AArch64, _, true) => format!("x{}", number),
+        (ExecutionState::AArch64, _, false) => format!("w{}", number),
+    }
+}
+
+/// Describes a register number field of an AArch64 instruction with the name of the X register.
+pub fn describe_register(field: FieldInfo) -> FieldInfo {
+    let name = register_name(field.value, ExecutionState::AArch64, true);
+    field.with_description(name)
+}
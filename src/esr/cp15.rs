@@ -0,0 +1,149 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Identification of trapped accesses to AArch32 System registers in the CP15 namespace.
+
+/// Returns the name of the AArch32 System register or System instruction accessed through CP15
+/// with the given encoding, and the name of its AArch64 equivalent if it has one.
+pub fn cp15_register(
+    opc1: u64,
+    crn: u64,
+    crm: u64,
+    opc2: u64,
+) -> Option<(&'static str, Option<&'static str>)> {
+    Some(match (opc1, crn, crm, opc2) {
+        // Identification registers.
+        (0, 0, 0, 0) => ("MIDR", Some("MIDR_EL1")),
+        (0, 0, 0, 1) => ("CTR", Some("CTR_EL0")),
+        (0, 0, 0, 5) => ("MPIDR", Some("MPIDR_EL1")),
+        (0, 0, 0, 6) => ("REVIDR", Some("REVIDR_EL1")),
+        (0, 0, 1, 0) => ("ID_PFR0", Some("ID_PFR0_EL1")),
+        (0, 0, 1, 1) => ("ID_PFR1", Some("ID_PFR1_EL1")),
+        (0, 0, 1, 2) => ("ID_DFR0", Some("ID_DFR0_EL1")),
+        (0, 0, 1, 3) => ("ID_AFR0", Some("ID_AFR0_EL1")),
+        (0, 0, 1, 4) => ("ID_MMFR0", Some("ID_MMFR0_EL1")),
+        (0, 0, 1, 5) => ("ID_MMFR1", Some("ID_MMFR1_EL1")),
+        (0, 0, 1, 6) => ("ID_MMFR2", Some("ID_MMFR2_EL1")),
+        (0, 0, 1, 7) => ("ID_MMFR3", Some("ID_MMFR3_EL1")),
+        (0, 0, 2, 0) => ("ID_ISAR0", Some("ID_ISAR0_EL1")),
+        (0, 0, 2, 1) => ("ID_ISAR1", Some("ID_ISAR1_EL1")),
+        (0, 0, 2, 2) => ("ID_ISAR2", Some("ID_ISAR2_EL1")),
+        (0, 0, 2, 3) => ("ID_ISAR3", Some("ID_ISAR3_EL1")),
+        (0, 0, 2, 4) => ("ID_ISAR4", Some("ID_ISAR4_EL1")),
+        (0, 0, 2, 5) => ("ID_ISAR5", Some("ID_ISAR5_EL1")),
+        (0, 0, 2, 6) => ("ID_MMFR4", Some("ID_MMFR4_EL1")),
+        (0, 0, 2, 7) => ("ID_ISAR6", Some("ID_ISAR6_EL1")),
+        (1, 0, 0, 0) => ("CCSIDR", Some("CCSIDR_EL1")),
+        (1, 0, 0, 1) => ("CLIDR", Some("CLIDR_EL1")),
+        (1, 0, 0, 7) => ("AIDR", Some("AIDR_EL1")),
+        (2, 0, 0, 0) => ("CSSELR", Some("CSSELR_EL1")),
+        (4, 0, 0, 0) => ("VPIDR", Some("VPIDR_EL2")),
+        (4, 0, 0, 5) => ("VMPIDR", Some("VMPIDR_EL2")),
+        // System control and memory management.
+        (0, 1, 0, 0) => ("SCTLR", Some("SCTLR_EL1")),
+        (0, 1, 0, 1) => ("ACTLR", Some("ACTLR_EL1")),
+        (0, 1, 0, 2) => ("CPACR", Some("CPACR_EL1")),
+        (0, 2, 0, 0) => ("TTBR0", Some("TTBR0_EL1")),
+        (0, 2, 0, 1) => ("TTBR1", Some("TTBR1_EL1")),
+        (0, 2, 0, 2) => ("TTBCR", Some("TCR_EL1")),
+        (0, 3, 0, 0) => ("DACR", Some("DACR32_EL2")),
+        (0, 5, 0, 0) => ("DFSR", Some("ESR_EL1")),
+        (0, 5, 0, 1) => ("IFSR", Some("IFSR32_EL2")),
+        (0, 5, 1, 0) => ("ADFSR", Some("AFSR0_EL1")),
+        (0, 5, 1, 1) => ("AIFSR", Some("AFSR1_EL1")),
+        (0, 6, 0, 0) => ("DFAR", Some("FAR_EL1")),
+        (0, 6, 0, 2) => ("IFAR", Some("FAR_EL1")),
+        (0, 7, 4, 0) => ("PAR", Some("PAR_EL1")),
+        (0, 10, 2, 0) => ("PRRR or MAIR0", Some("MAIR_EL1")),
+        (0, 10, 2, 1) => ("NMRR or MAIR1", Some("MAIR_EL1")),
+        (0, 10, 3, 0) => ("AMAIR0", Some("AMAIR_EL1")),
+        (0, 10, 3, 1) => ("AMAIR1", Some("AMAIR_EL1")),
+        (0, 12, 0, 0) => ("VBAR", Some("VBAR_EL1")),
+        (0, 13, 0, 1) => ("CONTEXTIDR", Some("CONTEXTIDR_EL1")),
+        (0, 13, 0, 2) => ("TPIDRURW", Some("TPIDR_EL0")),
+        (0, 13, 0, 3) => ("TPIDRURO", Some("TPIDRRO_EL0")),
+        (0, 13, 0, 4) => ("TPIDRPRW", Some("TPIDR_EL1")),
+        // Cache maintenance and barriers.
+        (0, 7, 1, 0) => ("ICIALLUIS", Some("IC IALLUIS")),
+        (0, 7, 5, 0) => ("ICIALLU", Some("IC IALLU")),
+        (0, 7, 5, 1) => ("ICIMVAU", Some("IC IVAU")),
+        (0, 7, 5, 4) => ("CP15ISB", Some("ISB")),
+        (0, 7, 5, 6) => ("BPIALL", None),
+        (0, 7, 6, 1) => ("DCIMVAC", Some("DC IVAC")),
+        (0, 7, 6, 2) => ("DCISW", Some("DC ISW")),
+        (0, 7, 10, 1) => ("DCCMVAC", Some("DC CVAC")),
+        (0, 7, 10, 2) => ("DCCSW", Some("DC CSW")),
+        (0, 7, 10, 4) => ("CP15DSB", Some("DSB")),
+        (0, 7, 10, 5) => ("CP15DMB", Some("DMB")),
+        (0, 7, 11, 1) => ("DCCMVAU", Some("DC CVAU")),
+        (0, 7, 14, 1) => ("DCCIMVAC", Some("DC CIVAC")),
+        (0, 7, 14, 2) => ("DCCISW", Some("DC CISW")),
+        // TLB maintenance.
+        (0, 8, 3, 0) => ("TLBIALLIS", Some("TLBI VMALLE1IS")),
+        (0, 8, 3, 1) => ("TLBIMVAIS", Some("TLBI VAE1IS")),
+        (0, 8, 3, 2) => ("TLBIASIDIS", Some("TLBI ASIDE1IS")),
+        (0, 8, 7, 0) => ("TLBIALL", Some("TLBI VMALLE1")),
+        (0, 8, 7, 1) => ("TLBIMVA", Some("TLBI VAE1")),
+        (0, 8, 7, 2) => ("TLBIASID", Some("TLBI ASIDE1")),
+        // Performance Monitors.
+        (0, 9, 12, 0) => ("PMCR", Some("PMCR_EL0")),
+        (0, 9, 12, 1) => ("PMCNTENSET", Some("PMCNTENSET_EL0")),
+        (0, 9, 12, 2) => ("PMCNTENCLR", Some("PMCNTENCLR_EL0")),
+        (0, 9, 12, 3) => ("PMOVSR", Some("PMOVSCLR_EL0")),
+        (0, 9, 12, 4) => ("PMSWINC", Some("PMSWINC_EL0")),
+        (0, 9, 12, 5) => ("PMSELR", Some("PMSELR_EL0")),
+        (0, 9, 12, 6) => ("PMCEID0", Some("PMCEID0_EL0")),
+        (0, 9, 12, 7) => ("PMCEID1", Some("PMCEID1_EL0")),
+        (0, 9, 13, 0) => ("PMCCNTR", Some("PMCCNTR_EL0")),
+        (0, 9, 13, 1) => ("PMXEVTYPER", Some("PMXEVTYPER_EL0")),
+        (0, 9, 13, 2) => ("PMXEVCNTR", Some("PMXEVCNTR_EL0")),
+        (0, 9, 14, 0) => ("PMUSERENR", Some("PMUSERENR_EL0")),
+        (0, 9, 14, 1) => ("PMINTENSET", Some("PMINTENSET_EL1")),
+        (0, 9, 14, 2) => ("PMINTENCLR", Some("PMINTENCLR_EL1")),
+        (0, 9, 14, 3) => ("PMOVSSET", Some("PMOVSSET_EL0")),
+        // Generic Timer.
+        (0, 14, 0, 0) => ("CNTFRQ", Some("CNTFRQ_EL0")),
+        (0, 14, 1, 0) => ("CNTKCTL", Some("CNTKCTL_EL1")),
+        (0, 14, 2, 0) => ("CNTP_TVAL", Some("CNTP_TVAL_EL0")),
+        (0, 14, 2, 1) => ("CNTP_CTL", Some("CNTP_CTL_EL0")),
+        (0, 14, 3, 0) => ("CNTV_TVAL", Some("CNTV_TVAL_EL0")),
+        (0, 14, 3, 1) => ("CNTV_CTL", Some("CNTV_CTL_EL0")),
+        _ => return None,
+    })
+}
+
+/// Returns the name of the 64-bit AArch32 System register accessed through CP15 with MCRR or MRRC
+/// with the given encoding, and the name of its AArch64 equivalent.
+pub fn cp15_register_64(opc1: u64, crm: u64) -> Option<(&'static str, &'static str)> {
+    Some(match (opc1, crm) {
+        (0, 2) => ("TTBR0", "TTBR0_EL1"),
+        (1, 2) => ("TTBR1", "TTBR1_EL1"),
+        (4, 2) => ("HTTBR", "TTBR0_EL2"),
+        (6, 2) => ("VTTBR", "VTTBR_EL2"),
+        (0, 7) => ("PAR", "PAR_EL1"),
+        (0, 9) => ("PMCCNTR", "PMCCNTR_EL0"),
+        (0, 12) => ("ICC_SGI1R", "ICC_SGI1R_EL1"),
+        (1, 12) => ("ICC_ASGI1R", "ICC_ASGI1R_EL1"),
+        (2, 12) => ("ICC_SGI0R", "ICC_SGI0R_EL1"),
+        (0, 14) => ("CNTPCT", "CNTPCT_EL0"),
+        (1, 14) => ("CNTVCT", "CNTVCT_EL0"),
+        (2, 14) => ("CNTP_CVAL", "CNTP_CVAL_EL0"),
+        (3, 14) => ("CNTV_CVAL", "CNTV_CVAL_EL0"),
+        (4, 14) => ("CNTVOFF", "CNTVOFF_EL2"),
+        (6, 14) => ("CNTHP_CVAL", "CNTHP_CVAL_EL2"),
+        (8, 14) => ("CNTPCTSS", "CNTPCTSS_EL0"),
+        (9, 14) => ("CNTVCTSS", "CNTVCTSS_EL0"),
+        _ => return None,
+    })
+}
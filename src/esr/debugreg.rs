@@ -0,0 +1,170 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Identification of trapped accesses to self-hosted debug registers, and the controls which trap
+//! them.
+
+/// Returns the name of the AArch64 debug system register with the given encoding, if it is one.
+///
+/// `read` is needed to distinguish DBGDTRRX_EL0 from DBGDTRTX_EL0, which share an encoding.
+pub fn debug_sysreg_name(
+    op0: u64,
+    op1: u64,
+    op2: u64,
+    crn: u64,
+    crm: u64,
+    read: bool,
+) -> Option<String> {
+    if op0 != 2 {
+        return None;
+    }
+    let name = match (crn, op1, crm, op2) {
+        (0, 0, _, 4) => return Some(format!("DBGBVR{}_EL1", crm)),
+        (0, 0, _, 5) => return Some(format!("DBGBCR{}_EL1", crm)),
+        (0, 0, _, 6) => return Some(format!("DBGWVR{}_EL1", crm)),
+        (0, 0, _, 7) => return Some(format!("DBGWCR{}_EL1", crm)),
+        (0, 0, 0, 2) => "OSDTRRX_EL1",
+        (0, 0, 2, 0) => "MDCCINT_EL1",
+        (0, 0, 2, 2) => "MDSCR_EL1",
+        (0, 0, 3, 2) => "OSDTRTX_EL1",
+        (0, 0, 6, 2) => "OSECCR_EL1",
+        (0, 3, 1, 0) => "MDCCSR_EL0",
+        (0, 3, 4, 0) => "DBGDTR_EL0",
+        (0, 3, 5, 0) if read => "DBGDTRRX_EL0",
+        (0, 3, 5, 0) => "DBGDTRTX_EL0",
+        (0, 4, 7, 0) => "DBGVCR32_EL2",
+        (1, 0, 0, 0) => "MDRAR_EL1",
+        (1, 0, 0, 4) => "OSLAR_EL1",
+        (1, 0, 1, 4) => "OSLSR_EL1",
+        (1, 0, 3, 4) => "OSDLR_EL1",
+        (1, 0, 4, 4) => "DBGPRCR_EL1",
+        (7, 0, 8, 6) => "DBGCLAIMSET_EL1",
+        (7, 0, 9, 6) => "DBGCLAIMCLR_EL1",
+        (7, 0, 14, 6) => "DBGAUTHSTATUS_EL1",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Returns the name of the AArch32 debug register accessed through CP14 with the given encoding,
+/// if it is one.
+///
+/// `read` is needed to distinguish DBGDTRRXint from DBGDTRTXint, which share an encoding.
+pub fn debug_cp14_name(opc1: u64, crn: u64, crm: u64, opc2: u64, read: bool) -> Option<String> {
+    if opc1 != 0 {
+        return None;
+    }
+    let name = match (crn, crm, opc2) {
+        (0, 0, 0) => "DBGDIDR",
+        (0, 1, 0) => "DBGDSCRint",
+        (0, 5, 0) if read => "DBGDTRRXint",
+        (0, 5, 0) => "DBGDTRTXint",
+        (0, 6, 0) => "DBGWFAR",
+        (0, 7, 0) => "DBGVCR",
+        (0, 0, 2) => "DBGDTRRXext",
+        (0, 2, 2) => "DBGDSCRext",
+        (0, 3, 2) => "DBGDTRTXext",
+        (0, 6, 2) => "DBGOSECCR",
+        (0, _, 4) => return Some(format!("DBGBVR{}", crm)),
+        (0, _, 5) => return Some(format!("DBGBCR{}", crm)),
+        (0, _, 6) => return Some(format!("DBGWVR{}", crm)),
+        (0, _, 7) => return Some(format!("DBGWCR{}", crm)),
+        (1, 0, 0) => "DBGDRAR",
+        (1, 0, 4) => "DBGOSLAR",
+        (1, 1, 4) => "DBGOSLSR",
+        (1, 3, 4) => "DBGOSDLR",
+        (1, 4, 4) => "DBGPRCR",
+        (1, _, 1) => return Some(format!("DBGBXVR{}", crm)),
+        (2, 0, 0) => "DBGDSAR",
+        (7, 0, 7) => "DBGDEVID2",
+        (7, 1, 7) => "DBGDEVID1",
+        (7, 2, 7) => "DBGDEVID",
+        (7, 8, 6) => "DBGCLAIMSET",
+        (7, 9, 6) => "DBGCLAIMCLR",
+        (7, 14, 6) => "DBGAUTHSTATUS",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Returns the name of the 64-bit AArch32 debug register accessed through CP14 with MCRR or MRRC
+/// with the given encoding, if it is one.
+pub fn debug_cp14_64_name(opc1: u64, crm: u64) -> Option<&'static str> {
+    match (opc1, crm) {
+        (0, 1) => Some("DBGDRAR"),
+        (0, 2) => Some("DBGDSAR"),
+        _ => None,
+    }
+}
+
+/// Returns a note about what the debug register with the given AArch64 or AArch32 name is, and
+/// which MDCR_EL2 and MDCR_EL3 controls may have caused accesses to it to be trapped.
+pub fn debug_note(name: &str) -> Option<String> {
+    let description =
+        if name.starts_with("DBGBVR") {
+            "Breakpoint value register"
+        } else if name.starts_with("DBGBCR") {
+            "Breakpoint control register"
+        } else if name.starts_with("DBGBXVR") {
+            "Breakpoint extended value register"
+        } else if name.starts_with("DBGWVR") {
+            "Watchpoint value register"
+        } else if name.starts_with("DBGWCR") {
+            "Watchpoint control register"
+        } else {
+            match name {
+                "MDCCSR_EL0" | "DBGDTR_EL0" | "DBGDTRRX_EL0" | "DBGDTRTX_EL0" | "DBGDIDR"
+                | "DBGDSCRint" | "DBGDTRRXint" | "DBGDTRTXint" => {
+                    return Some(
+                        "Debug Communications Channel register, accesses from EL0 trap to EL1 if \
+                     MDSCR_EL1.TDCC is set, and accesses trap to EL2 if MDCR_EL2.TDA, \
+                     MDCR_EL2.TDE or MDCR_EL2.TDCC is set, or to EL3 if MDCR_EL3.TDA or \
+                     MDCR_EL3.TDCC is set"
+                            .to_string(),
+                    )
+                }
+                "OSLAR_EL1" | "OSLSR_EL1" | "OSDLR_EL1" | "DBGPRCR_EL1" | "DBGOSLAR"
+                | "DBGOSLSR" | "DBGOSDLR" | "DBGPRCR" => return Some(
+                    "OS lock or power control register, accesses trap to EL2 if MDCR_EL2.TDOSA \
+                     or MDCR_EL2.TDE is set, or to EL3 if MDCR_EL3.TDOSA is set"
+                        .to_string(),
+                ),
+                "MDRAR_EL1" | "DBGDRAR" | "DBGDSAR" => {
+                    return Some(
+                        "Debug ROM address register, accesses trap to EL2 if MDCR_EL2.TDRA or \
+                     MDCR_EL2.TDE is set, or to EL3 if MDCR_EL3.TDA is set"
+                            .to_string(),
+                    )
+                }
+                "MDSCR_EL1" => "Monitor debug system control register",
+                "MDCCINT_EL1" => "Debug Communications Channel interrupt enable register",
+                "DBGDSCRext" => "Debug status and control register",
+                "OSDTRRX_EL1" | "OSDTRTX_EL1" | "OSECCR_EL1" | "DBGDTRRXext" | "DBGDTRTXext"
+                | "DBGOSECCR" => "OS lock save and restore register",
+                "DBGCLAIMSET_EL1" | "DBGCLAIMCLR_EL1" | "DBGCLAIMSET" | "DBGCLAIMCLR" => {
+                    "Claim tag register"
+                }
+                "DBGAUTHSTATUS_EL1" | "DBGAUTHSTATUS" => "Authentication status register",
+                "DBGVCR32_EL2" | "DBGVCR" => "Vector catch register",
+                "DBGWFAR" => "Watchpoint fault address register",
+                "DBGDEVID" | "DBGDEVID1" | "DBGDEVID2" => "Debug device ID register",
+                _ => return None,
+            }
+        };
+    Some(format!(
+        "{}, accesses trap to EL2 if MDCR_EL2.TDA or MDCR_EL2.TDE is set, or to EL3 if \
+         MDCR_EL3.TDA is set",
+        description
+    ))
+}
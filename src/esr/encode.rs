@@ -0,0 +1,538 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Construction of ESR values, the reverse of decoding.
+
+use super::abort::{fnv_allowed, SyndromeAccessSize};
+use bit_field::BitField;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// The cause of an instruction or data abort, as reported in the IFSC or DFSC field.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Fault {
+    /// Address size fault at the given level of translation.
+    AddressSize(u8),
+    /// Translation fault at the given level of translation.
+    Translation(u8),
+    /// Access flag fault at the given level of translation.
+    AccessFlag(u8),
+    /// Permission fault at the given level of translation.
+    Permission(u8),
+    /// Synchronous External abort, not on a translation table walk.
+    SynchronousExternal,
+    /// Synchronous Tag Check Fault.
+    TagCheck,
+    /// Synchronous parity or ECC error on memory access, not on a translation table walk.
+    ParityOrEcc,
+    /// Alignment fault.
+    Alignment,
+    /// TLB conflict abort.
+    TlbConflict,
+}
+
+impl Fault {
+    /// Returns the DFSC or IFSC value for the fault.
+    pub fn fsc(self) -> u64 {
+        match self {
+            Self::AddressSize(level) => u64::from(level),
+            Self::Translation(level) => 0b000100 | u64::from(level),
+            Self::AccessFlag(level) => 0b001000 | u64::from(level),
+            Self::Permission(level) => 0b001100 | u64::from(level),
+            Self::SynchronousExternal => 0b010000,
+            Self::TagCheck => 0b010001,
+            Self::ParityOrEcc => 0b011000,
+            Self::Alignment => 0b100001,
+            Self::TlbConflict => 0b110000,
+        }
+    }
+
+    /// Returns whether the fault may be reported with an invalid FAR, which is only the case for
+    /// a Synchronous External abort not on a translation table walk.
+    pub fn allows_invalid_far(self) -> bool {
+        fnv_allowed(self.fsc())
+    }
+}
+
+impl FromStr for Fault {
+    type Err = EncodeError;
+
+    /// Parses a fault such as "translation-3", "permission-1", "alignment" or "external".
+    ///
+    /// Faults which happen at a particular level of translation must have a level from 0 to 3.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        let (kind, level) = match lower.rsplit_once('-') {
+            Some((kind, level)) if level.len() == 1 => {
+                let level = level
+                    .parse()
+                    .ok()
+                    .filter(|&level| level <= 3)
+                    .ok_or_else(|| EncodeError::InvalidFault(s.to_owned()))?;
+                (kind, Some(level))
+            }
+            _ => (lower.as_str(), None),
+        };
+        match (kind, level) {
+            ("address-size", Some(level)) => Ok(Self::AddressSize(level)),
+            ("translation", Some(level)) => Ok(Self::Translation(level)),
+            ("access-flag", Some(level)) => Ok(Self::AccessFlag(level)),
+            ("permission", Some(level)) => Ok(Self::Permission(level)),
+            ("external", None) => Ok(Self::SynchronousExternal),
+            ("tag-check", None) => Ok(Self::TagCheck),
+            ("ecc", None) => Ok(Self::ParityOrEcc),
+            ("alignment", None) => Ok(Self::Alignment),
+            ("tlb-conflict", None) => Ok(Self::TlbConflict),
+            _ => Err(EncodeError::InvalidFault(s.to_owned())),
+        }
+    }
+}
+
+/// An error constructing an ESR value.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum EncodeError {
+    /// The instruction couldn't be parsed.
+    #[error("Invalid instruction {0:?}")]
+    InvalidInstruction(String),
+    /// The instruction isn't a load or store, so can't cause a data abort.
+    #[error("{0:?} is not a load or store instruction")]
+    NotLoadOrStore(String),
+    /// The fault couldn't be parsed.
+    #[error("Invalid fault {0:?}")]
+    InvalidFault(String),
+    /// The description of an exception to inject couldn't be parsed.
+    #[error("Invalid exception to inject {0:?}")]
+    InvalidInjection(String),
+    /// The fault can't be reported with an invalid FAR.
+    #[error(
+        "The FAR must be valid for {0:?}, only synchronous external aborts may have an invalid FAR"
+    )]
+    FarRequired(Fault),
+    /// The fault can't be reported for an instruction abort.
+    #[error("{0:?} can't be reported for an instruction abort")]
+    InvalidInstructionAbortFault(Fault),
+}
+
+/// The instruction syndrome of a data abort, describing the load or store which faulted.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct InstructionSyndrome {
+    /// The size of the access.
+    pub access_size: SyndromeAccessSize,
+    /// Whether the loaded value is sign-extended.
+    pub sign_extend: bool,
+    /// The number of the register being loaded or stored, where 31 is the zero register.
+    pub register: u8,
+    /// Whether the register is 64 bits wide.
+    pub sixty_four: bool,
+    /// Whether the instruction has acquire/release semantics.
+    pub acquire_release: bool,
+}
+
+/// The information needed to construct the ESR value for a Data Abort.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DataAbort {
+    /// The instruction syndrome, if the instruction is one for which it is valid.
+    pub syndrome: Option<InstructionSyndrome>,
+    /// Whether the abort was caused by a write rather than a read.
+    pub write: bool,
+    /// The cause of the abort.
+    pub fault: Fault,
+    /// Whether the FAR is not valid.
+    pub far_not_valid: bool,
+    /// Whether the abort was taken from a lower Exception level.
+    pub lower_el: bool,
+}
+
+impl DataAbort {
+    /// Constructs the data abort which an implementation would report for the given A64 load or
+    /// store instruction, such as "str w3, [x5]", faulting with the given fault.
+    ///
+    /// The instruction syndrome is filled in for loads and stores of a single general-purpose
+    /// register without writeback, other than exclusives, as the architecture requires.
+    pub fn from_instruction(instruction: &str, fault: Fault) -> Result<Self, EncodeError> {
+        let lower = instruction.trim().to_ascii_lowercase();
+        let (mnemonic, operands) = lower
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| EncodeError::InvalidInstruction(instruction.to_owned()))?;
+        if !mnemonic.starts_with("ld") && !mnemonic.starts_with("st") {
+            return Err(EncodeError::NotLoadOrStore(instruction.to_owned()));
+        }
+        let (register, address) = match (operands.split_once(','), operands.find('[')) {
+            (Some((register, _)), Some(address_start)) => {
+                (register.trim(), operands[address_start..].trim())
+            }
+            _ => return Err(EncodeError::InvalidInstruction(instruction.to_owned())),
+        };
+        // Pre-indexed addressing ends with '!', and post-indexed addressing has an offset after
+        // the closing bracket.
+        let writeback = address.ends_with('!') || !address.ends_with(']');
+        let syndrome = if writeback {
+            None
+        } else {
+            instruction_syndrome(mnemonic, register)
+        };
+        Ok(Self {
+            syndrome,
+            write: mnemonic.starts_with("st"),
+            fault,
+            far_not_valid: false,
+            lower_el: false,
+        })
+    }
+
+    /// Returns the ESR value for the data abort.
+    pub fn encode(&self) -> u64 {
+        let mut esr = 0;
+        esr.set_bits(26..32, if self.lower_el { 0b100100 } else { 0b100101 });
+        // All A64 instructions are 32 bits.
+        esr.set_bit(25, true);
+        if let Some(syndrome) = &self.syndrome {
+            esr.set_bit(24, true);
+            esr.set_bits(22..24, syndrome.access_size as u64);
+            esr.set_bit(21, syndrome.sign_extend);
+            esr.set_bits(16..21, u64::from(syndrome.register));
+            esr.set_bit(15, syndrome.sixty_four);
+            esr.set_bit(14, syndrome.acquire_release);
+        }
+        esr.set_bit(10, self.far_not_valid);
+        esr.set_bit(6, self.write);
+        esr.set_bits(0..6, self.fault.fsc());
+        esr
+    }
+}
+
+/// An exception which a hypervisor wants to inject into a guest, as the guest should observe it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Injection {
+    /// A Data Abort.
+    DataAbort {
+        /// The cause of the abort.
+        fault: Fault,
+        /// Whether the abort was caused by a write rather than a read.
+        write: bool,
+        /// Whether the FAR value given to the guest is valid.
+        far_valid: bool,
+        /// Whether the abort is taken from the guest's EL0 rather than EL1.
+        from_el0: bool,
+    },
+    /// An Instruction Abort.
+    InstructionAbort {
+        /// The cause of the abort.
+        fault: Fault,
+        /// Whether the FAR value given to the guest is valid.
+        far_valid: bool,
+        /// Whether the abort is taken from the guest's EL0 rather than EL1.
+        from_el0: bool,
+    },
+    /// An uncategorized SError interrupt.
+    SError,
+}
+
+impl Injection {
+    /// Returns the ESR_EL1 value to give the guest for the injected exception, or an error if the
+    /// combination of fields isn't one which the architecture allows.
+    ///
+    /// Injected aborts never have a valid instruction syndrome, as the hypervisor doesn't generally
+    /// know which register the guest was accessing, so IL is always set as the architecture
+    /// requires when ISV is 0. For an SError, bits 24:0 of the value are what should be written to
+    /// VSESR_EL2.
+    pub fn encode(&self) -> Result<u64, EncodeError> {
+        match *self {
+            Self::DataAbort {
+                fault,
+                write,
+                far_valid,
+                from_el0,
+            } => {
+                check_far(fault, far_valid)?;
+                Ok(DataAbort {
+                    syndrome: None,
+                    write,
+                    fault,
+                    far_not_valid: !far_valid,
+                    lower_el: from_el0,
+                }
+                .encode())
+            }
+            Self::InstructionAbort {
+                fault,
+                far_valid,
+                from_el0,
+            } => {
+                if matches!(fault, Fault::Alignment | Fault::TagCheck) {
+                    return Err(EncodeError::InvalidInstructionAbortFault(fault));
+                }
+                check_far(fault, far_valid)?;
+                let mut esr = 0;
+                esr.set_bits(26..32, if from_el0 { 0b100000 } else { 0b100001 });
+                esr.set_bit(25, true);
+                esr.set_bit(10, !far_valid);
+                esr.set_bits(0..6, fault.fsc());
+                Ok(esr)
+            }
+            Self::SError => {
+                let mut esr = 0;
+                esr.set_bits(26..32, 0b101111);
+                esr.set_bit(25, true);
+                Ok(esr)
+            }
+        }
+    }
+}
+
+impl FromStr for Injection {
+    type Err = EncodeError;
+
+    /// Parses a comma-separated description of an exception to inject, such as
+    /// "data,translation-3,write", "instruction,permission-1,el0" or "serror".
+    ///
+    /// The first item is the kind of exception, and the second is the fault for aborts. The
+    /// remaining items are optional: "write" or "read" for data aborts, "far-invalid" if the FAR
+    /// isn't valid, and "el0" if the exception is taken from EL0.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || EncodeError::InvalidInjection(s.to_owned());
+        let mut items = s.split(',').map(|item| item.trim().to_ascii_lowercase());
+        let kind = items.next().ok_or_else(invalid)?;
+        if kind == "serror" {
+            return if items.next().is_none() {
+                Ok(Self::SError)
+            } else {
+                Err(invalid())
+            };
+        }
+        let fault = items.next().ok_or_else(invalid)?.parse()?;
+        let mut write = false;
+        let mut far_valid = true;
+        let mut from_el0 = false;
+        for item in items {
+            match item.as_str() {
+                "write" if kind == "data" => write = true,
+                "read" if kind == "data" => write = false,
+                "far-invalid" => far_valid = false,
+                "el0" => from_el0 = true,
+                _ => return Err(invalid()),
+            }
+        }
+        match kind.as_str() {
+            "data" => Ok(Self::DataAbort {
+                fault,
+                write,
+                far_valid,
+                from_el0,
+            }),
+            "instruction" => Ok(Self::InstructionAbort {
+                fault,
+                far_valid,
+                from_el0,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Checks that the FAR is valid if it is required to be for the given fault.
+fn check_far(fault: Fault, far_valid: bool) -> Result<(), EncodeError> {
+    if far_valid || fault.allows_invalid_far() {
+        Ok(())
+    } else {
+        Err(EncodeError::FarRequired(fault))
+    }
+}
+
+/// Returns the instruction syndrome for the given load or store mnemonic and general-purpose
+/// register operand, or `None` if the instruction syndrome isn't valid for it.
+fn instruction_syndrome(mnemonic: &str, register: &str) -> Option<InstructionSyndrome> {
+    let (register, sixty_four) = match register {
+        "wzr" => (31, false),
+        "xzr" => (31, true),
+        _ => {
+            let sixty_four = match register.chars().next()? {
+                'w' => false,
+                'x' => true,
+                // SIMD and floating-point registers don't have an instruction syndrome.
+                _ => return None,
+            };
+            let number: u8 = register[1..].parse().ok()?;
+            if number > 30 {
+                return None;
+            }
+            (number, sixty_four)
+        }
+    };
+    let (acquire_release, base) = if let Some(base) = mnemonic
+        .strip_prefix("ldapr")
+        .or_else(|| mnemonic.strip_prefix("ldar"))
+        .or_else(|| mnemonic.strip_prefix("stlr"))
+    {
+        (true, base)
+    } else if let Some(base) = mnemonic
+        .strip_prefix("ldur")
+        .or_else(|| mnemonic.strip_prefix("ldr"))
+        .or_else(|| mnemonic.strip_prefix("stur"))
+        .or_else(|| mnemonic.strip_prefix("str"))
+    {
+        (false, base)
+    } else {
+        // Exclusives, pairs and other loads and stores don't have an instruction syndrome.
+        return None;
+    };
+    let full_size = if sixty_four {
+        SyndromeAccessSize::Doubleword
+    } else {
+        SyndromeAccessSize::Word
+    };
+    let (access_size, sign_extend) = match base {
+        "" => (full_size, false),
+        "b" => (SyndromeAccessSize::Byte, false),
+        "h" => (SyndromeAccessSize::Halfword, false),
+        "sb" if !acquire_release => (SyndromeAccessSize::Byte, true),
+        "sh" if !acquire_release => (SyndromeAccessSize::Halfword, true),
+        "sw" if !acquire_release && sixty_four => (SyndromeAccessSize::Word, true),
+        _ => return None,
+    };
+    Some(InstructionSyndrome {
+        access_size,
+        sign_extend,
+        register,
+        sixty_four,
+        acquire_release,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode, decode_lenient, DecodeOptions};
+
+    fn encode(instruction: &str, fault: &str) -> u64 {
+        DataAbort::from_instruction(instruction, fault.parse().unwrap())
+            .unwrap()
+            .encode()
+    }
+
+    #[test]
+    fn store_word() {
+        let esr = encode("str w3, [x5]", "translation-3");
+        assert_eq!(esr, 0x97830047);
+        let decoded = decode(esr).unwrap();
+        let iss = &decoded[4].subfields;
+        assert_eq!(iss[0].name, "ISV");
+        assert!(iss[0].as_bit());
+        assert_eq!(iss[1].description.as_deref(), Some("word"));
+        assert_eq!(iss[3].description.as_deref(), Some("w3"));
+    }
+
+    #[test]
+    fn loads() {
+        // Sign-extending byte load into a 64-bit register.
+        assert_eq!(encode("ldrsb x1, [x2, #4]", "permission-2"), 0x9721800e);
+        // Load-acquire of a halfword.
+        assert_eq!(encode("ldarh w0, [x1]", "access-flag-1"), 0x97404009);
+        // Zero register.
+        assert_eq!(encode("str xzr, [sp]", "alignment"), 0x97df8061);
+    }
+
+    #[test]
+    fn no_syndrome() {
+        // Writeback, pairs, exclusives and SIMD registers have no instruction syndrome.
+        assert_eq!(encode("ldr x0, [x1], #8", "translation-0"), 0x96000004);
+        assert_eq!(encode("str x0, [x1, #8]!", "translation-0"), 0x96000044);
+        assert_eq!(encode("stp x0, x1, [sp]", "translation-0"), 0x96000044);
+        assert_eq!(encode("ldxr x0, [x1]", "translation-0"), 0x96000004);
+        assert_eq!(encode("ldr q0, [x1]", "translation-0"), 0x96000004);
+    }
+
+    #[test]
+    fn inject() {
+        let inject = |description: &str| description.parse::<Injection>().unwrap().encode();
+        assert_eq!(inject("data,translation-3,write"), Ok(0x96000047));
+        assert_eq!(inject("data, translation-3, read, el0"), Ok(0x92000007));
+        assert_eq!(inject("data,external,far-invalid"), Ok(0x96000410));
+        assert_eq!(inject("instruction,permission-1,el0"), Ok(0x8200000d));
+        assert_eq!(inject("serror"), Ok(0xbe000000));
+        assert_eq!(
+            inject("data,permission-2,far-invalid"),
+            Err(EncodeError::FarRequired(Fault::Permission(2)))
+        );
+        assert_eq!(
+            inject("data,ecc,far-invalid"),
+            Err(EncodeError::FarRequired(Fault::ParityOrEcc))
+        );
+        assert_eq!(
+            inject("instruction,alignment"),
+            Err(EncodeError::InvalidInstructionAbortFault(Fault::Alignment))
+        );
+        assert_eq!(
+            "instruction,translation-1,write".parse::<Injection>(),
+            Err(EncodeError::InvalidInjection(
+                "instruction,translation-1,write".to_string()
+            ))
+        );
+        for description in &[
+            "data,translation-3,write",
+            "data,external,far-invalid",
+            "instruction,permission-1,el0",
+            "serror",
+        ] {
+            assert!(decode(inject(description).unwrap()).is_ok());
+        }
+    }
+
+    #[test]
+    fn inject_consistent() {
+        // Every exception which can be injected must decode without any inconsistency.
+        let faults = [
+            "address-size-0",
+            "translation-1",
+            "access-flag-2",
+            "permission-3",
+            "external",
+            "tag-check",
+            "ecc",
+            "alignment",
+            "tlb-conflict",
+        ];
+        for kind in &["data", "instruction"] {
+            for fault in &faults {
+                for options in &["", ",far-invalid", ",el0", ",far-invalid,el0"] {
+                    let description = format!("{},{}{}", kind, fault, options);
+                    if let Ok(esr) = description.parse::<Injection>().unwrap().encode() {
+                        let decoded = decode_lenient(esr, &DecodeOptions::default());
+                        assert_eq!(decoded.warnings, vec![], "{}", description);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            DataAbort::from_instruction("add x0, x1, x2", Fault::Alignment),
+            Err(EncodeError::NotLoadOrStore("add x0, x1, x2".to_string()))
+        );
+        assert_eq!(
+            DataAbort::from_instruction("ldr", Fault::Alignment),
+            Err(EncodeError::InvalidInstruction("ldr".to_string()))
+        );
+        assert_eq!(
+            "translation-4".parse::<Fault>(),
+            Err(EncodeError::InvalidFault("translation-4".to_string()))
+        );
+        assert_eq!(
+            "translation".parse::<Fault>(),
+            Err(EncodeError::InvalidFault("translation".to_string()))
+        );
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{DecodeError, FieldInfo};
+
+/// Decodes the ISS value for a trapped ERET, ERETAA or ERETAB instruction, and describes which
+/// instruction was trapped.
+pub fn decode_iss_eret(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+    let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 2, 25).check_res0()?;
+    let eret =
+        FieldInfo::get_bit(iss, "ERET", Some("Exception Return"), 1).describe_bit(describe_eret);
+    let ereta = FieldInfo::get_bit(iss, "ERETA", Some("Exception Return with key A or B"), 0);
+
+    let instruction = if eret.as_bit() {
+        if ereta.as_bit() {
+            "ERETAB"
+        } else {
+            "ERETAA"
+        }
+    } else {
+        "ERET"
+    };
+    let ereta = if eret.as_bit() {
+        ereta.describe_bit(describe_ereta)
+    } else {
+        ereta
+    };
+    let description = format!("{} instruction trapped", instruction);
+
+    Ok((vec![res0, eret, ereta], Some(description)))
+}
+
+fn describe_eret(eret: bool) -> &'static str {
+    if eret {
+        "ERETAA or ERETAB instruction"
+    } else {
+        "ERET instruction"
+    }
+}
+
+fn describe_ereta(ereta: bool) -> &'static str {
+    if ereta {
+        "ERETAB instruction, authenticating with key B"
+    } else {
+        "ERETAA instruction, authenticating with key A"
+    }
+}
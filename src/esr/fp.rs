@@ -14,13 +14,34 @@
 
 use crate::{DecodeError, FieldInfo};
 
-/// Decodes the ISS value for a floating-point exception.
-pub fn decode_iss_fp(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+/// Decodes the ISS value for a floating-point exception taken from AArch64 state, and describes
+/// which exceptions occurred.
+pub fn decode_iss_fp(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+    decode_iss_fp_common(iss, "UNKNOWN for exceptions taken from AArch64 state")
+}
+
+/// Decodes the ISS value for a floating-point exception taken from AArch32 state, and describes
+/// which exceptions occurred.
+///
+/// VECITR would give the element of a short vector which trapped, but short vectors aren't
+/// supported from Armv8 so it is RES1 or UNKNOWN.
+pub fn decode_iss_fp_aarch32(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+    decode_iss_fp_common(
+        iss,
+        "RES1 or UNKNOWN, as short vector operations are not supported",
+    )
+}
+
+fn decode_iss_fp_common(
+    iss: u64,
+    vecitr_description: &str,
+) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
     let res0a = FieldInfo::get_bit(iss, "RES0", Some("Reserved"), 24).check_res0()?;
     let tfv =
         FieldInfo::get_bit(iss, "TFV", Some("Trapped Fault Valid"), 23).describe_bit(describe_tfv);
     let res0b = FieldInfo::get(iss, "RES0", Some("Reserved"), 11, 23).check_res0()?;
-    let vecitr = FieldInfo::get(iss, "VECITR", Some("RES1 or UNKNOWN"), 8, 11);
+    let vecitr = FieldInfo::get(iss, "VECITR", Some("Vector iteration"), 8, 11)
+        .with_description(vecitr_description.to_string());
     let idf = FieldInfo::get_bit(iss, "IDF", Some("Input Denormal"), 7).describe_bit(describe_idf);
     let res0c = FieldInfo::get(iss, "RES0", Some("Reserved"), 5, 7).check_res0()?;
     let ixf = FieldInfo::get_bit(iss, "IXF", Some("Inexact"), 4).describe_bit(describe_ixf);
@@ -30,9 +51,36 @@ pub fn decode_iss_fp(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     let iof =
         FieldInfo::get_bit(iss, "IOF", Some("Invalid Operation"), 0).describe_bit(describe_iof);
 
-    Ok(vec![
-        res0a, tfv, res0b, vecitr, idf, res0c, ixf, uff, off, dzf, iof,
-    ])
+    let description = describe_exceptions(tfv.as_bit(), &[&iof, &dzf, &off, &uff, &ixf, &idf]);
+
+    Ok((
+        vec![
+            res0a, tfv, res0b, vecitr, idf, res0c, ixf, uff, off, dzf, iof,
+        ],
+        description,
+    ))
+}
+
+/// Lists which of the given exception flags are set, if they are valid.
+fn describe_exceptions(tfv: bool, flags: &[&FieldInfo]) -> Option<String> {
+    if !tfv {
+        return Some("Which floating-point exceptions occurred is not known".to_string());
+    }
+    let names: Vec<_> = flags
+        .iter()
+        .filter(|flag| flag.as_bit())
+        .filter_map(|flag| flag.long_name)
+        .collect();
+    let list = match names.split_last() {
+        None => return None,
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+    };
+    Some(format!(
+        "{} floating-point exception{} occurred",
+        list,
+        if names.len() > 1 { "s" } else { "" }
+    ))
 }
 
 fn describe_tfv(tfv: bool) -> &'static str {
@@ -0,0 +1,92 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::common::describe_register;
+use crate::{DecodeError, FieldInfo};
+
+/// Decodes the ISS value for a Guarded Control Stack exception.
+pub fn decode_iss_gcs(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0a = FieldInfo::get_bit(iss, "RES0", Some("Reserved"), 24).check_res0()?;
+    let ex_type =
+        FieldInfo::get(iss, "ExType", Some("Exception Type"), 20, 24).describe(describe_ex_type)?;
+    let res0b = FieldInfo::get(iss, "RES0", Some("Reserved"), 15, 20).check_res0()?;
+
+    let mut fields = vec![res0a, ex_type.clone(), res0b];
+    match ex_type.value {
+        // GCS data check exception.
+        0b0000 => {
+            let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 10, 15).check_res0()?;
+            let rn = describe_register(FieldInfo::get(
+                iss,
+                "Rn",
+                Some("Register holding the expected return address"),
+                5,
+                10,
+            ));
+            let it =
+                FieldInfo::get(iss, "IT", Some("Instruction Type"), 0, 5).describe(describe_it)?;
+            fields.extend([res0, rn, it]);
+        }
+        // Trapped GCSSTR or GCSSTTR.
+        0b0010 => {
+            let raddr = describe_register(FieldInfo::get(
+                iss,
+                "Raddr",
+                Some("Register holding the address"),
+                10,
+                15,
+            ));
+            let rvalue = describe_register(FieldInfo::get(
+                iss,
+                "Rvalue",
+                Some("Register holding the value to store"),
+                5,
+                10,
+            ));
+            let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 0, 5).check_res0()?;
+            fields.extend([raddr, rvalue, res0]);
+        }
+        // EXLOCK exception.
+        _ => {
+            fields.push(FieldInfo::get(iss, "RES0", Some("Reserved"), 0, 15).check_res0()?);
+        }
+    }
+
+    Ok(fields)
+}
+
+fn describe_ex_type(ex_type: u64) -> Result<&'static str, DecodeError> {
+    match ex_type {
+        0b0000 => Ok(
+            "GCS data check exception, the return address didn't match the Guarded Control Stack",
+        ),
+        0b0001 => Ok("EXLOCK exception, GCSCR_ELx.EXLOCKEN is set and PSTATE.EXLOCK was set"),
+        0b0010 => Ok("Trapped GCSSTR or GCSSTTR instruction"),
+        _ => Err(DecodeError::InvalidExType { ex_type }),
+    }
+}
+
+fn describe_it(it: u64) -> Result<&'static str, DecodeError> {
+    match it {
+        0b00000 => Ok("RET"),
+        0b00001 => Ok("GCSPOPM"),
+        0b00010 => Ok("RETAA"),
+        0b00011 => Ok("RETAB"),
+        0b00100 => Ok("GCSSS1"),
+        0b00101 => Ok("GCSSS2"),
+        0b01000 => Ok("GCSPOPCX"),
+        0b01001 => Ok("GCSPOPX"),
+        _ => Err(DecodeError::InvalidIt { it }),
+    }
+}
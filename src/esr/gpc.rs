@@ -0,0 +1,85 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::abort::{describe_fsc, describe_vncr};
+use crate::{DecodeError, FieldInfo};
+
+/// Decodes the ISS value for a Granule Protection Check exception.
+pub fn decode_iss_gpc(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0a = FieldInfo::get(iss, "RES0", Some("Reserved"), 22, 25).check_res0()?;
+    let s2ptw = FieldInfo::get_bit(iss, "S2PTW", Some("Stage 2 translation table walk"), 21)
+        .describe_bit(describe_s2ptw);
+    let ind =
+        FieldInfo::get_bit(iss, "InD", Some("Instruction not Data"), 20).describe_bit(describe_ind);
+    let gpcsc = FieldInfo::get(
+        iss,
+        "GPCSC",
+        Some("Granule Protection Check Status Code"),
+        14,
+        20,
+    )
+    .describe(describe_gpcsc)?;
+    let vncr = FieldInfo::get_bit(iss, "VNCR", None, 13).describe_bit(describe_vncr);
+    let res0b = FieldInfo::get(iss, "RES0", Some("Reserved"), 9, 13).check_res0()?;
+    let cm = FieldInfo::get_bit(iss, "CM", Some("Cache Maintenance"), 8);
+    let s1ptw = FieldInfo::get_bit(iss, "S1PTW", Some("Stage-1 translation table walk"), 7);
+    let wnr = FieldInfo::get_bit(iss, "WnR", Some("Write not Read"), 6).describe_bit(describe_wnr);
+    let xfsc = FieldInfo::get(
+        iss,
+        "xFSC",
+        Some("Instruction or Data Fault Status Code"),
+        0,
+        6,
+    )
+    .describe(describe_fsc)?;
+
+    Ok(vec![
+        res0a, s2ptw, ind, gpcsc, vncr, res0b, cm, s1ptw, wnr, xfsc,
+    ])
+}
+
+fn describe_s2ptw(s2ptw: bool) -> &'static str {
+    if s2ptw {
+        "Fault on a stage 2 translation table walk"
+    } else {
+        "Fault not on a stage 2 translation table walk"
+    }
+}
+
+fn describe_ind(ind: bool) -> &'static str {
+    if ind {
+        "Fault on an instruction fetch"
+    } else {
+        "Fault on a data access"
+    }
+}
+
+fn describe_wnr(wnr: bool) -> &'static str {
+    if wnr {
+        "Fault caused by writing to memory"
+    } else {
+        "Fault caused by reading from memory"
+    }
+}
+
+fn describe_gpcsc(gpcsc: u64) -> Result<&'static str, DecodeError> {
+    match gpcsc {
+        0b000000 => Ok("GPT address size fault at level 0"),
+        0b000100 => Ok("GPT walk fault at level 0"),
+        0b000101 => Ok("GPT walk fault at level 1"),
+        0b010100 => Ok("Synchronous External abort on GPT fetch at level 0"),
+        0b010101 => Ok("Synchronous External abort on GPT fetch at level 1"),
+        _ => Err(DecodeError::InvalidGpcsc { gpcsc }),
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::common::describe_cv;
 use crate::{DecodeError, FieldInfo};
 
 /// Decodes the ISS value for an HVC or SVC exception.
@@ -21,3 +22,29 @@ pub fn decode_iss_hvc(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
 
     Ok(vec![res0, imm16])
 }
+
+/// Decodes the ISS value for an SMC exception taken from AArch32 state.
+pub fn decode_iss_smc_aarch32(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let cv =
+        FieldInfo::get_bit(iss, "CV", Some("Condition code valid"), 24).describe_bit(describe_cv);
+    let cond = FieldInfo::get(
+        iss,
+        "COND",
+        Some("Condition code of the trapped instruction"),
+        20,
+        24,
+    );
+    let ccknownpass = FieldInfo::get_bit(iss, "CCKNOWNPASS", Some("Condition code known pass"), 19)
+        .describe_bit(describe_ccknownpass);
+    let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 0, 19).check_res0()?;
+
+    Ok(vec![cv, cond, ccknownpass, res0])
+}
+
+fn describe_ccknownpass(ccknownpass: bool) -> &'static str {
+    if ccknownpass {
+        "Instruction was conditional, and might have failed its condition code check"
+    } else {
+        "Instruction was unconditional, or was conditional and passed its condition code check"
+    }
+}
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use super::common::describe_cv;
-use crate::{DecodeError, FieldInfo};
+use crate::{DecodeError, FieldInfo, Unit};
 
 /// Decodes the ISS value for a trapped LDC or STC instruction.
 pub fn decode_iss_ldc(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
@@ -49,6 +49,7 @@ pub fn decode_iss_ldc(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     )
     .describe_bit(describe_offset);
     let am = FieldInfo::get(iss, "AM", Some("Addressing Mode"), 1, 4).describe(describe_am)?;
+    let imm8 = describe_imm8(imm8, am.value, offset.as_bit());
     let direction = FieldInfo::get_bit(
         iss,
         "Direction",
@@ -60,6 +61,25 @@ pub fn decode_iss_ldc(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     Ok(vec![cv, cond, imm8, res0, rn, offset, am, direction])
 }
 
+/// Describes the imm8 field according to the addressing mode, as either the signed byte offset
+/// from the base register or PC, or an option passed to the coprocessor.
+fn describe_imm8(imm8: FieldInfo, am: u64, add: bool) -> FieldInfo {
+    match am {
+        0b000 | 0b100 => {
+            let description = format!("Option {}, passed to the coprocessor", imm8.value);
+            imm8.with_description(description)
+        }
+        0b001..=0b011 | 0b110 => {
+            // The immediate is a number of words.
+            let offset = imm8.value as i64 * 4;
+            let offset = if add { offset } else { -offset };
+            imm8.with_description(format!("Offset of {} bytes", offset))
+                .with_derived(offset, Unit::Bytes)
+        }
+        _ => imm8,
+    }
+}
+
 fn describe_offset(offset: bool) -> &'static str {
     if offset {
         "Add offset"
@@ -70,12 +90,20 @@ fn describe_offset(offset: bool) -> &'static str {
 
 fn describe_am(am: u64) -> Result<&'static str, DecodeError> {
     match am {
-        0b000 => Ok("Immediate unindexed"),
-        0b001 => Ok("Immediate post-indexed"),
-        0b010 => Ok("Immediate offset"),
-        0b011 => Ok("Immediate pre-indexed"),
-        0b100 => Ok("Reserved for trapped STR or T32 LDC"),
-        0b110 => Ok("Reserved for trapped STC"),
+        0b000 => Ok("Immediate unindexed, accessing the address in Rn without write-back"),
+        0b001 => Ok(
+            "Immediate post-indexed, accessing the address in Rn and writing back Rn plus the offset",
+        ),
+        0b010 => Ok("Immediate offset, accessing Rn plus the offset without write-back"),
+        0b011 => Ok("Immediate pre-indexed, accessing Rn plus the offset and writing it back to Rn"),
+        0b100 => Ok(
+            "Literal unindexed, accessing the PC-relative address without write-back (reserved \
+             for trapped STC or T32 LDC)",
+        ),
+        0b110 => Ok(
+            "Literal offset, accessing the aligned PC plus the offset without write-back \
+             (reserved for trapped STC)",
+        ),
         _ => Err(DecodeError::InvalidAm { am }),
     }
 }
@@ -0,0 +1,225 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lenient decoding, which reports problems as warnings rather than failing.
+
+use super::abort::fnv_allowed;
+use super::{decode_with_options, DecodeOptions};
+use crate::{DecodeError, FieldInfo};
+use bit_field::BitField;
+use thiserror::Error;
+
+/// A decoded register value, along with any anomalies found while decoding it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Decoded {
+    /// The decoded fields.
+    pub fields: Vec<FieldInfo>,
+    /// Any anomalies found in the value, which may mean that some fields couldn't be decoded.
+    pub warnings: Vec<Warning>,
+}
+
+/// An anomaly found while decoding a register value.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum Warning {
+    /// A reserved field which should be zero was not.
+    #[error("RES0 field is {res0:#x}")]
+    Res0Set { res0: u64 },
+    /// A field had a value which isn't allocated by the architecture.
+    #[error("Unallocated encoding {value:#x} for {field}")]
+    UnallocatedEncoding { field: &'static str, value: u64 },
+    /// FnV was set, but the fault isn't an external abort so the FAR must be valid.
+    #[error("FnV is set, but FSC {fsc:#x} is not an external abort so FnV should be 0")]
+    InconsistentFnv { fsc: u64 },
+}
+
+impl From<&DecodeError> for Warning {
+    fn from(error: &DecodeError) -> Self {
+        match *error {
+            DecodeError::InvalidRes0 { res0 } => Self::Res0Set { res0 },
+            DecodeError::InvalidEc { ec } => Self::UnallocatedEncoding {
+                field: "EC",
+                value: ec,
+            },
+            DecodeError::InvalidFsc { fsc } => Self::UnallocatedEncoding {
+                field: "FSC",
+                value: fsc,
+            },
+            DecodeError::InvalidSet { set } => Self::UnallocatedEncoding {
+                field: "SET",
+                value: set,
+            },
+            DecodeError::InvalidAet { aet } => Self::UnallocatedEncoding {
+                field: "AET",
+                value: aet,
+            },
+            DecodeError::InvalidAm { am } => Self::UnallocatedEncoding {
+                field: "AM",
+                value: am,
+            },
+            DecodeError::InvalidLd64bIss { iss } => Self::UnallocatedEncoding {
+                field: "ISS",
+                value: iss,
+            },
+            DecodeError::InvalidSmtc { smtc } => Self::UnallocatedEncoding {
+                field: "SMTC",
+                value: smtc,
+            },
+            DecodeError::InvalidGpcsc { gpcsc } => Self::UnallocatedEncoding {
+                field: "GPCSC",
+                value: gpcsc,
+            },
+            DecodeError::InvalidExType { ex_type } => Self::UnallocatedEncoding {
+                field: "ExType",
+                value: ex_type,
+            },
+            DecodeError::InvalidIt { it } => Self::UnallocatedEncoding {
+                field: "IT",
+                value: it,
+            },
+            DecodeError::InvalidLevel { level } => Self::UnallocatedEncoding {
+                field: "Level",
+                value: level,
+            },
+        }
+    }
+}
+
+/// Decodes the given Exception Syndrome Register value with the given options, decoding as much as
+/// possible rather than failing if it is not valid.
+///
+/// If the top-level RES0 field is set then it is ignored. If the ISS can't be decoded then it is
+/// left without subfields. Each problem is reported as a warning, as are inconsistencies between
+/// fields which strict decoding doesn't check.
+pub fn decode_lenient(esr: u64, options: &DecodeOptions) -> Decoded {
+    let mut warnings = vec![];
+    let res0 = esr.get_bits(56..64);
+    if res0 != 0 {
+        warnings.push(Warning::Res0Set { res0 });
+    }
+    let without_res0 = esr.get_bits(0..56);
+    let fields = match decode_with_options(without_res0, options) {
+        Ok(fields) => fields,
+        Err(e) => {
+            warnings.push((&e).into());
+            decode_without_iss(without_res0, options)
+        }
+    };
+    let fields = restore_res0(fields, res0);
+    warnings.extend(check_fnv(&fields));
+    Decoded { fields, warnings }
+}
+
+/// Decodes the given ESR value without decoding its ISS, for when the ISS is not valid.
+fn decode_without_iss(esr: u64, options: &DecodeOptions) -> Vec<FieldInfo> {
+    let iss = FieldInfo::get(esr, "ISS", Some("Instruction Specific Syndrome"), 0, 25);
+    // Decode with a zero ISS to describe the other fields, if the EC is valid.
+    let mut fields = decode_with_options(esr & !0x1ff_ffff, options).unwrap_or_else(|_| {
+        vec![
+            FieldInfo::get(esr, "RES0", Some("Reserved"), 56, 64),
+            FieldInfo::get(esr, "ISS2", None, 32, 56),
+            FieldInfo::get(esr, "EC", Some("Exception Class"), 26, 32)
+                .with_description("Unallocated exception class".to_string()),
+            FieldInfo::get_bit(esr, "IL", Some("Instruction Length"), 25),
+        ]
+    });
+    fields.retain(|field| field.name != "ISS");
+    fields.push(iss);
+    fields
+}
+
+/// Replaces the value of the top-level RES0 field, which was cleared before decoding.
+fn restore_res0(mut fields: Vec<FieldInfo>, res0: u64) -> Vec<FieldInfo> {
+    if let Some(field) = fields.iter_mut().find(|field| field.name == "RES0") {
+        field.value = res0;
+    }
+    fields
+}
+
+/// Checks that FnV is only set for external aborts.
+fn check_fnv(fields: &[FieldInfo]) -> Option<Warning> {
+    let iss = &fields.iter().find(|field| field.name == "ISS")?.subfields;
+    let fnv = iss.iter().find(|field| field.name == "FnV")?;
+    let fsc = iss
+        .iter()
+        .find(|field| field.name == "DFSC" || field.name == "IFSC")?
+        .value;
+    if fnv.as_bit() && !fnv_allowed(fsc) {
+        Some(Warning::InconsistentFnv { fsc })
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        let decoded = decode_lenient(0x96000050, &DecodeOptions::default());
+        assert_eq!(decoded.warnings, vec![]);
+        assert_eq!(decoded.fields[4].subfields.len(), 10);
+    }
+
+    #[test]
+    fn res0_set() {
+        let decoded = decode_lenient(0x0800_0000_9600_0050, &DecodeOptions::default());
+        assert_eq!(decoded.warnings, vec![Warning::Res0Set { res0: 0x8 }]);
+        assert_eq!(decoded.fields[0].value, 0x8);
+        assert_eq!(decoded.fields[4].subfields.len(), 10);
+    }
+
+    #[test]
+    fn invalid_iss() {
+        // Data abort with an unallocated DFSC.
+        let decoded = decode_lenient(0x96000032, &DecodeOptions::default());
+        assert_eq!(
+            decoded.warnings,
+            vec![Warning::UnallocatedEncoding {
+                field: "FSC",
+                value: 0x32,
+            }]
+        );
+        assert_eq!(
+            decoded.fields[2].description.as_deref(),
+            Some("Data Abort taken without a change in Exception level")
+        );
+        assert_eq!(decoded.fields[4].name, "ISS");
+        assert_eq!(decoded.fields[4].value, 0x32);
+        assert_eq!(decoded.fields[4].subfields, vec![]);
+    }
+
+    #[test]
+    fn invalid_ec() {
+        let decoded = decode_lenient(0xfe000000, &DecodeOptions::default());
+        assert_eq!(
+            decoded.warnings,
+            vec![Warning::UnallocatedEncoding {
+                field: "EC",
+                value: 0x3f,
+            }]
+        );
+        assert_eq!(decoded.fields.len(), 5);
+    }
+
+    #[test]
+    fn inconsistent_fnv() {
+        // Translation fault with FnV set.
+        let decoded = decode_lenient(0x96000407, &DecodeOptions::default());
+        assert_eq!(
+            decoded.warnings,
+            vec![Warning::InconsistentFnv { fsc: 0b000111 }]
+        );
+    }
+}
@@ -12,19 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::common::describe_cv;
+use super::common::{describe_cv, register_name, with_cond_mnemonic};
+use super::cp15::{cp15_register, cp15_register_64};
+use super::debugreg::{debug_cp14_64_name, debug_cp14_name, debug_note};
+use super::ExecutionState;
 use crate::{DecodeError, FieldInfo};
 
 /// Decodes the ISS value for an MCR or MRC access.
 pub fn decode_iss_mcr(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     let cv =
         FieldInfo::get_bit(iss, "CV", Some("Condition code valid"), 24).describe_bit(describe_cv);
-    let cond = FieldInfo::get(
-        iss,
-        "COND",
-        Some("Condition code of the trapped instruction"),
-        20,
-        24,
+    let cond = with_cond_mnemonic(
+        FieldInfo::get(
+            iss,
+            "COND",
+            Some("Condition code of the trapped instruction"),
+            20,
+            24,
+        ),
+        cv.as_bit(),
     );
     let opc2 = FieldInfo::get(iss, "Opc2", None, 17, 20);
     let opc1 = FieldInfo::get(iss, "Opc1", None, 14, 17);
@@ -38,22 +44,122 @@ pub fn decode_iss_mcr(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
         0,
     )
     .describe_bit(describe_direction);
+    let rt_description = describe_mcr_rt(rt.value, direction.as_bit());
+    let rt = rt.with_description(rt_description);
 
     Ok(vec![cv, cond, opc2, opc1, crn, rt, crm, direction])
 }
 
+/// Decodes the ISS value for an MCR or MRC access with coproc=0b1111, identifying the System
+/// register accessed and its AArch64 equivalent if it is a known one.
+pub fn decode_iss_mcr_cp15(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+    let fields = decode_iss_mcr(iss)?;
+    let (opc2, opc1, crn, _, crm, _) = mcr_encoding(iss);
+    let description = cp15_register(opc1, crn, crm, opc2).map(|(name, aarch64)| {
+        let instruction = mcr_instruction(15, iss);
+        match aarch64 {
+            Some(aarch64) => format!(
+                "{} accessing {} (AArch64 equivalent {})",
+                instruction, name, aarch64
+            ),
+            None => format!("{} accessing {}", instruction, name),
+        }
+    });
+
+    Ok((fields, description))
+}
+
+/// Decodes the ISS value for an MCR or MRC access with coproc=0b1110, identifying the debug
+/// register accessed if it is one.
+pub fn decode_iss_mcr_cp14(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+    let fields = decode_iss_mcr(iss)?;
+    let (opc2, opc1, crn, _, crm, read) = mcr_encoding(iss);
+    let description = debug_cp14_name(opc1, crn, crm, opc2, read).map(|name| {
+        let instruction = mcr_instruction(14, iss);
+        match debug_note(&name) {
+            Some(note) => format!("{} accessing {} ({})", instruction, name, note),
+            None => format!("{} accessing {}", instruction, name),
+        }
+    });
+
+    Ok((fields, description))
+}
+
+/// Returns the Opc2, Opc1, CRn, Rt and CRm fields and the direction of the given MCR or MRC ISS
+/// value, where the direction is true for a read.
+fn mcr_encoding(iss: u64) -> (u64, u64, u64, u64, u64, bool) {
+    (
+        iss >> 17 & 0b111,
+        iss >> 14 & 0b111,
+        iss >> 10 & 0b1111,
+        iss >> 5 & 0b11111,
+        iss >> 1 & 0b1111,
+        iss & 0b1 == 1,
+    )
+}
+
+/// Formats the MCR or MRC instruction with the given coprocessor number which the given ISS value
+/// describes.
+fn mcr_instruction(coproc: u8, iss: u64) -> String {
+    let (opc2, opc1, crn, rt, crm, read) = mcr_encoding(iss);
+    format!(
+        "{} p{}, {}, r{}, c{}, c{}, {}",
+        if read { "MRC" } else { "MCR" },
+        coproc,
+        opc1,
+        rt,
+        crn,
+        crm,
+        opc2
+    )
+}
+
+/// Decodes the ISS value for a trapped VMRS access, identifying the floating-point ID register
+/// accessed if it is one.
+///
+/// This uses the same encoding as an MCR or MRC access, with the register in CRn.
+pub fn decode_iss_vmrs(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+    let fields = decode_iss_mcr(iss)?;
+    let (crn, rt) = (iss >> 10 & 0b1111, iss >> 5 & 0b11111);
+    let description = vmrs_register_name(crn).map(|name| {
+        format!(
+            "VMRS {}, {}",
+            register_name(rt, ExecutionState::AArch32, false),
+            name
+        )
+    });
+
+    Ok((fields, description))
+}
+
+/// Returns the name of the register which a VMRS instruction with the given reg field reads, if it
+/// is one which can be trapped.
+fn vmrs_register_name(reg: u64) -> Option<&'static str> {
+    match reg {
+        0b0000 => Some("FPSID"),
+        0b0101 => Some("MVFR2"),
+        0b0110 => Some("MVFR1"),
+        0b0111 => Some("MVFR0"),
+        0b1000 => Some("FPEXC"),
+        _ => None,
+    }
+}
+
 /// Decodes the ISS value for an MCRR or MRRC access.
 pub fn decode_iss_mcrr(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     let cv =
         FieldInfo::get_bit(iss, "CV", Some("Condition code valid"), 24).describe_bit(describe_cv);
-    let cond = FieldInfo::get(
-        iss,
-        "COND",
-        Some("Condition code of the trapped instruction"),
-        20,
-        24,
+    let cond = with_cond_mnemonic(
+        FieldInfo::get(
+            iss,
+            "COND",
+            Some("Condition code of the trapped instruction"),
+            20,
+            24,
+        ),
+        cv.as_bit(),
     );
-    let opc1 = FieldInfo::get(iss, "Opc2", None, 16, 20);
+    let opc1 = FieldInfo::get(iss, "Opc1", None, 16, 20);
     let res0 = FieldInfo::get_bit(iss, "RES0", Some("Reserved"), 15).check_res0()?;
     let rt2 = FieldInfo::get(iss, "Rt2", None, 10, 15);
     let rt = FieldInfo::get(iss, "Rt", None, 5, 10);
@@ -65,10 +171,91 @@ pub fn decode_iss_mcrr(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
         0,
     )
     .describe_bit(describe_direction);
+    let rt2_description = describe_mcrr_rt(rt2.value);
+    let rt2 = rt2.with_description(rt2_description);
+    let rt_description = describe_mcrr_rt(rt.value);
+    let rt = rt.with_description(rt_description);
 
     Ok(vec![cv, cond, opc1, res0, rt2, rt, crm, direction])
 }
 
+/// Decodes the ISS value for an MRRC access with coproc=0b1110, identifying the debug register
+/// accessed if it is one.
+pub fn decode_iss_mrrc_cp14(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+    let fields = decode_iss_mcrr(iss)?;
+    let (opc1, crm) = (iss >> 16 & 0b1111, iss >> 1 & 0b1111);
+    let description = debug_cp14_64_name(opc1, crm).map(|name| {
+        let instruction = mcrr_instruction(14, iss);
+        match debug_note(name) {
+            Some(note) => format!("{} accessing {} ({})", instruction, name, note),
+            None => format!("{} accessing {}", instruction, name),
+        }
+    });
+
+    Ok((fields, description))
+}
+
+/// Decodes the ISS value for an MCRR or MRRC access with coproc=0b1111, identifying the 64-bit
+/// System register accessed and its AArch64 equivalent if it is a known one.
+pub fn decode_iss_mcrr_cp15(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+    let fields = decode_iss_mcrr(iss)?;
+    let (opc1, crm) = (iss >> 16 & 0b1111, iss >> 1 & 0b1111);
+    let description = cp15_register_64(opc1, crm).map(|(name, aarch64)| {
+        format!(
+            "{} accessing {} (AArch64 equivalent {})",
+            mcrr_instruction(15, iss),
+            name,
+            aarch64
+        )
+    });
+
+    Ok((fields, description))
+}
+
+/// Formats the MCRR or MRRC instruction with the given coprocessor number which the given ISS
+/// value describes.
+fn mcrr_instruction(coproc: u8, iss: u64) -> String {
+    let (opc1, rt2, rt, crm, read) = (
+        iss >> 16 & 0b1111,
+        iss >> 10 & 0b11111,
+        iss >> 5 & 0b11111,
+        iss >> 1 & 0b1111,
+        iss & 0b1 == 1,
+    );
+    format!(
+        "{} p{}, {}, r{}, r{}, c{}",
+        if read { "MRRC" } else { "MCRR" },
+        coproc,
+        opc1,
+        rt,
+        rt2,
+        crm
+    )
+}
+
+/// Describes the Rt register of an MCR or MRC instruction.
+///
+/// r15 is only permitted for MRC, where it means the result is written to the condition flags.
+fn describe_mcr_rt(rt: u64, read: bool) -> String {
+    let name = register_name(rt, ExecutionState::AArch32, false);
+    match (rt, read) {
+        (15, true) => format!("{}, writes APSR_nzcv rather than a register", name),
+        (15, false) => format!("{}, which is UNPREDICTABLE for MCR", name),
+        _ => name,
+    }
+}
+
+/// Describes the Rt or Rt2 register of an MCRR or MRRC instruction, for which r15 is never
+/// permitted.
+fn describe_mcrr_rt(rt: u64) -> String {
+    let name = register_name(rt, ExecutionState::AArch32, false);
+    if rt == 15 {
+        format!("{}, which is UNPREDICTABLE for MCRR and MRRC", name)
+    } else {
+        name
+    }
+}
+
 fn describe_direction(direction: bool) -> &'static str {
     if direction {
         "Read from system register (MRC or VMRS)"
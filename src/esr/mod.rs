@@ -2,35 +2,69 @@ mod abort;
 mod breakpoint;
 mod bti;
 mod common;
+mod cp15;
+mod debugreg;
+mod encode;
+mod eret;
 mod fp;
+mod gcs;
+mod gpc;
 mod hvc;
 mod ld64b;
 mod ldc;
+mod lenient;
 mod mcr;
+mod mops;
 mod msr;
+mod nested;
 mod pauth;
+mod pmu;
+mod roundtrip;
 mod serror;
+mod severity;
+mod sme;
+mod summary;
 mod sve;
 #[cfg(test)]
 mod tests;
+mod tstart;
 mod wf;
 
-use super::{DecodeError, FieldInfo};
-use abort::{decode_iss_data_abort, decode_iss_instruction_abort};
+use super::{ArchVersion, DecodeError, FieldInfo};
+pub use abort::SyndromeAccessSize;
+use abort::{decode_iss2_data_abort, decode_iss_data_abort, decode_iss_instruction_abort};
+pub(crate) use abort::{describe_fsc, far_valid};
+use bit_field::BitField;
 use breakpoint::{
-    decode_iss_breakpoint, decode_iss_breakpoint_vector_catch, decode_iss_software_step,
-    decode_iss_watchpoint,
+    decode_iss_breakpoint, decode_iss_breakpoint_vector_catch, decode_iss_brk,
+    decode_iss_software_step, decode_iss_watchpoint,
 };
 use bti::decode_iss_bti;
-use fp::decode_iss_fp;
-use hvc::decode_iss_hvc;
+pub use encode::{DataAbort, EncodeError, Fault, Injection, InstructionSyndrome};
+use eret::decode_iss_eret;
+use fp::{decode_iss_fp, decode_iss_fp_aarch32};
+use gcs::decode_iss_gcs;
+use gpc::decode_iss_gpc;
+use hvc::{decode_iss_hvc, decode_iss_smc_aarch32};
 use ld64b::decode_iss_ld64b;
 use ldc::decode_iss_ldc;
-use mcr::{decode_iss_mcr, decode_iss_mcrr};
-use msr::decode_iss_msr;
+pub use lenient::{decode_lenient, Decoded, Warning};
+use mcr::{
+    decode_iss_mcr_cp14, decode_iss_mcr_cp15, decode_iss_mcrr_cp15, decode_iss_mrrc_cp14,
+    decode_iss_vmrs,
+};
+use mops::decode_iss_mops;
+use msr::{decode_iss_msr, decode_iss_msrr};
+pub use nested::{decode_nested, validate_forwarded, ForwardingIssue, NestedIssue, NestedReport};
 use pauth::decode_iss_pauth;
+use pmu::decode_iss_pmu;
+pub use roundtrip::{check_coverage, check_round_trip, encode_fields, RoundTripError};
 use serror::decode_iss_serror;
-use sve::decode_iss_sve;
+pub use severity::{severity, ParseSeverityError, Severity};
+use sme::decode_iss_sme;
+pub use summary::{summarize, summarize_fields, summarize_with_options};
+use sve::{decode_iss_sve, decode_iss_sve_access};
+use tstart::decode_iss_tstart;
 use wf::decode_iss_wf;
 
 fn decode_iss_res0(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
@@ -40,36 +74,97 @@ fn decode_iss_res0(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     Ok(vec![res0])
 }
 
+/// The execution state from which an exception was taken.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExecutionState {
+    AArch32,
+    AArch64,
+}
+
+impl ExecutionState {
+    /// Returns the execution state indicated by the M[4] bit of the given SPSR value.
+    pub fn from_spsr(spsr: u64) -> Self {
+        if spsr.get_bit(4) {
+            Self::AArch32
+        } else {
+            Self::AArch64
+        }
+    }
+}
+
+/// A function to decode the ISS value of an IMPLEMENTATION DEFINED exception, returning its fields
+/// and optionally a description.
+pub type ImpdefIssDecoder = fn(u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError>;
+
+/// Options controlling how an Exception Syndrome Register value is decoded.
+// Comparing `impdef_decoder` by address is good enough to tell whether two sets of options are the
+// same.
+#[allow(unpredictable_function_pointer_comparisons)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DecodeOptions {
+    /// The execution state from which the exception was taken, if known.
+    ///
+    /// If this is `None` then it will be inferred from the EC and IL fields where possible.
+    pub execution_state: Option<ExecutionState>,
+    /// The version of the architecture to decode according to.
+    pub arch_version: ArchVersion,
+    /// A vendor-specific decoder for the ISS of IMPLEMENTATION DEFINED exceptions to EL3.
+    ///
+    /// If this is `None` then the ISS is left undecoded.
+    pub impdef_decoder: Option<ImpdefIssDecoder>,
+}
+
 /// Decodes the given Exception Syndrome Register value, or returns an error if it is not valid.
 pub fn decode(esr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
-    let res0 = FieldInfo::get(esr, "RES0", Some("Reserved"), 37, 64).check_res0()?;
-    let iss2 = FieldInfo::get(esr, "ISS2", None, 32, 37);
+    decode_with_options(esr, &DecodeOptions::default())
+}
+
+/// Decodes the given Exception Syndrome Register value with the given options, or returns an error
+/// if it is not valid.
+pub fn decode_with_options(
+    esr: u64,
+    options: &DecodeOptions,
+) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(esr, "RES0", Some("Reserved"), 56, 64).check_res0()?;
+    let iss2 = FieldInfo::get(esr, "ISS2", None, 32, 56);
     let ec = FieldInfo::get(esr, "EC", Some("Exception Class"), 26, 32);
     let il =
         FieldInfo::get_bit(esr, "IL", Some("Instruction Length"), 25).describe_bit(describe_il);
     let iss = FieldInfo::get(esr, "ISS", Some("Instruction Specific Syndrome"), 0, 25);
+    let state = options
+        .execution_state
+        .or_else(|| implied_execution_state(ec.value, il.as_bit()));
     let (class, iss_subfields, iss_description) = match ec.value {
         0b000000 => ("Unknown reason", decode_iss_res0(iss.value)?, None),
         0b000001 => (
             "Wrapped WF* instruction execution",
-            decode_iss_wf(iss.value)?,
-            None,
-        ),
-        0b000011 => (
-            "Trapped MCR or MRC access with coproc=0b1111",
-            decode_iss_mcr(iss.value)?,
-            None,
-        ),
-        0b000100 => (
-            "Trapped MCRR or MRRC access with coproc=0b1111",
-            decode_iss_mcrr(iss.value)?,
-            None,
-        ),
-        0b000101 => (
-            "Trapped MCR or MRC access with coproc=0b1110",
-            decode_iss_mcr(iss.value)?,
+            decode_iss_wf(iss.value, state)?,
             None,
         ),
+        0b000011 => {
+            let (subfields, description) = decode_iss_mcr_cp15(iss.value)?;
+            (
+                "Trapped MCR or MRC access with coproc=0b1111",
+                subfields,
+                description,
+            )
+        }
+        0b000100 => {
+            let (subfields, description) = decode_iss_mcrr_cp15(iss.value)?;
+            (
+                "Trapped MCRR or MRRC access with coproc=0b1111",
+                subfields,
+                description,
+            )
+        }
+        0b000101 => {
+            let (subfields, description) = decode_iss_mcr_cp14(iss.value)?;
+            (
+                "Trapped MCR or MRC access with coproc=0b1110",
+                subfields,
+                description,
+            )
+        }
         0b000110 => (
             "Trapped LDC or STC access",
             decode_iss_ldc(iss.value)?,
@@ -77,7 +172,21 @@ pub fn decode(esr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
         ),
         0b000111 => (
             "Trapped access to SVE, Advanced SIMD or floating point",
-            decode_iss_sve(iss.value)?,
+            decode_iss_sve(iss.value, state)?,
+            None,
+        ),
+        0b001000 => {
+            let (subfields, description) = decode_iss_vmrs(iss.value)?;
+            (
+                "Trapped VMRS access, from ID group trap, that is not reported using EC 0b000111",
+                subfields,
+                description,
+            )
+        }
+        0b001001 => (
+            "Trapped use of a Pointer authentication instruction because HCR_EL2.API == 0 || \
+                 SCR_EL3.API == 0",
+            decode_iss_res0(iss.value)?,
             None,
         ),
         0b001010 => (
@@ -85,11 +194,14 @@ pub fn decode(esr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
             decode_iss_ld64b(iss.value)?,
             None,
         ),
-        0b001100 => (
-            "Trapped MRRC access with (coproc==0b1110)",
-            decode_iss_mcrr(iss.value)?,
-            None,
-        ),
+        0b001100 => {
+            let (subfields, description) = decode_iss_mrrc_cp14(iss.value)?;
+            (
+                "Trapped MRRC access with (coproc==0b1110)",
+                subfields,
+                description,
+            )
+        }
         0b001101 => ("Branch Target Exception", decode_iss_bti(iss.value)?, None),
         0b001110 => ("Illegal Execution state", decode_iss_res0(iss.value)?, None),
         0b010001 => (
@@ -97,6 +209,24 @@ pub fn decode(esr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
             decode_iss_hvc(iss.value)?,
             None,
         ),
+        0b010010 => (
+            "HVC instruction execution in AArch32 state",
+            decode_iss_hvc(iss.value)?,
+            None,
+        ),
+        0b010011 => (
+            "SMC instruction execution in AArch32 state",
+            decode_iss_smc_aarch32(iss.value)?,
+            None,
+        ),
+        0b010100 => {
+            let (subfields, description) = decode_iss_msrr(iss.value)?;
+            (
+                "Trapped MSRR, MRRS or System instruction execution in AArch64 state",
+                subfields,
+                description,
+            )
+        }
         0b010101 => (
             "SVC instruction execution in AArch64 state",
             decode_iss_hvc(iss.value)?,
@@ -120,17 +250,56 @@ pub fn decode(esr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
                 description,
             )
         }
-        0b011001 => (
+        0b011001 if options.arch_version >= ArchVersion::Armv8_2 => (
             "Access to SVE functionality trapped as a result of CPACR_EL1.ZEN, CPTR_EL2.ZEN, \
                  CPTR_EL2.TZ, or CPTR_EL3.EZ",
-            decode_iss_res0(iss.value)?,
+            decode_iss_sve_access(iss.value, options.arch_version),
+            None,
+        ),
+        0b011010 => {
+            let (subfields, description) = decode_iss_eret(iss.value)?;
+            (
+                "Trapped ERET, ERETAA or ERETAB instruction execution",
+                subfields,
+                description,
+            )
+        }
+        0b011011 => (
+            "Exception from an access to a TSTART instruction at EL0 when SCTLR_EL1.TME0 == 0, EL0 \
+                 when SCTLR_EL2.TME0 == 0, at EL1 when SCTLR_EL1.TME == 0, at EL2 when \
+                 SCTLR_EL2.TME == 0 or at EL3 when SCTLR_EL3.TME == 0",
+            decode_iss_tstart(iss.value)?,
+            None,
+        ),
+        0b011100 => {
+            let (subfields, description) = decode_iss_pauth(iss.value)?;
+            (
+                "Exception from a Pointer Authentication instruction authentication failure",
+                subfields,
+                description,
+            )
+        }
+        0b011101 => (
+            "Trapped SME, SVE or Advanced SIMD access due to SME functionality",
+            decode_iss_sme(iss.value)?,
             None,
         ),
-        0b011100 => (
-            "Exception from a Pointer Authentication instruction authentication failure",
-            decode_iss_pauth(iss.value)?,
+        0b011110 => (
+            "Granule Protection Check exception",
+            decode_iss_gpc(iss.value)?,
             None,
         ),
+        0b011111 => {
+            let (subfields, description) = match options.impdef_decoder {
+                Some(decoder) => decoder(iss.value)?,
+                None => (vec![], None),
+            };
+            (
+                "IMPLEMENTATION DEFINED exception to EL3",
+                subfields,
+                description,
+            )
+        }
         0b100000 => (
             "Instruction Abort from a lower Exception level",
             decode_iss_instruction_abort(iss.value)?,
@@ -146,29 +315,51 @@ pub fn decode(esr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
             decode_iss_res0(iss.value)?,
             None,
         ),
-        0b100100 => (
-            "Data Abort from a lower Exception level",
-            decode_iss_data_abort(iss.value)?,
-            None,
-        ),
-        0b100101 => (
-            "Data Abort taken without a change in Exception level",
-            decode_iss_data_abort(iss.value)?,
-            None,
-        ),
+        0b100100 => {
+            let (subfields, description) = decode_iss_data_abort(iss.value, state)?;
+            (
+                "Data Abort from a lower Exception level",
+                subfields,
+                description,
+            )
+        }
+        0b100101 => {
+            let (subfields, description) = decode_iss_data_abort(iss.value, state)?;
+            (
+                "Data Abort taken without a change in Exception level",
+                subfields,
+                description,
+            )
+        }
         0b100110 => (
             "SP alignment fault exception",
             decode_iss_res0(iss.value)?,
             None,
         ),
-        0b101000 => (
-            "Trapped floating-point exception taken from AArch32 state",
-            decode_iss_fp(iss.value)?,
+        0b100111 => (
+            "Memory Copy or Memory Set exception",
+            decode_iss_mops(iss.value)?,
             None,
         ),
-        0b101100 => (
-            "Trapped floating-point exception taken from AArch64 state",
-            decode_iss_fp(iss.value)?,
+        0b101000 => {
+            let (subfields, description) = decode_iss_fp_aarch32(iss.value)?;
+            (
+                "Trapped floating-point exception taken from AArch32 state",
+                subfields,
+                description,
+            )
+        }
+        0b101100 => {
+            let (subfields, description) = decode_iss_fp(iss.value)?;
+            (
+                "Trapped floating-point exception taken from AArch64 state",
+                subfields,
+                description,
+            )
+        }
+        0b101101 => (
+            "Guarded Control Stack exception",
+            decode_iss_gcs(iss.value)?,
             None,
         ),
         0b101111 => ("SError interrupt", decode_iss_serror(iss.value)?, None),
@@ -207,11 +398,17 @@ pub fn decode(esr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
             decode_iss_breakpoint(iss.value)?,
             None,
         ),
+        0b111010 => (
+            "Vector Catch exception from AArch32 state",
+            decode_iss_breakpoint_vector_catch(iss.value)?,
+            None,
+        ),
         0b111100 => (
             "BRK instruction execution in AArch64 state",
-            decode_iss_breakpoint(iss.value)?,
+            decode_iss_brk(iss.value)?,
             None,
         ),
+        0b111101 => ("PMU exception", decode_iss_pmu(iss.value)?, None),
         _ => return Err(DecodeError::InvalidEc { ec: ec.value }),
     };
     let iss = FieldInfo {
@@ -219,10 +416,35 @@ pub fn decode(esr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
         subfields: iss_subfields,
         ..iss
     };
+    let iss2 = match ec.value {
+        0b100100 | 0b100101 => FieldInfo {
+            subfields: decode_iss2_data_abort(iss2.value)?,
+            ..iss2
+        },
+        _ => iss2,
+    };
     let ec = ec.with_description(class.to_string());
     Ok(vec![res0, iss2, ec, il, iss])
 }
 
+/// Returns the execution state from which an exception must have been taken, if it can be
+/// determined from the EC and IL fields alone.
+fn implied_execution_state(ec: u64, il: bool) -> Option<ExecutionState> {
+    match ec {
+        // Exception classes which only exist for exceptions taken from AArch32.
+        0b000011 | 0b000100 | 0b000101 | 0b000110 | 0b001000 | 0b001100 | 0b010001 | 0b010010
+        | 0b010011 | 0b101000 | 0b111000 | 0b111010 => Some(ExecutionState::AArch32),
+        // Exception classes which only exist for exceptions taken from AArch64, or which are taken
+        // without a change in Exception level and so must be from AArch64.
+        0b001001 | 0b010100 | 0b010101 | 0b010110 | 0b010111 | 0b011000 | 0b011001 | 0b011010
+        | 0b011011 | 0b011100 | 0b011101 | 0b100001 | 0b100101 | 0b100111 | 0b101100 | 0b101101
+        | 0b110001 | 0b110011 | 0b110101 | 0b111100 | 0b111101 => Some(ExecutionState::AArch64),
+        // A 16-bit instruction can only be a T32 instruction.
+        _ if !il => Some(ExecutionState::AArch32),
+        _ => None,
+    }
+}
+
 fn describe_il(il: bool) -> &'static str {
     if il {
         "32-bit instruction trapped"
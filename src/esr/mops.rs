@@ -0,0 +1,111 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::common::describe_register;
+use crate::{DecodeError, FieldInfo};
+
+/// Decodes the ISS value for an exception from a Memory Copy or Memory Set instruction.
+pub fn decode_iss_mops(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let mem_inst = FieldInfo::get_bit(iss, "MemInst", Some("Memory Instruction"), 24)
+        .describe_bit(describe_mem_inst);
+    let is_setg = FieldInfo::get_bit(iss, "isSETG", Some("Is SETG* instruction"), 23);
+    let is_setg = if mem_inst.as_bit() {
+        is_setg
+    } else {
+        is_setg.describe_bit(describe_is_setg)
+    };
+    let options = FieldInfo::get(
+        iss,
+        "Options",
+        Some("Options field of the instruction"),
+        19,
+        23,
+    );
+    let from_epilogue = FieldInfo::get_bit(iss, "FromEpilogue", Some("From Epilogue"), 18)
+        .describe_bit(describe_from_epilogue);
+    let wrong_option = FieldInfo::get_bit(iss, "WrongOption", Some("Wrong Option"), 17)
+        .describe_bit(describe_wrong_option);
+    let option_a =
+        FieldInfo::get_bit(iss, "OptionA", Some("Option A"), 16).describe_bit(describe_option_a);
+    let res0 = FieldInfo::get_bit(iss, "RES0", Some("Reserved"), 15).check_res0()?;
+    let destreg = describe_register(FieldInfo::get(
+        iss,
+        "destreg",
+        Some("Destination register"),
+        10,
+        15,
+    ));
+    let srcreg = describe_register(FieldInfo::get(
+        iss,
+        "srcreg",
+        Some("Source register"),
+        5,
+        10,
+    ));
+    let sizereg = describe_register(FieldInfo::get(iss, "sizereg", Some("Size register"), 0, 5));
+
+    Ok(vec![
+        mem_inst,
+        is_setg,
+        options,
+        from_epilogue,
+        wrong_option,
+        option_a,
+        res0,
+        destreg,
+        srcreg,
+        sizereg,
+    ])
+}
+
+fn describe_mem_inst(mem_inst: bool) -> &'static str {
+    if mem_inst {
+        "Memory Copy instruction (CPY*)"
+    } else {
+        "Memory Set instruction (SET*)"
+    }
+}
+
+fn describe_is_setg(is_setg: bool) -> &'static str {
+    if is_setg {
+        "SETG* instruction, which also sets Allocation Tags"
+    } else {
+        "SET* instruction other than SETG*"
+    }
+}
+
+fn describe_from_epilogue(from_epilogue: bool) -> &'static str {
+    if from_epilogue {
+        "Exception from the epilogue instruction"
+    } else {
+        "Exception from the prologue or main instruction"
+    }
+}
+
+fn describe_wrong_option(wrong_option: bool) -> &'static str {
+    if wrong_option {
+        "The algorithm option used by the instruction is inconsistent with the registers and \
+         PSTATE.C, e.g. because the sequence was migrated to a different CPU"
+    } else {
+        "The algorithm option is consistent"
+    }
+}
+
+fn describe_option_a(option_a: bool) -> &'static str {
+    if option_a {
+        "Option A was used by the instruction"
+    } else {
+        "Option B was used by the instruction"
+    }
+}
@@ -12,10 +12,56 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::debugreg::{debug_note, debug_sysreg_name};
 use crate::{DecodeError, FieldInfo};
 
 /// Decodes the ISS value for an MSR or MRS instruction.
 pub fn decode_iss_msr(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+    let (fields, name) = decode_iss_sysreg(iss, describe_direction)?;
+    let (rt, read) = (fields[5].value, fields[7].as_bit());
+    let instruction = if read {
+        format!("MRS x{}, {}", rt, name)
+    } else {
+        format!("MSR {}, x{}", name, rt)
+    };
+    let description = match sysreg_note(&name) {
+        Some(note) => format!("{} ({})", instruction, note),
+        None => instruction,
+    };
+
+    Ok((fields, Some(description)))
+}
+
+/// Decodes the ISS value for an MSRR, MRRS or SYSP instruction.
+pub fn decode_iss_msrr(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+    let (fields, name) = decode_iss_sysreg(iss, describe_direction_128)?;
+    let (op0, op2, op1, crn, rt, crm, read) = (
+        fields[1].value,
+        fields[2].value,
+        fields[3].value,
+        fields[4].value,
+        fields[5].value,
+        fields[6].value,
+        fields[7].as_bit(),
+    );
+    let pair = register_pair(rt);
+    let description = if op0 == 1 {
+        format!("SYSP #{}, C{}, C{}, #{}, {}", op1, crn, crm, op2, pair)
+    } else if read {
+        format!("MRRS {}, {}", pair, name)
+    } else {
+        format!("MSRR {}, {}", name, pair)
+    };
+
+    Ok((fields, Some(description)))
+}
+
+/// Decodes the fields of the ISS value for a trapped system register or System instruction
+/// access, and returns them along with the name of the register.
+fn decode_iss_sysreg(
+    iss: u64,
+    describe_direction: fn(bool) -> &'static str,
+) -> Result<(Vec<FieldInfo>, String), DecodeError> {
     let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 22, 25).check_res0()?;
     let op0 = FieldInfo::get(iss, "Op0", None, 20, 22);
     let op2 = FieldInfo::get(iss, "Op2", None, 17, 20);
@@ -37,17 +83,33 @@ pub fn decode_iss_msr(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), Deco
     )
     .describe_bit(describe_direction);
 
-    let name = sysreg_name(op0.value, op1.value, op2.value, crn.value, crm.value);
-    let description = if direction.value == 0 {
-        format!("MSR {}, x{}", name, rt.value)
-    } else {
-        format!("MRS x{}, {}", rt.value, name)
-    };
+    let name = debug_sysreg_name(
+        op0.value,
+        op1.value,
+        op2.value,
+        crn.value,
+        crm.value,
+        direction.as_bit(),
+    )
+    .or_else(|| indexed_sysreg_name(op0.value, op1.value, op2.value, crn.value, crm.value))
+    .unwrap_or_else(|| {
+        sysreg_name(op0.value, op1.value, op2.value, crn.value, crm.value).to_string()
+    });
 
-    Ok((
-        vec![res0, op0, op2, op1, crn, rt, crm, direction],
-        Some(description),
-    ))
+    Ok((vec![res0, op0, op2, op1, crn, rt, crm, direction], name))
+}
+
+/// Returns the pair of registers starting at the given register number, as used by 128-bit
+/// system register accesses.
+///
+/// The first register of the pair should be even; odd register numbers are CONSTRAINED
+/// UNPREDICTABLE.
+fn register_pair(rt: u64) -> String {
+    match rt {
+        31 => "xzr, xzr".to_string(),
+        _ if rt % 2 == 1 => format!("x{}, x{} (odd register pair is UNPREDICTABLE)", rt, rt + 1),
+        _ => format!("x{}, x{}", rt, rt + 1),
+    }
 }
 
 fn describe_direction(direction: bool) -> &'static str {
@@ -58,6 +120,202 @@ fn describe_direction(direction: bool) -> &'static str {
     }
 }
 
+fn describe_direction_128(direction: bool) -> &'static str {
+    if direction {
+        "Read from 128-bit system register (MRRS)"
+    } else {
+        "Write to 128-bit system register (MSRR) or System instruction (SYSP)"
+    }
+}
+
+/// Returns the name of the system register with the given encoding, if it is one of a numbered
+/// series of registers.
+fn indexed_sysreg_name(op0: u64, op1: u64, op2: u64, crn: u64, crm: u64) -> Option<String> {
+    // The index is split between the low bit of CRm and Op2.
+    let index = (crm & 0b1) << 3 | op2;
+    let series = match (op0, crn, op1, crm >> 1) {
+        (3, 13, 3, 0b010) => "AMEVCNTR0",
+        (3, 13, 3, 0b011) => "AMEVTYPER0",
+        (3, 13, 3, 0b110) => "AMEVCNTR1",
+        (3, 13, 3, 0b111) => "AMEVTYPER1",
+        (3, 13, 4, 0b100) => return Some(format!("AMEVCNTVOFF0{}_EL2", index)),
+        (3, 13, 4, 0b101) => return Some(format!("AMEVCNTVOFF1{}_EL2", index)),
+        (3, 12, 4, 0b110) => return Some(format!("ICH_LR{}_EL2", index)),
+        _ => return None,
+    };
+    Some(format!("{}{}_EL0", series, index))
+}
+
+/// Returns a note about what the system register with the given name is and which controls may
+/// have caused accesses to it to be trapped, if there is anything useful to say.
+fn sysreg_note(name: &str) -> Option<String> {
+    if name.starts_with("AMEVCNTVOFF") {
+        Some(
+            "Activity Monitors virtual offset register, accesses from EL2 trap to EL3 if \
+             CPTR_EL3.TAM is set"
+                .to_string(),
+        )
+    } else if name.starts_with("AM") && name.ends_with("_EL0") {
+        Some(
+            "Activity Monitors register, accesses from EL0 trap to EL1 unless AMUSERENR_EL0.EN \
+             is set, to EL2 if CPTR_EL2.TAM is set, or to EL3 if CPTR_EL3.TAM is set"
+                .to_string(),
+        )
+    } else if name.starts_with("CNT") {
+        timer_note(name)
+    } else if name.starts_with("DBG") || name.starts_with("MD") || name.starts_with("OS") {
+        debug_note(name)
+    } else if name.starts_with("ICC_") || name.starts_with("ICH_") {
+        gic_note(name)
+    } else {
+        None
+    }
+}
+
+/// Returns a note about which generic timer the timer or counter register with the given name
+/// belongs to, and which controls may have trapped accesses to it.
+fn timer_note(name: &str) -> Option<String> {
+    match name {
+        "CNTFRQ_EL0" => {
+            return Some(
+                "Counter frequency register, accesses from EL0 trap to EL1 unless \
+                 CNTKCTL_EL1.EL0PCTEN or CNTKCTL_EL1.EL0VCTEN is set"
+                    .to_string(),
+            )
+        }
+        "CNTPCT_EL0" | "CNTPCTSS_EL0" => {
+            return Some(
+                "Physical counter, accesses from EL0 trap to EL1 unless CNTKCTL_EL1.EL0PCTEN is \
+                 set, or to EL2 unless CNTHCTL_EL2.EL1PCTEN is set"
+                    .to_string(),
+            )
+        }
+        "CNTVCT_EL0" | "CNTVCTSS_EL0" => {
+            return Some(
+                "Virtual counter, accesses from EL0 trap to EL1 unless CNTKCTL_EL1.EL0VCTEN is \
+                 set, or to EL2 if CNTHCTL_EL2.EL1TVCT is set"
+                    .to_string(),
+            )
+        }
+        "CNTKCTL_EL1" | "CNTKCTL_EL12" => return Some("Kernel timer control register".to_string()),
+        "CNTHCTL_EL2" => return Some("Hypervisor timer control register".to_string()),
+        "CNTVOFF_EL2" => return Some("Virtual counter offset register".to_string()),
+        _ => {}
+    }
+
+    // Timer registers are named CNT<timer>_<register>_<EL>.
+    let mut parts = name.split('_');
+    let timer = parts.next()?;
+    let register = parts.next()?;
+    let suffix = parts.next()?;
+    let register = match register {
+        "CTL" => "Control register",
+        "CVAL" => "Compare value register",
+        "TVAL" => "Timer value register",
+        _ => return None,
+    };
+    let (timer, traps) = match (timer, suffix) {
+        ("CNTP", "EL0") => (
+            "EL1 physical timer",
+            "accesses from EL0 trap to EL1 unless CNTKCTL_EL1.EL0PTEN is set, and accesses \
+             trap to EL2 unless CNTHCTL_EL2.EL1PCEN (or CNTHCTL_EL2.EL1PTEN if HCR_EL2.E2H is \
+             set) is set",
+        ),
+        ("CNTV", "EL0") => (
+            "EL1 virtual timer",
+            "accesses from EL0 trap to EL1 unless CNTKCTL_EL1.EL0VTEN is set, and accesses \
+             trap to EL2 if CNTHCTL_EL2.EL1TVT is set",
+        ),
+        ("CNTP", "EL02") => (
+            "EL1 physical timer",
+            "accessed from EL2 with HCR_EL2.E2H set, or trapped to EL2 by HCR_EL2.NV",
+        ),
+        ("CNTV", "EL02") => (
+            "EL1 virtual timer",
+            "accessed from EL2 with HCR_EL2.E2H set, or trapped to EL2 by HCR_EL2.NV",
+        ),
+        ("CNTHP", "EL2") => (
+            "EL2 physical timer",
+            "accesses from EL1 trap to EL2 if HCR_EL2.NV is set",
+        ),
+        ("CNTHV", "EL2") => (
+            "EL2 virtual timer",
+            "accesses from EL1 trap to EL2 if HCR_EL2.NV is set",
+        ),
+        ("CNTHPS", "EL2") => (
+            "Secure EL2 physical timer",
+            "accesses from EL1 trap to EL2 if HCR_EL2.NV is set",
+        ),
+        ("CNTHVS", "EL2") => (
+            "Secure EL2 virtual timer",
+            "accesses from EL1 trap to EL2 if HCR_EL2.NV is set",
+        ),
+        ("CNTPS", "EL1") => (
+            "Secure physical timer",
+            "accesses from EL1 trap to EL3 unless SCR_EL3.ST is set",
+        ),
+        _ => return None,
+    };
+    Some(format!("{} of the {}, {}", register, timer, traps))
+}
+
+/// Returns a note about the GIC CPU interface register with the given name, including which virtual
+/// interface register is accessed instead when interrupts are routed to EL2, and which controls may
+/// have trapped accesses to it.
+fn gic_note(name: &str) -> Option<String> {
+    if name.starts_with("ICH_") {
+        return Some(
+            "GIC virtual interface control register, accesses from EL1 trap to EL2 if HCR_EL2.NV \
+             is set"
+                .to_string(),
+        );
+    }
+    let register = name.strip_prefix("ICC_")?.strip_suffix("_EL1")?;
+    let (description, routing, trap) =
+        match register {
+            "IAR0" | "EOIR0" | "HPPIR0" | "BPR0" | "AP0R0" | "AP0R1" | "AP0R2" | "AP0R3"
+            | "IGRPEN0" => (
+                "GIC CPU interface group 0 register",
+                "HCR_EL2.FMO",
+                "ICH_HCR_EL2.TALL0",
+            ),
+            "IAR1" | "EOIR1" | "HPPIR1" | "BPR1" | "AP1R0" | "AP1R1" | "AP1R2" | "AP1R3"
+            | "IGRPEN1" | "NMIAR1" => (
+                "GIC CPU interface group 1 register",
+                "HCR_EL2.IMO",
+                "ICH_HCR_EL2.TALL1",
+            ),
+            "CTLR" | "PMR" | "RPR" => (
+                "GIC CPU interface register",
+                "HCR_EL2.IMO or HCR_EL2.FMO",
+                "ICH_HCR_EL2.TC",
+            ),
+            "DIR" => (
+                "GIC CPU interface register",
+                "HCR_EL2.IMO or HCR_EL2.FMO",
+                "ICH_HCR_EL2.TC or ICH_HCR_EL2.TDIR",
+            ),
+            "SGI0R" | "SGI1R" | "ASGI1R" => return Some(
+                "GIC software generated interrupt register, which has no virtual equivalent, so \
+                 accesses from EL1 trap to EL2 if HCR_EL2.IMO or HCR_EL2.FMO is set"
+                    .to_string(),
+            ),
+            "SRE" => {
+                return Some(
+                    "GIC system register enable register, accesses from EL1 trap to EL2 unless \
+                 ICC_SRE_EL2.Enable is set"
+                        .to_string(),
+                )
+            }
+            _ => return None,
+        };
+    Some(format!(
+        "{}, or the virtual interface register ICV_{}_EL1 if accessed from EL1 with {} set, \
+         which traps to EL2 if {} is set",
+        description, register, routing, trap
+    ))
+}
+
 fn sysreg_name(op0: u64, op1: u64, op2: u64, crn: u64, crm: u64) -> &'static str {
     match (op0, crn, op1, crm, op2) {
         (3, 1, 0, 0, 1) => "ACTLR_EL1",
@@ -81,6 +339,51 @@ fn sysreg_name(op0: u64, op1: u64, op2: u64, crn: u64, crm: u64) -> &'static str
         (3, 0, 2, 0, 0) => "CSSELR_EL1",
         (3, 0, 3, 0, 1) => "CTR_EL0",
         (3, 12, 0, 1, 1) => "DISR_EL1",
+        (3, 12, 0, 8, 4) => "ICC_AP0R0_EL1",
+        (3, 12, 0, 8, 5) => "ICC_AP0R1_EL1",
+        (3, 12, 0, 8, 6) => "ICC_AP0R2_EL1",
+        (3, 12, 0, 8, 7) => "ICC_AP0R3_EL1",
+        (3, 12, 0, 9, 0) => "ICC_AP1R0_EL1",
+        (3, 12, 0, 9, 1) => "ICC_AP1R1_EL1",
+        (3, 12, 0, 9, 2) => "ICC_AP1R2_EL1",
+        (3, 12, 0, 9, 3) => "ICC_AP1R3_EL1",
+        (3, 12, 0, 11, 6) => "ICC_ASGI1R_EL1",
+        (3, 12, 0, 8, 3) => "ICC_BPR0_EL1",
+        (3, 12, 0, 12, 3) => "ICC_BPR1_EL1",
+        (3, 12, 0, 12, 4) => "ICC_CTLR_EL1",
+        (3, 12, 6, 12, 4) => "ICC_CTLR_EL3",
+        (3, 12, 0, 11, 1) => "ICC_DIR_EL1",
+        (3, 12, 0, 8, 1) => "ICC_EOIR0_EL1",
+        (3, 12, 0, 12, 1) => "ICC_EOIR1_EL1",
+        (3, 12, 0, 8, 2) => "ICC_HPPIR0_EL1",
+        (3, 12, 0, 12, 2) => "ICC_HPPIR1_EL1",
+        (3, 12, 0, 8, 0) => "ICC_IAR0_EL1",
+        (3, 12, 0, 12, 0) => "ICC_IAR1_EL1",
+        (3, 12, 0, 12, 6) => "ICC_IGRPEN0_EL1",
+        (3, 12, 0, 12, 7) => "ICC_IGRPEN1_EL1",
+        (3, 12, 6, 12, 7) => "ICC_IGRPEN1_EL3",
+        (3, 12, 0, 9, 5) => "ICC_NMIAR1_EL1",
+        (3, 4, 0, 6, 0) => "ICC_PMR_EL1",
+        (3, 12, 0, 11, 3) => "ICC_RPR_EL1",
+        (3, 12, 0, 11, 7) => "ICC_SGI0R_EL1",
+        (3, 12, 0, 11, 5) => "ICC_SGI1R_EL1",
+        (3, 12, 0, 12, 5) => "ICC_SRE_EL1",
+        (3, 12, 4, 9, 5) => "ICC_SRE_EL2",
+        (3, 12, 6, 12, 5) => "ICC_SRE_EL3",
+        (3, 12, 4, 8, 0) => "ICH_AP0R0_EL2",
+        (3, 12, 4, 8, 1) => "ICH_AP0R1_EL2",
+        (3, 12, 4, 8, 2) => "ICH_AP0R2_EL2",
+        (3, 12, 4, 8, 3) => "ICH_AP0R3_EL2",
+        (3, 12, 4, 9, 0) => "ICH_AP1R0_EL2",
+        (3, 12, 4, 9, 1) => "ICH_AP1R1_EL2",
+        (3, 12, 4, 9, 2) => "ICH_AP1R2_EL2",
+        (3, 12, 4, 9, 3) => "ICH_AP1R3_EL2",
+        (3, 12, 4, 11, 3) => "ICH_EISR_EL2",
+        (3, 12, 4, 11, 5) => "ICH_ELRSR_EL2",
+        (3, 12, 4, 11, 0) => "ICH_HCR_EL2",
+        (3, 12, 4, 11, 2) => "ICH_MISR_EL2",
+        (3, 12, 4, 11, 7) => "ICH_VMCR_EL2",
+        (3, 12, 4, 11, 1) => "ICH_VTR_EL2",
         (3, 5, 0, 3, 0) => "ERRIDR_EL1",
         (3, 5, 0, 3, 1) => "ERRSELR_EL1",
         (3, 5, 0, 4, 3) => "ERXADDR_EL1",
@@ -145,6 +448,15 @@ fn sysreg_name(op0: u64, op1: u64, op2: u64, crn: u64, crm: u64) -> &'static str
         (3, 5, 5, 1, 0) => "AFSR0_EL12",
         (3, 5, 5, 1, 1) => "AFSR1_EL12",
         (3, 10, 5, 3, 0) => "AMAIR_EL12",
+        (3, 13, 3, 2, 1) => "AMCFGR_EL0",
+        (3, 13, 3, 2, 6) => "AMCG1IDR_EL0",
+        (3, 13, 3, 2, 2) => "AMCGCR_EL0",
+        (3, 13, 3, 2, 4) => "AMCNTENCLR0_EL0",
+        (3, 13, 3, 3, 0) => "AMCNTENCLR1_EL0",
+        (3, 13, 3, 2, 5) => "AMCNTENSET0_EL0",
+        (3, 13, 3, 3, 1) => "AMCNTENSET1_EL0",
+        (3, 13, 3, 2, 0) => "AMCR_EL0",
+        (3, 13, 3, 2, 3) => "AMUSERENR_EL0",
         (3, 14, 3, 0, 0) => "CNTFRQ_EL0",
         (3, 14, 4, 1, 0) => "CNTHCTL_EL2",
         (3, 14, 4, 2, 1) => "CNTHP_CTL_EL2",
@@ -162,6 +474,13 @@ fn sysreg_name(op0: u64, op1: u64, op2: u64, crn: u64, crm: u64) -> &'static str
         (3, 14, 3, 2, 0) => "CNTP_TVAL_EL0",
         (3, 14, 5, 2, 0) => "CNTP_TVAL_EL02",
         (3, 14, 3, 0, 1) => "CNTPCT_EL0",
+        (3, 14, 3, 0, 5) => "CNTPCTSS_EL0",
+        (3, 14, 4, 5, 1) => "CNTHPS_CTL_EL2",
+        (3, 14, 4, 5, 2) => "CNTHPS_CVAL_EL2",
+        (3, 14, 4, 5, 0) => "CNTHPS_TVAL_EL2",
+        (3, 14, 4, 4, 1) => "CNTHVS_CTL_EL2",
+        (3, 14, 4, 4, 2) => "CNTHVS_CVAL_EL2",
+        (3, 14, 4, 4, 0) => "CNTHVS_TVAL_EL2",
         (3, 14, 7, 2, 1) => "CNTPS_CTL_EL1",
         (3, 14, 7, 2, 2) => "CNTPS_CVAL_EL1",
         (3, 14, 7, 2, 0) => "CNTPS_TVAL_EL1",
@@ -172,6 +491,7 @@ fn sysreg_name(op0: u64, op1: u64, op2: u64, crn: u64, crm: u64) -> &'static str
         (3, 14, 3, 3, 0) => "CNTV_TVAL_EL0",
         (3, 14, 5, 3, 0) => "CNTV_TVAL_EL02",
         (3, 14, 3, 0, 2) => "CNTVCT_EL0",
+        (3, 14, 3, 0, 6) => "CNTVCTSS_EL0",
         (3, 14, 4, 0, 3) => "CNTVOFF_EL2",
         (3, 13, 0, 0, 1) => "CONTEXTIDR_EL1",
         (3, 13, 5, 0, 1) => "CONTEXTIDR_EL12",
@@ -220,6 +540,50 @@ fn sysreg_name(op0: u64, op1: u64, op2: u64, crn: u64, crm: u64) -> &'static str
         (3, 12, 6, 0, 0) => "VBAR_EL3",
         (3, 0, 4, 0, 5) => "VMPIDR_EL2",
         (3, 0, 4, 0, 0) => "VPIDR_EL2",
+        #[cfg(feature = "profiling")]
+        (2, 9, 1, 0, 0) => "BRBCR_EL1",
+        #[cfg(feature = "profiling")]
+        (2, 9, 4, 0, 0) => "BRBCR_EL2",
+        #[cfg(feature = "profiling")]
+        (2, 9, 5, 0, 0) => "BRBCR_EL12",
+        #[cfg(feature = "profiling")]
+        (2, 9, 1, 0, 1) => "BRBFCR_EL1",
+        #[cfg(feature = "profiling")]
+        (2, 9, 1, 2, 0) => "BRBIDR0_EL1",
+        #[cfg(feature = "profiling")]
+        (2, 9, 1, 1, 0) => "BRBINFINJ_EL1",
+        #[cfg(feature = "profiling")]
+        (2, 9, 1, 1, 1) => "BRBSRCINJ_EL1",
+        #[cfg(feature = "profiling")]
+        (2, 9, 1, 1, 2) => "BRBTGTINJ_EL1",
+        #[cfg(feature = "profiling")]
+        (2, 9, 1, 0, 2) => "BRBTS_EL1",
+        #[cfg(feature = "profiling")]
+        (2, 8, 1, _, 0) | (2, 8, 1, _, 4) => "BRBINF<n>_EL1",
+        #[cfg(feature = "profiling")]
+        (2, 8, 1, _, 1) | (2, 8, 1, _, 5) => "BRBSRC<n>_EL1",
+        #[cfg(feature = "profiling")]
+        (2, 8, 1, _, 2) | (2, 8, 1, _, 6) => "BRBTGT<n>_EL1",
+        #[cfg(feature = "profiling")]
+        (3, 9, 0, 11, 2) => "TRBBASER_EL1",
+        #[cfg(feature = "profiling")]
+        (3, 9, 0, 11, 7) => "TRBIDR_EL1",
+        #[cfg(feature = "profiling")]
+        (3, 9, 0, 11, 0) => "TRBLIMITR_EL1",
+        #[cfg(feature = "profiling")]
+        (3, 9, 0, 11, 4) => "TRBMAR_EL1",
+        #[cfg(feature = "profiling")]
+        (3, 9, 0, 11, 1) => "TRBPTR_EL1",
+        #[cfg(feature = "profiling")]
+        (3, 9, 0, 11, 3) => "TRBSR_EL1",
+        #[cfg(feature = "profiling")]
+        (3, 9, 0, 11, 6) => "TRBTRG_EL1",
+        #[cfg(feature = "profiling")]
+        (3, 1, 0, 2, 1) => "TRFCR_EL1",
+        #[cfg(feature = "profiling")]
+        (3, 1, 4, 2, 1) => "TRFCR_EL2",
+        #[cfg(feature = "profiling")]
+        (3, 1, 5, 2, 1) => "TRFCR_EL12",
         _ => "unknown",
     }
 }
@@ -0,0 +1,347 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Comparison of a pair of ESR values from a fault taken to EL2 and then injected into a guest at
+//! EL1.
+
+use super::{decode, summarize};
+use crate::{DecodeError, FieldInfo};
+use bit_field::BitField;
+use thiserror::Error;
+
+/// A report on an ESR_EL2 value for an exception taken to a hypervisor, and the ESR_EL1 value which
+/// the hypervisor injected into the guest as a result.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NestedReport {
+    /// The decoded ESR_EL1 value.
+    pub esr_el1: Vec<FieldInfo>,
+    /// The decoded ESR_EL2 value.
+    pub esr_el2: Vec<FieldInfo>,
+    /// A description of how the two exceptions are related.
+    pub description: String,
+    /// Any inconsistencies between the two values, which may indicate a bug in the hypervisor.
+    pub issues: Vec<NestedIssue>,
+}
+
+/// An inconsistency between an ESR_EL2 value and the ESR_EL1 value injected as a result of it.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum NestedIssue {
+    /// The injected abort is a different kind of abort to the one taken to EL2.
+    #[error("ESR_EL2 is an {el2} abort but ESR_EL1 is an {el1} abort")]
+    AbortKindMismatch {
+        el1: &'static str,
+        el2: &'static str,
+    },
+    /// S1PTW was set in the injected syndrome.
+    #[error("S1PTW is set in ESR_EL1, but a guest can never observe a stage 2 fault")]
+    InjectedS1ptw,
+    /// VNCR was set in the injected syndrome.
+    #[error("VNCR is set in ESR_EL1, but it is only reported to EL2")]
+    InjectedVncr,
+    /// A stage 2 fault on a stage 1 translation table walk was injected as some other fault.
+    #[error(
+        "ESR_EL2 is for a stage 2 fault on a stage 1 translation table walk, but ESR_EL1 doesn't \
+         report a fault on a translation table walk"
+    )]
+    Stage1WalkNotReported,
+    /// The direction of the access doesn't match.
+    #[error("WnR is {el2} in ESR_EL2 but {el1} in ESR_EL1")]
+    WnrMismatch { el1: bool, el2: bool },
+}
+
+/// A problem with an ESR_EL1 value which a hypervisor forwarded to a guest after trapping the
+/// exception described by an ESR_EL2 value.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ForwardingIssue {
+    /// The exception class is different, other than a change between the lower Exception level
+    /// and same Exception level variants.
+    #[error("EC is {el2:#x} in ESR_EL2 but {el1:#x} in ESR_EL1")]
+    EcChanged { el1: u64, el2: u64 },
+    /// The instruction length is different.
+    #[error("IL is {el2} in ESR_EL2 but {el1} in ESR_EL1")]
+    IlChanged { el1: bool, el2: bool },
+    /// A field which must be preserved has been changed.
+    #[error("{name} is {el2:#x} in ESR_EL2 but {el1:#x} in ESR_EL1, it should be preserved")]
+    FieldChanged {
+        name: &'static str,
+        el1: u64,
+        el2: u64,
+    },
+    /// A field which is only meaningful to EL2 has not been cleared.
+    #[error("{0} is set in ESR_EL1, but must be cleared when forwarding to the guest")]
+    NotCleared(&'static str),
+}
+
+/// Fields of an abort ISS which must be preserved when forwarding it to a guest.
+const PRESERVED_ABORT_FIELDS: [&str; 12] = [
+    "ISV", "SAS", "SSE", "SRT", "SF", "AR", "SET", "FnV", "EA", "CM", "WnR", "DFSC",
+];
+
+/// Fields of an abort ISS which are only meaningful to EL2, so must be cleared when forwarding it.
+const EL2_ONLY_ABORT_FIELDS: [&str; 2] = ["S1PTW", "VNCR"];
+
+/// Checks whether the given ESR_EL1 value is a correct re-encoding of the exception described by
+/// the given ESR_EL2 value, for a hypervisor which forwards an exception it trapped to its guest.
+///
+/// The exception class may change between its lower Exception level and same Exception level
+/// variants, as the guest may have been running at EL0 or EL1, but the instruction length must be
+/// preserved. For aborts, the fault details and instruction syndrome must be preserved (other than
+/// the fault status code for a stage 2 fault on a stage 1 translation table walk, which must be
+/// converted), and S1PTW and VNCR must be cleared. For other exceptions the whole ISS must be
+/// preserved.
+pub fn validate_forwarded(esr_el2: u64, esr_el1: u64) -> Result<Vec<ForwardingIssue>, DecodeError> {
+    let el2 = decode(esr_el2)?;
+    let el1 = decode(esr_el1)?;
+    let mut issues = vec![];
+
+    let el2_ec = esr_el2.get_bits(26..32);
+    let el1_ec = esr_el1.get_bits(26..32);
+    if exception_class_group(el2_ec) != exception_class_group(el1_ec) {
+        issues.push(ForwardingIssue::EcChanged {
+            el1: el1_ec,
+            el2: el2_ec,
+        });
+    }
+    let el2_il = esr_el2.get_bit(25);
+    let el1_il = esr_el1.get_bit(25);
+    if el2_il != el1_il {
+        issues.push(ForwardingIssue::IlChanged {
+            el1: el1_il,
+            el2: el2_il,
+        });
+    }
+
+    if abort_kind(&el2).is_some() && abort_kind(&el1) == abort_kind(&el2) {
+        let stage1_walk = iss_bit(&el2, "S1PTW");
+        for &name in PRESERVED_ABORT_FIELDS.iter() {
+            let (el2_field, el1_field) = if name == "DFSC" {
+                if stage1_walk {
+                    continue;
+                }
+                (
+                    iss_field(&el2, "DFSC").or_else(|| iss_field(&el2, "IFSC")),
+                    iss_field(&el1, "DFSC").or_else(|| iss_field(&el1, "IFSC")),
+                )
+            } else {
+                (iss_field(&el2, name), iss_field(&el1, name))
+            };
+            // Fields which are missing from either, such as the instruction syndrome when ISV is
+            // false, are covered by the fields which determine whether they are present.
+            if let (Some(el2_field), Some(el1_field)) = (el2_field, el1_field) {
+                if el2_field.value != el1_field.value {
+                    issues.push(ForwardingIssue::FieldChanged {
+                        name,
+                        el1: el1_field.value,
+                        el2: el2_field.value,
+                    });
+                }
+            }
+        }
+        for &name in EL2_ONLY_ABORT_FIELDS.iter() {
+            if iss_bit(&el1, name) {
+                issues.push(ForwardingIssue::NotCleared(name));
+            }
+        }
+    } else {
+        let el2_iss = esr_el2.get_bits(0..25);
+        let el1_iss = esr_el1.get_bits(0..25);
+        if el2_iss != el1_iss {
+            issues.push(ForwardingIssue::FieldChanged {
+                name: "ISS",
+                el1: el1_iss,
+                el2: el2_iss,
+            });
+        }
+    }
+    Ok(issues)
+}
+
+/// Returns the exception class with any distinction between exceptions from a lower Exception
+/// level and without a change in Exception level removed.
+fn exception_class_group(ec: u64) -> u64 {
+    match ec {
+        0b100001 | 0b100101 | 0b110001 | 0b110011 | 0b110101 => ec - 1,
+        _ => ec,
+    }
+}
+
+/// Decodes the given ESR_EL1 and ESR_EL2 values, where ESR_EL1 is the syndrome which a hypervisor
+/// injected into a guest after handling an exception described by ESR_EL2, and checks whether they
+/// are consistent with each other.
+pub fn decode_nested(esr_el1: u64, esr_el2: u64) -> Result<NestedReport, DecodeError> {
+    let el1 = decode(esr_el1)?;
+    let el2 = decode(esr_el2)?;
+    let description = format!(
+        "{} Injected into the guest as: {}",
+        summarize(esr_el2),
+        summarize(esr_el1)
+    );
+    let issues = match (abort_kind(&el1), abort_kind(&el2)) {
+        (Some(el1_kind), Some(el2_kind)) => check_aborts(&el1, el1_kind, &el2, el2_kind),
+        _ => vec![],
+    };
+    Ok(NestedReport {
+        esr_el1: el1,
+        esr_el2: el2,
+        description,
+        issues,
+    })
+}
+
+/// Checks the consistency of an abort injected at EL1 with the abort taken to EL2.
+fn check_aborts(
+    el1: &[FieldInfo],
+    el1_kind: &'static str,
+    el2: &[FieldInfo],
+    el2_kind: &'static str,
+) -> Vec<NestedIssue> {
+    let mut issues = vec![];
+    if el1_kind != el2_kind {
+        issues.push(NestedIssue::AbortKindMismatch {
+            el1: el1_kind,
+            el2: el2_kind,
+        });
+    }
+    if iss_bit(el1, "S1PTW") {
+        issues.push(NestedIssue::InjectedS1ptw);
+    }
+    if iss_bit(el1, "VNCR") {
+        issues.push(NestedIssue::InjectedVncr);
+    }
+    if iss_bit(el2, "S1PTW") {
+        let el1_fsc = iss_field(el1, "DFSC")
+            .or_else(|| iss_field(el1, "IFSC"))
+            .map(|fsc| fsc.value);
+        if !matches!(el1_fsc, Some(0b010011..=0b010111 | 0b011011..=0b011111)) {
+            issues.push(NestedIssue::Stage1WalkNotReported);
+        }
+    } else if el1_kind == "data" && el2_kind == "data" {
+        let el1_wnr = iss_bit(el1, "WnR");
+        let el2_wnr = iss_bit(el2, "WnR");
+        if el1_wnr != el2_wnr {
+            issues.push(NestedIssue::WnrMismatch {
+                el1: el1_wnr,
+                el2: el2_wnr,
+            });
+        }
+    }
+    issues
+}
+
+/// Returns "data" or "instruction" if the given decoded ESR is for a Data Abort or Instruction
+/// Abort.
+fn abort_kind(fields: &[FieldInfo]) -> Option<&'static str> {
+    match FieldInfo::find(fields, "EC")?.value {
+        0b100000 | 0b100001 => Some("instruction"),
+        0b100100 | 0b100101 => Some("data"),
+        _ => None,
+    }
+}
+
+fn iss_field<'a>(fields: &'a [FieldInfo], name: &str) -> Option<&'a FieldInfo> {
+    FieldInfo::find(&FieldInfo::find(fields, "ISS")?.subfields, name)
+}
+
+fn iss_bit(fields: &[FieldInfo], name: &str) -> bool {
+    iss_field(fields, name).is_some_and(FieldInfo::as_bit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage2_fault_on_stage1_walk() {
+        // Translation fault at level 2 on a stage 1 walk, injected as a synchronous external abort
+        // on a translation table walk at level 2.
+        let report = decode_nested(0x96000016, 0x92000086).unwrap();
+        assert_eq!(report.issues, vec![]);
+
+        // Injected as a translation fault instead.
+        let report = decode_nested(0x96000007, 0x92000086).unwrap();
+        assert_eq!(report.issues, vec![NestedIssue::Stage1WalkNotReported]);
+    }
+
+    #[test]
+    fn forwarded() {
+        // A data abort from EL1 with a valid syndrome forwarded correctly.
+        assert_eq!(validate_forwarded(0x93c08047, 0x97c08047).unwrap(), vec![]);
+        // An HVC forwarded with a different immediate.
+        assert_eq!(
+            validate_forwarded(0x5a000001, 0x5a000002).unwrap(),
+            vec![ForwardingIssue::FieldChanged {
+                name: "ISS",
+                el1: 0x2,
+                el2: 0x1,
+            }]
+        );
+        // The instruction syndrome was dropped, and S1PTW left set on a stage 2 fault on a stage 1
+        // walk which was correctly converted to an external abort on a translation table walk.
+        assert_eq!(
+            validate_forwarded(0x93c08086, 0x96000096).unwrap(),
+            vec![
+                ForwardingIssue::FieldChanged {
+                    name: "ISV",
+                    el1: 0,
+                    el2: 1,
+                },
+                ForwardingIssue::NotCleared("S1PTW"),
+            ]
+        );
+        // Forwarded as a different class with a different IL.
+        assert_eq!(
+            validate_forwarded(0x92000007, 0x84000007).unwrap(),
+            vec![
+                ForwardingIssue::EcChanged {
+                    el1: 0b100001,
+                    el2: 0b100100,
+                },
+                ForwardingIssue::IlChanged {
+                    el1: false,
+                    el2: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn mismatches() {
+        let report = decode_nested(0x86000087, 0x92000047).unwrap();
+        assert_eq!(
+            report.issues,
+            vec![
+                NestedIssue::AbortKindMismatch {
+                    el1: "instruction",
+                    el2: "data",
+                },
+                NestedIssue::InjectedS1ptw,
+            ]
+        );
+
+        let report = decode_nested(0x96000007, 0x92000047).unwrap();
+        assert_eq!(
+            report.issues,
+            vec![NestedIssue::WnrMismatch {
+                el1: false,
+                el2: true,
+            }]
+        );
+        assert_eq!(
+            report.description,
+            "Data Abort from a lower Exception level: Translation fault, level 3, on write. \
+             Injected into the guest as: Data Abort taken without a change in Exception level: \
+             Translation fault, level 3, on read."
+        );
+    }
+}
@@ -14,8 +14,8 @@
 
 use crate::{DecodeError, FieldInfo};
 
-/// Decodes the ISS value for a Pointer Authentication failure.
-pub fn decode_iss_pauth(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+/// Decodes the ISS value for a Pointer Authentication failure, and describes which key failed.
+pub fn decode_iss_pauth(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
     let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 2, 25).check_res0()?;
     let instruction_or_data =
         FieldInfo::get_bit(iss, "IorD", Some("Instruction key or Data key"), 1)
@@ -23,7 +23,15 @@ pub fn decode_iss_pauth(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     let a_or_b =
         FieldInfo::get_bit(iss, "AorB", Some("A key or B key"), 0).describe_bit(describe_a_or_b);
 
-    Ok(vec![res0, instruction_or_data, a_or_b])
+    let key = match (instruction_or_data.as_bit(), a_or_b.as_bit()) {
+        (false, false) => "APIAKey",
+        (false, true) => "APIBKey",
+        (true, false) => "APDAKey",
+        (true, true) => "APDBKey",
+    };
+    let description = format!("Authentication with {} failed", key);
+
+    Ok((vec![res0, instruction_or_data, a_or_b], Some(description)))
 }
 
 fn describe_instruction_or_data(instruction_or_data: bool) -> &'static str {
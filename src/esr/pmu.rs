@@ -0,0 +1,31 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{DecodeError, FieldInfo};
+
+/// Decodes the ISS value for a PMU exception.
+pub fn decode_iss_pmu(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 1, 25).check_res0()?;
+    let sync = FieldInfo::get_bit(iss, "SYNC", Some("Synchronous"), 0).describe_bit(describe_sync);
+
+    Ok(vec![res0, sync])
+}
+
+fn describe_sync(sync: bool) -> &'static str {
+    if sync {
+        "Synchronous PMU exception, taken on the instruction which caused the counter to overflow"
+    } else {
+        "Asynchronous PMU exception"
+    }
+}
@@ -0,0 +1,297 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::decode;
+use crate::FieldInfo;
+use thiserror::Error;
+
+/// A way in which decoding a register value and encoding the result again didn't give back the
+/// original value.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum RoundTripError {
+    /// A field's value has bits set beyond its width.
+    #[error("{field} value {value:#x} doesn't fit in {width} bits")]
+    ValueTooWide {
+        field: String,
+        value: u64,
+        width: usize,
+    },
+    /// A field extends beyond the field or register containing it.
+    #[error("{field} extends beyond its containing field")]
+    OutOfRange { field: String },
+    /// A field overlaps another field at the same level.
+    #[error("{field} overlaps another field")]
+    Overlap { field: String },
+    /// A field's sub-fields don't encode to the field's value.
+    #[error("Sub-fields of {field} encode to {encoded:#x} rather than {value:#x}")]
+    SubfieldMismatch {
+        field: String,
+        value: u64,
+        encoded: u64,
+    },
+    /// The top-level fields don't encode to the original register value.
+    #[error("Fields encode to {encoded:#x} rather than {value:#x}")]
+    Mismatch { value: u64, encoded: u64 },
+    /// Decoding the encoded fields gave different fields.
+    #[error("Decoding the encoded fields of {value:#x} gave different fields")]
+    Unstable { value: u64 },
+    /// Some bits of a field or the register aren't covered by any of its sub-fields.
+    #[error("Bits {bits:#x} of {field} aren't covered by any field")]
+    Uncovered { field: String, bits: u64 },
+}
+
+/// Encodes the given fields back into a register value, by placing each field's value at its
+/// position.
+///
+/// This is the inverse of decoding, for fields returned by any of the decoding functions.
+pub fn encode_fields(fields: &[FieldInfo]) -> u64 {
+    fields
+        .iter()
+        .fold(0, |value, field| value | field.value << field.start)
+}
+
+/// Checks that decoding the given ESR value and then encoding the resulting fields with
+/// [`encode_fields`] gives back the same value, and that decoding that again gives the same fields.
+///
+/// Also checks that the fields and sub-fields tile the register consistently: every value fits its
+/// width, no two fields at the same level overlap, and every field's sub-fields encode to its
+/// value. This is intended for use in tests, to catch mistakes in decoding tables. Values which
+/// can't be decoded are ignored.
+pub fn check_round_trip(esr: u64) -> Result<(), RoundTripError> {
+    let fields = match decode(esr) {
+        Ok(fields) => fields,
+        Err(_) => return Ok(()),
+    };
+    check_fields(&fields, "", 64)?;
+    let encoded = encode_fields(&fields);
+    if encoded != esr {
+        return Err(RoundTripError::Mismatch {
+            value: esr,
+            encoded,
+        });
+    }
+    if decode(encoded).ok().as_ref() != Some(&fields) {
+        return Err(RoundTripError::Unstable { value: esr });
+    }
+    Ok(())
+}
+
+/// Checks that every bit of the given ESR value is covered by one of its decoded fields, and that
+/// every bit of each field which has sub-fields is covered by one of them.
+///
+/// Together with [`check_round_trip`], which checks that fields don't overlap, this means each bit
+/// is explained exactly once at each level. Values which can't be decoded are ignored.
+pub fn check_coverage(esr: u64) -> Result<(), RoundTripError> {
+    match decode(esr) {
+        Ok(fields) => check_fields_cover(&fields, "ESR", 64),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Checks that the given fields cover every bit of a containing field of the given width, and
+/// recursively that their sub-fields cover them.
+fn check_fields_cover(
+    fields: &[FieldInfo],
+    parent: &str,
+    width: usize,
+) -> Result<(), RoundTripError> {
+    let mut used = 0u128;
+    for field in fields {
+        used |= ((1u128 << field.width) - 1) << field.start;
+        if !field.subfields.is_empty() {
+            check_fields_cover(
+                &field.subfields,
+                &format!("{}.{}", parent, field.canonical_name(fields)),
+                field.width,
+            )?;
+        }
+    }
+    let uncovered = !used & ((1u128 << width) - 1);
+    if uncovered != 0 {
+        return Err(RoundTripError::Uncovered {
+            field: parent.to_owned(),
+            bits: uncovered as u64,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that the given fields fit within a containing field of the given width without
+/// overlapping, and that their sub-fields are consistent with them.
+fn check_fields(fields: &[FieldInfo], parent: &str, width: usize) -> Result<(), RoundTripError> {
+    let mut used = 0u128;
+    for field in fields {
+        let name = if parent.is_empty() {
+            field.canonical_name(fields)
+        } else {
+            format!("{}.{}", parent, field.canonical_name(fields))
+        };
+        if field.width < 64 && field.value >> field.width != 0 {
+            return Err(RoundTripError::ValueTooWide {
+                field: name,
+                value: field.value,
+                width: field.width,
+            });
+        }
+        if field.start + field.width > width {
+            return Err(RoundTripError::OutOfRange { field: name });
+        }
+        let mask = ((1u128 << field.width) - 1) << field.start;
+        if used & mask != 0 {
+            return Err(RoundTripError::Overlap { field: name });
+        }
+        used |= mask;
+        if !field.subfields.is_empty() {
+            check_fields(&field.subfields, &name, field.width)?;
+            let encoded = encode_fields(&field.subfields);
+            if encoded != field.value {
+                return Err(RoundTripError::SubfieldMismatch {
+                    field: name,
+                    value: field.value,
+                    encoded,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DataAbort, Fault, Injection};
+
+    /// Returns a deterministic sequence of pseudo-random ISS values, including some edge cases.
+    fn iss_values() -> impl Iterator<Item = u64> {
+        let mut state = 0x2545f4914f6cdd1du64;
+        [0, 1, 0x1ff_ffff]
+            .iter()
+            .copied()
+            .chain((0..500).map(move |_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state & 0x1ff_ffff
+            }))
+    }
+
+    #[test]
+    fn all_exception_classes() {
+        for ec in 0..0b111111u64 {
+            for iss2 in [0, 0b11111u64] {
+                for il in 0..2u64 {
+                    for iss in iss_values() {
+                        let esr = iss2 << 32 | ec << 26 | il << 25 | iss;
+                        assert_eq!(check_round_trip(esr), Ok(()), "ESR {:#x}", esr);
+                        assert_eq!(check_coverage(esr), Ok(()), "ESR {:#x}", esr);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mistakes() {
+        let register = 0xf0;
+        let mut iss = FieldInfo::get(register, "ISS", None, 0, 25);
+        iss.subfields = vec![
+            FieldInfo::get(register, "A", None, 4, 8),
+            FieldInfo::get(register, "B", None, 6, 10),
+        ];
+        assert_eq!(
+            check_fields(&[iss.clone()], "", 64),
+            Err(RoundTripError::Overlap {
+                field: "ISS.B".to_string()
+            })
+        );
+        iss.subfields = vec![FieldInfo::get(register, "A", None, 4, 7)];
+        assert_eq!(
+            check_fields_cover(&[iss.clone()], "ESR", 64),
+            Err(RoundTripError::Uncovered {
+                field: "ESR.ISS".to_string(),
+                bits: 0x1ff_ff8f,
+            })
+        );
+        assert_eq!(
+            check_fields(&[iss], "", 64),
+            Err(RoundTripError::SubfieldMismatch {
+                field: "ISS".to_string(),
+                value: 0xf0,
+                encoded: 0x70,
+            })
+        );
+    }
+
+    #[test]
+    fn data_aborts() {
+        for instruction in &[
+            "ldr x0, [x1]",
+            "strb w3, [x5]",
+            "ldrsh x2, [sp, #8]",
+            "stp x0, x1, [sp]",
+        ] {
+            for &fault in &[Fault::Translation(3), Fault::SynchronousExternal] {
+                let esr = DataAbort::from_instruction(instruction, fault)
+                    .unwrap()
+                    .encode();
+                assert_eq!(check_round_trip(esr), Ok(()), "{}", instruction);
+                assert!(decode(esr).is_ok(), "{}", instruction);
+            }
+        }
+    }
+
+    #[test]
+    fn injections() {
+        let faults = [
+            Fault::AddressSize(0),
+            Fault::Translation(3),
+            Fault::AccessFlag(2),
+            Fault::Permission(1),
+            Fault::SynchronousExternal,
+            Fault::TagCheck,
+            Fault::ParityOrEcc,
+            Fault::Alignment,
+            Fault::TlbConflict,
+        ];
+        for &fault in &faults {
+            for &write in &[false, true] {
+                for &far_valid in &[false, true] {
+                    for &from_el0 in &[false, true] {
+                        let injections = [
+                            Injection::DataAbort {
+                                fault,
+                                write,
+                                far_valid,
+                                from_el0,
+                            },
+                            Injection::InstructionAbort {
+                                fault,
+                                far_valid,
+                                from_el0,
+                            },
+                        ];
+                        for injection in &injections {
+                            if let Ok(esr) = injection.encode() {
+                                assert_eq!(check_round_trip(esr), Ok(()), "{:?}", injection);
+                                assert!(decode(esr).is_ok(), "{:?}", injection);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let esr = Injection::SError.encode().unwrap();
+        assert_eq!(check_round_trip(esr), Ok(()));
+    }
+}
@@ -72,7 +72,7 @@ fn describe_aet(aet: u64) -> Result<&'static str, DecodeError> {
         0b001 => Ok("Unrecoverable state (UEU)"),
         0b010 => Ok("Restartable state (UEO)"),
         0b011 => Ok("Recoverable state (UER)"),
-        0x110 => Ok("Corrected (CE)"),
+        0b110 => Ok("Corrected (CE)"),
         _ => Err(DecodeError::InvalidAet { aet }),
     }
 }
@@ -0,0 +1,145 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::decode;
+use bit_field::BitField;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// How serious an exception is likely to be, from expected to fatal.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// An exception which is an expected part of normal operation, such as a system call, a
+    /// breakpoint or a WFI/WFE trap.
+    Info,
+    /// A trapped instruction or register access which may need emulating, such as an MSR or
+    /// floating-point trap.
+    Warning,
+    /// A fault which is probably a software bug, such as a translation fault or alignment fault, or
+    /// a value which couldn't be decoded.
+    Error,
+    /// A hardware error, such as an SError or a synchronous external abort or ECC error.
+    Fatal,
+}
+
+impl Severity {
+    const ALL: [Self; 4] = [Self::Info, Self::Warning, Self::Error, Self::Fatal];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+            Self::Fatal => "fatal",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = ParseSeverityError;
+
+    /// Parses a severity name such as "warning", in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|severity| s.eq_ignore_ascii_case(severity.name()))
+            .ok_or_else(|| ParseSeverityError(s.to_owned()))
+    }
+}
+
+/// An error parsing a severity.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[error("Unknown severity {0:?}")]
+pub struct ParseSeverityError(String);
+
+/// Classifies how serious the exception described by the given ESR value is likely to be.
+///
+/// Values which can't be decoded are classified as `Severity::Error`.
+pub fn severity(esr: u64) -> Severity {
+    if decode(esr).is_err() {
+        return Severity::Error;
+    }
+    let ec = esr.get_bits(26..32);
+    let fsc = esr.get_bits(0..6);
+    match ec {
+        // SVC, HVC, SMC, BKPT and BRK instructions, debug exceptions and WF* traps.
+        0b000001
+        | 0b010001
+        | 0b010010
+        | 0b010011
+        | 0b010101
+        | 0b010110
+        | 0b010111
+        | 0b110000..=0b110101
+        | 0b111000
+        | 0b111010
+        | 0b111100
+        | 0b111101 => Severity::Info,
+        // Trapped instructions and floating-point exceptions.
+        0b000011..=0b001010
+        | 0b001100
+        | 0b010100
+        | 0b011000
+        | 0b011001
+        | 0b011010
+        | 0b011011
+        | 0b011101
+        | 0b100111
+        | 0b101000
+        | 0b101100 => Severity::Warning,
+        // Instruction and data aborts caused by external aborts or parity or ECC errors.
+        0b100000 | 0b100001 | 0b100100 | 0b100101 if is_external_abort(fsc) => Severity::Fatal,
+        0b101111 => Severity::Fatal,
+        _ => Severity::Error,
+    }
+}
+
+/// Returns whether the given DFSC or IFSC value indicates a synchronous external abort or parity
+/// or ECC error.
+fn is_external_abort(fsc: u64) -> bool {
+    matches!(fsc, 0b010000 | 0b010011..=0b011111)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify() {
+        assert_eq!(severity(0x56000000), Severity::Info);
+        assert_eq!(severity(0xf2000800), Severity::Info);
+        assert_eq!(severity(0x62300021), Severity::Warning);
+        assert_eq!(severity(0x96000045), Severity::Error);
+        assert_eq!(severity(0x96000050), Severity::Fatal);
+        assert_eq!(severity(0xbe000000), Severity::Fatal);
+        assert_eq!(severity(0xffffffff), Severity::Error);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("error".parse(), Ok(Severity::Error));
+        assert_eq!("Fatal".parse(), Ok(Severity::Fatal));
+        assert!("bad".parse::<Severity>().is_err());
+        assert!(Severity::Info < Severity::Warning);
+        assert_eq!(Severity::Warning.to_string(), "warning");
+    }
+}
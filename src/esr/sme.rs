@@ -0,0 +1,40 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{DecodeError, FieldInfo};
+
+/// Decodes the ISS value for a trapped SME, SVE or Advanced SIMD access due to SME functionality.
+pub fn decode_iss_sme(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 3, 25).check_res0()?;
+    let smtc = FieldInfo::get(iss, "SMTC", Some("SME Trap Code"), 0, 3).describe(describe_smtc)?;
+
+    Ok(vec![res0, smtc])
+}
+
+fn describe_smtc(smtc: u64) -> Result<&'static str, DecodeError> {
+    match smtc {
+        0b000 => Ok(
+            "Access to SME functionality trapped by CPACR_EL1.SMEN, CPTR_EL2.SMEN, \
+                     CPTR_EL2.TSM or CPTR_EL3.ESM",
+        ),
+        0b001 => Ok("Advanced SIMD, SVE or SVE2 instruction trapped because PSTATE.SM is 1"),
+        0b010 => Ok("SME instruction trapped because PSTATE.ZA is 0"),
+        0b011 => Ok("SME instruction trapped because PSTATE.SM is 0"),
+        0b100 => Ok(
+            "Access to ZT0 trapped because SMCR_EL1.EZT0, SMCR_EL2.EZT0 or \
+                     SMCR_EL3.EZT0 is 0",
+        ),
+        _ => Err(DecodeError::InvalidSmtc { smtc }),
+    }
+}
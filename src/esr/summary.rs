@@ -0,0 +1,103 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{decode_with_options, DecodeOptions};
+use crate::FieldInfo;
+
+/// Returns a one-sentence explanation of the given Exception Syndrome Register value, combining the
+/// exception class with the most important facts from the ISS.
+///
+/// If the value is not valid then the sentence will explain why.
+pub fn summarize(esr: u64) -> String {
+    summarize_with_options(esr, &DecodeOptions::default())
+}
+
+/// Returns a one-sentence explanation of the given Exception Syndrome Register value, decoded with
+/// the given options so that it agrees with the fields from [`decode_with_options`].
+///
+/// If the value is not valid then the sentence will explain why.
+pub fn summarize_with_options(esr: u64, options: &DecodeOptions) -> String {
+    match decode_with_options(esr, options) {
+        Ok(fields) => summarize_fields(&fields),
+        Err(e) => format!("Invalid ESR {:#x}: {}.", esr, e),
+    }
+}
+
+/// Returns a one-sentence summary of the given decoded ESR fields, for callers which have already
+/// decoded the value.
+pub fn summarize_fields(fields: &[FieldInfo]) -> String {
+    let class = FieldInfo::find(fields, "EC")
+        .and_then(|ec| ec.description.as_deref())
+        .unwrap_or("Unknown exception class");
+    let mut facts = vec![];
+    if let Some(iss) = FieldInfo::find(fields, "ISS") {
+        facts.extend(iss.description.clone());
+        for name in FACT_FIELDS {
+            if let Some(fact) = FieldInfo::find(&iss.subfields, name).and_then(describe_fact) {
+                facts.push(fact);
+            }
+        }
+    }
+
+    if facts.is_empty() {
+        format!("{}.", class)
+    } else {
+        format!("{}: {}.", class, facts.join(", "))
+    }
+}
+
+/// The ISS subfields which may be included in a summary, in the order they should be included.
+const FACT_FIELDS: [&str; 10] = [
+    "DFSC", "IFSC", "GPCSC", "TI", "AET", "SMTC", "ISS", "imm16", "Comment", "WnR",
+];
+
+/// Returns the fact to include in the summary for the given ISS subfield, if it is a decisive one.
+fn describe_fact(field: &FieldInfo) -> Option<String> {
+    match field.name {
+        "DFSC" | "IFSC" | "GPCSC" | "AET" | "SMTC" => field
+            .description
+            .as_deref()
+            .map(|description| description.trim_end_matches('.').to_string()),
+        // Leave out the note about which controls trap the instruction.
+        "TI" => field
+            .description
+            .as_deref()
+            .and_then(|description| description.split(" (").next())
+            .map(ToOwned::to_owned),
+        "WnR" => Some(
+            if field.as_bit() {
+                "on write"
+            } else {
+                "on read"
+            }
+            .to_string(),
+        ),
+        "imm16" | "Comment" => Some(format!("immediate {}", field.value_string())),
+        "ISS" => field.description.clone(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::selftest::golden_summaries;
+
+    #[test]
+    fn golden() {
+        for (esr, summary) in golden_summaries() {
+            assert_eq!(summarize(esr), summary, "ESR {:#x}", esr);
+        }
+    }
+}
@@ -12,21 +12,38 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::common::describe_cv;
-use crate::{DecodeError, FieldInfo};
+use super::common::{describe_cv, get_cond};
+use super::ExecutionState;
+use crate::{ArchVersion, DecodeError, FieldInfo};
 
 /// Decodes the ISS value for a trapped SVE, Advanced SIMD or FP instruction.
-pub fn decode_iss_sve(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+pub fn decode_iss_sve(
+    iss: u64,
+    state: Option<ExecutionState>,
+) -> Result<Vec<FieldInfo>, DecodeError> {
     let cv =
         FieldInfo::get_bit(iss, "CV", Some("Condition code valid"), 24).describe_bit(describe_cv);
-    let cond = FieldInfo::get(
-        iss,
-        "COND",
-        Some("Condition code of the trapped instruction"),
-        20,
-        24,
-    );
+    let cond = get_cond(iss, state);
     let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 0, 20).check_res0()?;
 
     Ok(vec![cv, cond, res0])
 }
+
+/// Decodes the ISS value for an access to SVE functionality trapped by CPACR_EL1.ZEN,
+/// CPTR_EL2.ZEN, CPTR_EL2.TZ or CPTR_EL3.EZ.
+///
+/// The ISS is RES0 in all versions of the architecture which this crate knows about, but rather
+/// than failing if it is non-zero the value is reported as reserved, as later versions may use it
+/// to encode the reason for the trap.
+pub fn decode_iss_sve_access(iss: u64, arch_version: ArchVersion) -> Vec<FieldInfo> {
+    let res0 = FieldInfo::get(iss, "RES0", Some("Reserved"), 0, 25);
+    let description = if res0.value == 0 {
+        format!("ISS is RES0 in {}", arch_version)
+    } else {
+        format!(
+            "ISS is RES0 in {}, but is non-zero so may be defined by a later version",
+            arch_version
+        )
+    };
+    vec![res0.with_description(description)]
+}
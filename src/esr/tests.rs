@@ -1,5 +1,8 @@
-use super::decode;
-use crate::FieldInfo;
+use super::{
+    decode, decode_with_options, far_valid, summarize, summarize_with_options, DecodeOptions,
+    ExecutionState,
+};
+use crate::{ArchVersion, DecodeError, Derived, FieldInfo, Unit};
 
 #[test]
 fn unknown() {
@@ -10,19 +13,21 @@ fn unknown() {
             FieldInfo {
                 name: "RES0",
                 long_name: Some("Reserved"),
-                start: 37,
-                width: 27,
+                start: 56,
+                width: 8,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
                 name: "ISS2",
                 long_name: None,
                 start: 32,
-                width: 5,
+                width: 24,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -32,6 +37,7 @@ fn unknown() {
                 width: 6,
                 value: 0,
                 description: Some("Unknown reason".to_string()),
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -41,6 +47,7 @@ fn unknown() {
                 width: 1,
                 value: 0,
                 description: Some("16-bit instruction trapped".to_string()),
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -50,6 +57,7 @@ fn unknown() {
                 width: 25,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![FieldInfo {
                     name: "RES0",
                     long_name: Some("Reserved"),
@@ -57,6 +65,7 @@ fn unknown() {
                     width: 25,
                     value: 0,
                     description: Some("ISS is RES0".to_string()),
+                    derived: None,
                     subfields: vec![],
                 }],
             },
@@ -72,20 +81,113 @@ fn data_abort() {
             FieldInfo {
                 name: "RES0",
                 long_name: Some("Reserved"),
-                start: 37,
-                width: 27,
+                start: 56,
+                width: 8,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
                 name: "ISS2",
                 long_name: None,
                 start: 32,
-                width: 5,
+                width: 24,
                 value: 0,
                 description: None,
-                subfields: vec![],
+                derived: None,
+                subfields: vec![
+                    FieldInfo {
+                        name: "RES0",
+                        long_name: Some("Reserved"),
+                        start: 12,
+                        width: 12,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "HDBSSF",
+                        long_name: Some("HDBSS Full"),
+                        start: 11,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "TnD",
+                        long_name: Some("Tag not Data"),
+                        start: 10,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "TagAccess",
+                        long_name: Some("Tag Access"),
+                        start: 9,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "GCS",
+                        long_name: Some("Guarded Control Stack data access"),
+                        start: 8,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "AssuredOnly",
+                        long_name: None,
+                        start: 7,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "Overlay",
+                        long_name: Some("Permission Overlay"),
+                        start: 6,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "DirtyBit",
+                        long_name: None,
+                        start: 5,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "Xs",
+                        long_name: Some("Register for the status result of ST64BV or ST64BV0"),
+                        start: 0,
+                        width: 5,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                ],
             },
             FieldInfo {
                 name: "EC",
@@ -96,6 +198,7 @@ fn data_abort() {
                 description: Some(
                     "Data Abort taken without a change in Exception level".to_string()
                 ),
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -105,6 +208,7 @@ fn data_abort() {
                 width: 1,
                 value: 1,
                 description: Some("32-bit instruction trapped".to_string()),
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -114,6 +218,7 @@ fn data_abort() {
                 width: 25,
                 value: 80,
                 description: None,
+                derived: None,
                 subfields: vec![
                     FieldInfo {
                         name: "ISV",
@@ -122,6 +227,7 @@ fn data_abort() {
                         width: 1,
                         value: 0,
                         description: Some("No valid instruction syndrome".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -131,6 +237,7 @@ fn data_abort() {
                         width: 10,
                         value: 0,
                         description: None,
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -139,7 +246,11 @@ fn data_abort() {
                         start: 13,
                         width: 1,
                         value: 0,
-                        description: None,
+                        description: Some(
+                            "Fault not from a System register access redirected by VNCR_EL2"
+                                .to_string()
+                        ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -149,6 +260,7 @@ fn data_abort() {
                         width: 2,
                         value: 0,
                         description: Some("Recoverable state (UER)".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -158,6 +270,7 @@ fn data_abort() {
                         width: 1,
                         value: 0,
                         description: Some("FAR is valid".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -166,7 +279,10 @@ fn data_abort() {
                         start: 9,
                         width: 1,
                         value: 0,
-                        description: None,
+                        description: Some(
+                            "IMPLEMENTATION DEFINED classification of external aborts".to_string()
+                        ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -175,7 +291,11 @@ fn data_abort() {
                         start: 8,
                         width: 1,
                         value: 0,
-                        description: None,
+                        description: Some(
+                            "Abort not caused by a cache maintenance or address translation instruction"
+                                .to_string(),
+                        ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -184,7 +304,11 @@ fn data_abort() {
                         start: 7,
                         width: 1,
                         value: 0,
-                        description: None,
+                        description: Some(
+                            "Fault not on a stage 2 translation for a stage 1 translation table walk"
+                                .to_string(),
+                        ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -194,6 +318,7 @@ fn data_abort() {
                         width: 1,
                         value: 1,
                         description: Some("Abort caused by writing to memory".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -207,6 +332,7 @@ fn data_abort() {
                                          walk or hardware update of translation table."
                                 .to_string()
                         ),
+                        derived: None,
                         subfields: vec![],
                     }
                 ]
@@ -223,20 +349,113 @@ fn data_abort_isv() {
             FieldInfo {
                 name: "RES0",
                 long_name: Some("Reserved"),
-                start: 37,
-                width: 27,
+                start: 56,
+                width: 8,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
                 name: "ISS2",
                 long_name: None,
                 start: 32,
-                width: 5,
+                width: 24,
                 value: 0,
                 description: None,
-                subfields: vec![],
+                derived: None,
+                subfields: vec![
+                    FieldInfo {
+                        name: "RES0",
+                        long_name: Some("Reserved"),
+                        start: 12,
+                        width: 12,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "HDBSSF",
+                        long_name: Some("HDBSS Full"),
+                        start: 11,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "TnD",
+                        long_name: Some("Tag not Data"),
+                        start: 10,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "TagAccess",
+                        long_name: Some("Tag Access"),
+                        start: 9,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "GCS",
+                        long_name: Some("Guarded Control Stack data access"),
+                        start: 8,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "AssuredOnly",
+                        long_name: None,
+                        start: 7,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "Overlay",
+                        long_name: Some("Permission Overlay"),
+                        start: 6,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "DirtyBit",
+                        long_name: None,
+                        start: 5,
+                        width: 1,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                    FieldInfo {
+                        name: "Xs",
+                        long_name: Some("Register for the status result of ST64BV or ST64BV0"),
+                        start: 0,
+                        width: 5,
+                        value: 0,
+                        description: None,
+                        derived: None,
+                        subfields: vec![],
+                    },
+                ],
             },
             FieldInfo {
                 name: "EC",
@@ -247,6 +466,7 @@ fn data_abort_isv() {
                 description: Some(
                     "Data Abort taken without a change in Exception level".to_string()
                 ),
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -256,6 +476,7 @@ fn data_abort_isv() {
                 width: 1,
                 value: 1,
                 description: Some("32-bit instruction trapped".to_string()),
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -264,7 +485,8 @@ fn data_abort_isv() {
                 start: 0,
                 width: 25,
                 value: 22163536,
-                description: None,
+                description: Some("32-bit halfword store from w18, no acquire/release".to_string()),
+                derived: None,
                 subfields: vec![
                     FieldInfo {
                         name: "ISV",
@@ -273,6 +495,7 @@ fn data_abort_isv() {
                         width: 1,
                         value: 1,
                         description: Some("Valid instruction syndrome".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -282,6 +505,10 @@ fn data_abort_isv() {
                         width: 2,
                         value: 1,
                         description: Some("halfword".to_string()),
+                        derived: Some(Derived {
+                            value: 2,
+                            unit: Unit::Bytes,
+                        }),
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -291,6 +518,7 @@ fn data_abort_isv() {
                         width: 1,
                         value: 0,
                         description: None,
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -299,7 +527,8 @@ fn data_abort_isv() {
                         start: 16,
                         width: 5,
                         value: 18,
-                        description: None,
+                        description: Some("w18".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -309,6 +538,7 @@ fn data_abort_isv() {
                         width: 1,
                         value: 0,
                         description: Some("32-bit wide register".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -318,6 +548,7 @@ fn data_abort_isv() {
                         width: 1,
                         value: 0,
                         description: Some("No acquire/release semantics".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -326,7 +557,12 @@ fn data_abort_isv() {
                         start: 13,
                         width: 1,
                         value: 1,
-                        description: None,
+                        description: Some(
+                            "Fault from a System register access redirected to memory by VNCR_EL2, \
+                             for a guest hypervisor under nested virtualization (FEAT_NV2)"
+                                .to_string()
+                        ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -336,6 +572,7 @@ fn data_abort_isv() {
                         width: 2,
                         value: 2,
                         description: Some("Uncontainable (UC)".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -345,6 +582,7 @@ fn data_abort_isv() {
                         width: 1,
                         value: 0,
                         description: Some("FAR is valid".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -353,7 +591,10 @@ fn data_abort_isv() {
                         start: 9,
                         width: 1,
                         value: 0,
-                        description: None,
+                        description: Some(
+                            "IMPLEMENTATION DEFINED classification of external aborts".to_string()
+                        ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -362,7 +603,11 @@ fn data_abort_isv() {
                         start: 8,
                         width: 1,
                         value: 0,
-                        description: None,
+                        description: Some(
+                            "Abort not caused by a cache maintenance or address translation instruction"
+                                .to_string(),
+                        ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -371,7 +616,11 @@ fn data_abort_isv() {
                         start: 7,
                         width: 1,
                         value: 0,
-                        description: None,
+                        description: Some(
+                            "Fault not on a stage 2 translation for a stage 1 translation table walk"
+                                .to_string(),
+                        ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -381,6 +630,7 @@ fn data_abort_isv() {
                         width: 1,
                         value: 1,
                         description: Some("Abort caused by writing to memory".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -394,6 +644,7 @@ fn data_abort_isv() {
                                          walk or hardware update of translation table."
                                 .to_string()
                         ),
+                        derived: None,
                         subfields: vec![],
                     }
                 ]
@@ -410,19 +661,21 @@ fn instruction_abort() {
             FieldInfo {
                 name: "RES0",
                 long_name: Some("Reserved"),
-                start: 37,
-                width: 27,
+                start: 56,
+                width: 8,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
                 name: "ISS2",
                 long_name: None,
                 start: 32,
-                width: 5,
+                width: 24,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -432,6 +685,7 @@ fn instruction_abort() {
                 width: 6,
                 value: 32,
                 description: Some("Instruction Abort from a lower Exception level".to_string()),
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -441,6 +695,7 @@ fn instruction_abort() {
                 width: 1,
                 value: 1,
                 description: Some("32-bit instruction trapped".to_string()),
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -450,6 +705,7 @@ fn instruction_abort() {
                 width: 25,
                 value: 7696,
                 description: None,
+                derived: None,
                 subfields: vec![
                     FieldInfo {
                         name: "RES0",
@@ -458,6 +714,7 @@ fn instruction_abort() {
                         width: 12,
                         value: 0,
                         description: None,
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -467,6 +724,7 @@ fn instruction_abort() {
                         width: 2,
                         value: 3,
                         description: Some("Restartable state (UEO)".to_string()),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -478,6 +736,7 @@ fn instruction_abort() {
                         description: Some(
                             "FAR is not valid, it holds an unknown value".to_string()
                         ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -486,7 +745,10 @@ fn instruction_abort() {
                         start: 9,
                         width: 1,
                         value: 1,
-                        description: None,
+                        description: Some(
+                            "IMPLEMENTATION DEFINED classification of external aborts".to_string()
+                        ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -496,6 +758,7 @@ fn instruction_abort() {
                         width: 1,
                         value: 0,
                         description: None,
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -504,7 +767,11 @@ fn instruction_abort() {
                         start: 7,
                         width: 1,
                         value: 0,
-                        description: None,
+                        description: Some(
+                            "Fault not on a stage 2 translation for a stage 1 translation table walk"
+                                .to_string(),
+                        ),
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -514,6 +781,7 @@ fn instruction_abort() {
                         width: 1,
                         value: 0,
                         description: None,
+                        derived: None,
                         subfields: vec![],
                     },
                     FieldInfo {
@@ -527,6 +795,7 @@ fn instruction_abort() {
                                          walk or hardware update of translation table."
                                 .to_string()
                         ),
+                        derived: None,
                         subfields: vec![],
                     }
                 ]
@@ -543,19 +812,21 @@ fn sve() {
             FieldInfo {
                 name: "RES0",
                 long_name: Some("Reserved"),
-                start: 37,
-                width: 27,
+                start: 56,
+                width: 8,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
                 name: "ISS2",
                 long_name: None,
                 start: 32,
-                width: 5,
+                width: 24,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -567,6 +838,7 @@ fn sve() {
                 description: Some(
                     "Trapped access to SVE, Advanced SIMD or floating point".to_string()
                 ),
+                derived: None,
                 subfields: vec![]
             },
             FieldInfo {
@@ -576,6 +848,7 @@ fn sve() {
                 width: 1,
                 value: 1,
                 description: Some("32-bit instruction trapped".to_string()),
+                derived: None,
                 subfields: vec![]
             },
             FieldInfo {
@@ -585,6 +858,7 @@ fn sve() {
                 width: 25,
                 value: 19922944,
                 description: None,
+                derived: None,
                 subfields: vec![
                     FieldInfo {
                         name: "CV",
@@ -593,6 +867,7 @@ fn sve() {
                         width: 1,
                         value: 1,
                         description: Some("COND is valid".to_string()),
+                        derived: None,
                         subfields: vec![]
                     },
                     FieldInfo {
@@ -602,6 +877,7 @@ fn sve() {
                         width: 4,
                         value: 3,
                         description: None,
+                        derived: None,
                         subfields: vec![]
                     },
                     FieldInfo {
@@ -611,6 +887,7 @@ fn sve() {
                         width: 20,
                         value: 0,
                         description: None,
+                        derived: None,
                         subfields: vec![]
                     }
                 ]
@@ -627,19 +904,21 @@ fn ld64b() {
             FieldInfo {
                 name: "RES0",
                 long_name: Some("Reserved"),
-                start: 37,
-                width: 27,
+                start: 56,
+                width: 8,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
                 name: "ISS2",
                 long_name: None,
                 start: 32,
-                width: 5,
+                width: 24,
                 value: 0,
                 description: None,
+                derived: None,
                 subfields: vec![],
             },
             FieldInfo {
@@ -652,6 +931,7 @@ fn ld64b() {
                     "Trapped execution of an LD64B, ST64B, ST64BV, or ST64BV0 instruction"
                         .to_string()
                 ),
+                derived: None,
                 subfields: vec![]
             },
             FieldInfo {
@@ -661,6 +941,7 @@ fn ld64b() {
                 width: 1,
                 value: 1,
                 description: Some("32-bit instruction trapped".to_string()),
+                derived: None,
                 subfields: vec![]
             },
             FieldInfo {
@@ -670,6 +951,7 @@ fn ld64b() {
                 width: 25,
                 value: 2,
                 description: None,
+                derived: None,
                 subfields: vec![FieldInfo {
                     name: "ISS",
                     long_name: None,
@@ -677,9 +959,1057 @@ fn ld64b() {
                     width: 25,
                     value: 2,
                     description: Some("LD64B or ST64B trapped".to_string()),
+                    derived: None,
                     subfields: vec![]
                 }]
             }
         ]
     );
 }
+
+#[test]
+fn data_abort_isv_aarch32() {
+    // Data Abort from a lower Exception level, ISV, halfword store from r3.
+    let esr = 0x93434040;
+    let srt = |options| decode_with_options(esr, &options).unwrap()[4].subfields[3].clone();
+
+    assert_eq!(
+        srt(DecodeOptions::default()).description,
+        Some("w3".to_string())
+    );
+    assert_eq!(
+        srt(DecodeOptions {
+            execution_state: Some(ExecutionState::from_spsr(0x10)),
+            ..Default::default()
+        })
+        .description,
+        Some("r3".to_string())
+    );
+
+    // The same store from register 19, which is SP_svc in the AArch64 view.
+    assert_eq!(
+        decode_with_options(
+            0x93534040,
+            &DecodeOptions {
+                execution_state: Some(ExecutionState::from_spsr(0x10)),
+                ..Default::default()
+            }
+        )
+        .unwrap()[4]
+            .subfields[3]
+            .description,
+        Some("SP_svc".to_string())
+    );
+}
+
+#[test]
+fn cond_aarch64() {
+    let decoded = decode_with_options(
+        0x1fe00000,
+        &DecodeOptions {
+            execution_state: Some(ExecutionState::AArch64),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        decoded[4].subfields[1].description,
+        Some("Not used for exceptions taken from AArch64 state".to_string())
+    );
+}
+
+#[test]
+fn sve_access() {
+    let decoded = decode(0x66000000).unwrap();
+    assert_eq!(
+        decoded[4].subfields[0].description,
+        Some("ISS is RES0 in Armv9.5".to_string())
+    );
+
+    // Non-zero reserved bits shouldn't cause an error.
+    let decoded = decode_with_options(
+        0x66000003,
+        &DecodeOptions {
+            arch_version: ArchVersion::Armv9_2,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        decoded[4].subfields[0].description,
+        Some(
+            "ISS is RES0 in Armv9.2, but is non-zero so may be defined by a later version"
+                .to_string()
+        )
+    );
+
+    // SVE didn't exist before Armv8.2.
+    assert!(matches!(
+        decode_with_options(
+            0x66000000,
+            &DecodeOptions {
+                arch_version: ArchVersion::Armv8_1,
+                ..Default::default()
+            },
+        ),
+        Err(DecodeError::InvalidEc { ec: 0b011001 })
+    ));
+}
+
+#[cfg(feature = "profiling")]
+#[test]
+fn msr_brbe_trbe() {
+    let decoded = decode(0x62206461).unwrap();
+    assert_eq!(
+        decoded[4].description,
+        Some("MRS x3, BRBCR_EL1".to_string())
+    );
+    let decoded = decode(0x62362476).unwrap();
+    assert_eq!(
+        decoded[4].description,
+        Some("MSR TRBSR_EL1, x3".to_string())
+    );
+}
+
+#[test]
+fn msr_amu() {
+    let amu_note = "Activity Monitors register, accesses from EL0 trap to EL1 unless \
+                    AMUSERENR_EL0.EN is set, to EL2 if CPTR_EL2.TAM is set, or to EL3 if \
+                    CPTR_EL3.TAM is set";
+    assert_eq!(
+        decode(0x6236f449).unwrap()[4].description,
+        Some(format!("MRS x2, AMEVCNTR03_EL0 ({})", amu_note))
+    );
+    assert_eq!(
+        decode(0x6238f41e).unwrap()[4].description,
+        Some(format!("MSR AMEVTYPER112_EL0, x0 ({})", amu_note))
+    );
+    assert_eq!(
+        decode(0x6230f425).unwrap()[4].description,
+        Some(format!("MRS x1, AMCR_EL0 ({})", amu_note))
+    );
+}
+
+#[test]
+fn debug_registers() {
+    let tda = "accesses trap to EL2 if MDCR_EL2.TDA or MDCR_EL2.TDE is set, or to EL3 if \
+               MDCR_EL3.TDA is set";
+    assert_eq!(
+        decode(0x622a0026).unwrap()[4].description,
+        Some(format!(
+            "MSR DBGBCR3_EL1, x1 (Breakpoint control register, {})",
+            tda
+        ))
+    );
+    assert_eq!(
+        decode(0x62280440).unwrap()[4].description,
+        Some(
+            "MSR OSLAR_EL1, x2 (OS lock or power control register, accesses trap to EL2 if \
+             MDCR_EL2.TDOSA or MDCR_EL2.TDE is set, or to EL3 if MDCR_EL3.TDOSA is set)"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        decode(0x62200481).unwrap()[4].description,
+        Some(
+            "MRS x4, MDRAR_EL1 (Debug ROM address register, accesses trap to EL2 if \
+             MDCR_EL2.TDRA or MDCR_EL2.TDE is set, or to EL3 if MDCR_EL3.TDA is set)"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        decode(0x6220c00b).unwrap()[4].description,
+        Some(
+            "MRS x0, DBGDTRRX_EL0 (Debug Communications Channel register, accesses from EL0 \
+             trap to EL1 if MDSCR_EL1.TDCC is set, and accesses trap to EL2 if MDCR_EL2.TDA, \
+             MDCR_EL2.TDE or MDCR_EL2.TDCC is set, or to EL3 if MDCR_EL3.TDA or MDCR_EL3.TDCC \
+             is set)"
+                .to_string()
+        )
+    );
+
+    // AArch32 accesses through CP14.
+    assert_eq!(
+        decode(0x17e80440).unwrap()[4].description,
+        Some(
+            "MCR p14, 0, r2, c1, c0, 4 accessing DBGOSLAR (OS lock or power control register, \
+             accesses trap to EL2 if MDCR_EL2.TDOSA or MDCR_EL2.TDE is set, or to EL3 if \
+             MDCR_EL3.TDOSA is set)"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        decode(0x17e00063).unwrap()[4]
+            .description
+            .as_deref()
+            .unwrap()
+            .split(" (")
+            .next(),
+        Some("MRC p14, 0, r3, c0, c1, 0 accessing DBGDSCRint")
+    );
+    // Trace registers aren't identified.
+    assert_eq!(decode(0x17e04001).unwrap()[4].description, None);
+}
+
+#[test]
+fn msr_timer() {
+    // MRS x0, CNTV_CTL_EL0
+    assert_eq!(
+        decode(0x6232f807).unwrap()[4].description,
+        Some(
+            "MRS x0, CNTV_CTL_EL0 (Control register of the EL1 virtual timer, accesses from EL0 \
+             trap to EL1 unless CNTKCTL_EL1.EL0VTEN is set, and accesses trap to EL2 if \
+             CNTHCTL_EL2.EL1TVT is set)"
+                .to_string()
+        )
+    );
+    // MSR CNTP_CVAL_EL0, x5
+    assert_eq!(
+        decode(0x6234f8a4).unwrap()[4].description,
+        Some(
+            "MSR CNTP_CVAL_EL0, x5 (Compare value register of the EL1 physical timer, accesses \
+             from EL0 trap to EL1 unless CNTKCTL_EL1.EL0PTEN is set, and accesses trap to EL2 \
+             unless CNTHCTL_EL2.EL1PCEN (or CNTHCTL_EL2.EL1PTEN if HCR_EL2.E2H is set) is set)"
+                .to_string()
+        )
+    );
+    // MRS x1, CNTVCT_EL0
+    assert_eq!(
+        decode(0x6234f821).unwrap()[4].description,
+        Some(
+            "MRS x1, CNTVCT_EL0 (Virtual counter, accesses from EL0 trap to EL1 unless \
+             CNTKCTL_EL1.EL0VCTEN is set, or to EL2 if CNTHCTL_EL2.EL1TVCT is set)"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn msr_gic() {
+    // MRS x2, ICC_IAR1_EL1
+    assert_eq!(
+        decode(0x62303059).unwrap()[4].description,
+        Some(
+            "MRS x2, ICC_IAR1_EL1 (GIC CPU interface group 1 register, or the virtual interface \
+             register ICV_IAR1_EL1 if accessed from EL1 with HCR_EL2.IMO set, which traps to EL2 \
+             if ICH_HCR_EL2.TALL1 is set)"
+                .to_string()
+        )
+    );
+    // MSR ICC_PMR_EL1, x7
+    assert_eq!(
+        decode(0x623010ec).unwrap()[4].description,
+        Some(
+            "MSR ICC_PMR_EL1, x7 (GIC CPU interface register, or the virtual interface register \
+             ICV_PMR_EL1 if accessed from EL1 with HCR_EL2.IMO or HCR_EL2.FMO set, which traps \
+             to EL2 if ICH_HCR_EL2.TC is set)"
+                .to_string()
+        )
+    );
+    // MSR ICC_SGI1R_EL1, x4
+    assert_eq!(
+        decode(0x623a3096).unwrap()[4].description,
+        Some(
+            "MSR ICC_SGI1R_EL1, x4 (GIC software generated interrupt register, which has no \
+             virtual equivalent, so accesses from EL1 trap to EL2 if HCR_EL2.IMO or HCR_EL2.FMO \
+             is set)"
+                .to_string()
+        )
+    );
+    // MRS x0, ICH_LR10_EL2
+    assert_eq!(
+        decode(0x6235301b).unwrap()[4].description,
+        Some(
+            "MRS x0, ICH_LR10_EL2 (GIC virtual interface control register, accesses from EL1 \
+             trap to EL2 if HCR_EL2.NV is set)"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn wf_trap_controls() {
+    // WFI
+    assert_eq!(
+        decode(0x06000000).unwrap()[4].subfields[6].description,
+        Some(
+            "WFI trapped (from EL0 to EL1 if SCTLR_EL1.nTWI is 0, from EL0 to EL2 if \
+             SCTLR_EL2.nTWI is 0 and HCR_EL2.{E2H,TGE} is {1,1}, from EL0 or EL1 to EL2 if \
+             HCR_EL2.TWI is 1, or from EL0, EL1 or EL2 to EL3 if SCR_EL3.TWI is 1)"
+                .to_string()
+        )
+    );
+    // WFET
+    assert_eq!(
+        decode(0x06000003).unwrap()[4].subfields[6].description,
+        Some(
+            "WFET trapped (from EL0 to EL1 if SCTLR_EL1.nTWE is 0, from EL0 to EL2 if \
+             SCTLR_EL2.nTWE is 0 and HCR_EL2.{E2H,TGE} is {1,1}, from EL0 or EL1 to EL2 if \
+             HCR_EL2.TWE is 1, or from EL0, EL1 or EL2 to EL3 if SCR_EL3.TWE is 1)"
+                .to_string()
+        )
+    );
+    // WFIT x5
+    let iss = &decode(0x060000a6).unwrap()[4];
+    assert_eq!(
+        iss.subfields[3].description,
+        Some("x5, holding the timeout".to_string())
+    );
+    assert!(iss.subfields[6]
+        .description
+        .as_ref()
+        .unwrap()
+        .starts_with("WFIT trapped"));
+    assert_eq!(
+        decode(0x060000a2).unwrap()[4].subfields[3].description,
+        None
+    );
+}
+
+#[test]
+fn mcr_registers() {
+    // MRC p15, 0, APSR_nzcv, c0, c1, 0
+    assert_eq!(
+        decode(0x0fe001e3).unwrap()[4].subfields[5].description,
+        Some("r15 (PC), writes APSR_nzcv rather than a register".to_string())
+    );
+    // MCR p15, 0, r13, c0, c1, 0
+    assert_eq!(
+        decode(0x0fe001a2).unwrap()[4].subfields[5].description,
+        Some("r13 (SP)".to_string())
+    );
+    // MRRC p15, 0, r14, r15, c2
+    let iss = &decode(0x13e03dc5).unwrap()[4];
+    assert_eq!(
+        iss.subfields[4].description,
+        Some("r15 (PC), which is UNPREDICTABLE for MCRR and MRRC".to_string())
+    );
+    assert_eq!(iss.subfields[5].description, Some("r14 (LR)".to_string()));
+}
+
+#[test]
+fn fsc_level() {
+    // Translation fault, level 3.
+    assert_eq!(
+        decode(0x92000047).unwrap()[4].subfields[9].derived,
+        Some(Derived {
+            value: 3,
+            unit: Unit::Level
+        })
+    );
+    // Translation fault, level -1.
+    assert_eq!(
+        decode(0x8200002b).unwrap()[4].subfields[7].derived,
+        Some(Derived {
+            value: -1,
+            unit: Unit::Level
+        })
+    );
+    // Synchronous External abort, not on translation table walk.
+    assert_eq!(decode(0x96000010).unwrap()[4].subfields[9].derived, None);
+}
+
+#[test]
+fn fsc_level_not_subfield() {
+    // Translation fault, level -1, which only the derived value gives.
+    let ifsc = &decode(0x8200002b).unwrap()[4].subfields[7];
+    assert_eq!(ifsc.subfields, vec![]);
+    assert_eq!(ifsc.derived.map(|derived| derived.value), Some(-1));
+}
+
+#[test]
+fn hvc_aarch32() {
+    let decoded = decode(0x4a001234).unwrap();
+    assert_eq!(
+        decoded[2].description,
+        Some("HVC instruction execution in AArch32 state".to_string())
+    );
+    assert_eq!(decoded[4].subfields[1].name, "imm16");
+    assert_eq!(decoded[4].subfields[1].value, 0x1234);
+    assert_eq!(
+        summarize(0x4a001234),
+        "HVC instruction execution in AArch32 state: immediate 0x1234."
+    );
+    assert!(matches!(
+        decode(0x4a011234),
+        Err(DecodeError::InvalidRes0 { res0: 0x1 })
+    ));
+}
+
+#[test]
+fn smc_aarch32() {
+    let decoded = decode(0x4fe80000).unwrap();
+    assert_eq!(
+        decoded[2].description,
+        Some("SMC instruction execution in AArch32 state".to_string())
+    );
+    let iss = &decoded[4].subfields;
+    assert_eq!(iss[0].value, 1);
+    assert_eq!(iss[1].value, 0xe);
+    assert_eq!(iss[2].name, "CCKNOWNPASS");
+    assert_eq!(
+        iss[2].description,
+        Some(
+            "Instruction was conditional, and might have failed its condition code check"
+                .to_string()
+        )
+    );
+    assert!(matches!(
+        decode(0x4e000001),
+        Err(DecodeError::InvalidRes0 { res0: 0x1 })
+    ));
+}
+
+#[test]
+fn brk_immediates() {
+    let comment = |esr| decode(esr).unwrap()[4].subfields[1].description.clone();
+    assert_eq!(
+        comment(0xf2000800),
+        Some("Linux BUG() or WARN()".to_string())
+    );
+    assert_eq!(
+        comment(0xf2000932),
+        Some("Linux KASAN tag check failure, on write of 4 bytes, recoverable".to_string())
+    );
+    assert_eq!(
+        comment(0xf2005503),
+        Some("Linux UBSAN check failure, check type 3".to_string())
+    );
+    assert_eq!(comment(0xf2001234), None);
+    // BKPT immediates aren't interpreted.
+    assert_eq!(comment(0xe2000800), None);
+}
+
+#[test]
+fn bti() {
+    let decoded = decode(0x36000002).unwrap();
+    assert_eq!(
+        decoded[2].description,
+        Some("Branch Target Exception".to_string())
+    );
+    assert_eq!(
+        decoded[4].subfields[1].description,
+        Some(
+            "Indirect call with BLR*, which may only land on BTI c, BTI jc, PACIASP or PACIBSP"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn pauth() {
+    let decoded = decode(0x72000001).unwrap();
+    assert_eq!(
+        decoded[4].description,
+        Some("Authentication with APIBKey failed".to_string())
+    );
+    assert_eq!(
+        summarize(0x72000002),
+        "Exception from a Pointer Authentication instruction authentication failure: \
+         Authentication with APDAKey failed."
+    );
+}
+
+#[test]
+fn serror_aet() {
+    let decoded = decode(0xbe001811).unwrap();
+    let iss = &decoded[4].subfields;
+    assert_eq!(iss[3].name, "AET");
+    assert_eq!(iss[3].description, Some("Corrected (CE)".to_string()));
+    assert_eq!(
+        summarize(0xbe001811),
+        "SError interrupt: Asynchronous SError interrupt, Corrected (CE)."
+    );
+    assert!(matches!(
+        decode(0xbe001011),
+        Err(DecodeError::InvalidAet { aet: 0b100 })
+    ));
+}
+
+#[test]
+fn watchpoint() {
+    let decoded = decode(0xd60e8062).unwrap();
+    let iss = &decoded[4].subfields;
+    let names: Vec<_> = iss.iter().map(|field| field.name).collect();
+    assert_eq!(
+        names,
+        vec![
+            "RES0", "WPT", "WPTV", "WPF", "FnP", "RES0", "VNCR", "RES0", "CM", "RES0", "WnR",
+            "DFSC"
+        ]
+    );
+    assert_eq!(iss[1].value, 3);
+    assert_eq!(iss[1].description, Some("Watchpoint 3 was hit".to_string()));
+    assert_eq!(iss[3].value, 0);
+    assert_eq!(iss[4].value, 1);
+    assert_eq!(iss[10].value, 1);
+    // Without WPTV, WPT isn't described.
+    assert_eq!(
+        decode(0xd60c0062).unwrap()[4].subfields[1].description,
+        None
+    );
+}
+
+#[test]
+fn fp_exceptions() {
+    assert_eq!(
+        summarize(0xb2800015),
+        "Trapped floating-point exception taken from AArch64 state: Invalid Operation, Overflow \
+         and Inexact floating-point exceptions occurred."
+    );
+    assert_eq!(
+        decode(0xb2800002).unwrap()[4].description,
+        Some("Divide by Zero floating-point exception occurred".to_string())
+    );
+    assert_eq!(
+        decode(0xb2000002).unwrap()[4].description,
+        Some("Which floating-point exceptions occurred is not known".to_string())
+    );
+}
+
+#[test]
+fn fp_vecitr() {
+    let decoded = decode(0xa2800701).unwrap();
+    assert_eq!(decoded[4].subfields[3].name, "VECITR");
+    assert_eq!(decoded[4].subfields[3].value, 0b111);
+    assert_eq!(
+        decoded[4].subfields[3].description,
+        Some("RES1 or UNKNOWN, as short vector operations are not supported".to_string())
+    );
+    assert_eq!(
+        decode(0xb2800701).unwrap()[4].subfields[3].description,
+        Some("UNKNOWN for exceptions taken from AArch64 state".to_string())
+    );
+}
+
+#[test]
+fn sme() {
+    assert_eq!(
+        summarize(0x76000002),
+        "Trapped SME, SVE or Advanced SIMD access due to SME functionality: SME instruction \
+         trapped because PSTATE.ZA is 0."
+    );
+    assert!(matches!(
+        decode(0x76000005),
+        Err(DecodeError::InvalidSmtc { smtc: 0b101 })
+    ));
+    assert!(matches!(
+        decode(0x76000008),
+        Err(DecodeError::InvalidRes0 { res0: 0x1 })
+    ));
+}
+
+#[test]
+fn granule_protection_check() {
+    let decoded = decode(0x7a014068).unwrap();
+    let names: Vec<_> = decoded[4]
+        .subfields
+        .iter()
+        .map(|field| field.name)
+        .collect();
+    assert_eq!(
+        names,
+        vec!["RES0", "S2PTW", "InD", "GPCSC", "VNCR", "RES0", "CM", "S1PTW", "WnR", "xFSC"]
+    );
+    assert_eq!(
+        summarize(0x7a014068),
+        "Granule Protection Check exception: GPT walk fault at level 1, on write."
+    );
+    assert!(matches!(
+        decode(0x7a00c000),
+        Err(DecodeError::InvalidGpcsc { gpcsc: 0b000011 })
+    ));
+}
+
+#[test]
+fn mops() {
+    let decoded = decode(0x9f0b0443).unwrap();
+    let iss = &decoded[4].subfields;
+    assert_eq!(
+        decoded[2].description,
+        Some("Memory Copy or Memory Set exception".to_string())
+    );
+    assert_eq!(
+        iss[0].description,
+        Some("Memory Copy instruction (CPY*)".to_string())
+    );
+    assert_eq!(iss[2].value, 0b0001);
+    assert_eq!(iss[4].name, "WrongOption");
+    assert!(iss[4].as_bit());
+    assert_eq!(iss[7].description, Some("x1".to_string()));
+    assert_eq!(iss[8].description, Some("x2".to_string()));
+    assert_eq!(iss[9].description, Some("x3".to_string()));
+}
+
+#[test]
+fn tstart() {
+    let decoded = decode(0x6e0000a0).unwrap();
+    let iss = &decoded[4].subfields;
+    assert_eq!(iss[1].name, "Rd");
+    assert_eq!(iss[1].value, 5);
+    assert_eq!(iss[1].description, Some("x5".to_string()));
+    assert!(decode(0x6e0000a1).is_err());
+}
+
+#[test]
+fn eret() {
+    let decoded = decode(0x6a000003).unwrap();
+    assert_eq!(
+        decoded[4].description,
+        Some("ERETAB instruction trapped".to_string())
+    );
+    assert_eq!(
+        decode(0x6a000000).unwrap()[4].description,
+        Some("ERET instruction trapped".to_string())
+    );
+    assert!(decode(0x6a000004).is_err());
+}
+
+#[test]
+fn vmrs() {
+    let decoded = decode(0x23e1dc61).unwrap();
+    assert_eq!(decoded[4].description, Some("VMRS r3, MVFR0".to_string()));
+    assert_eq!(decoded[4].subfields[4].name, "CRn");
+    assert_eq!(decoded[4].subfields[4].value, 0b0111);
+    assert_eq!(decode(0x23e1ec61).unwrap()[4].description, None);
+}
+
+#[test]
+fn pauth_trap() {
+    let decoded = decode(0x26000000).unwrap();
+    assert_eq!(
+        decoded[2].description,
+        Some(
+            "Trapped use of a Pointer authentication instruction because HCR_EL2.API == 0 || \
+             SCR_EL3.API == 0"
+                .to_string()
+        )
+    );
+    assert!(decode(0x26000001).is_err());
+}
+
+#[test]
+fn mrrc_cp14() {
+    let decoded = decode(0x32000443).unwrap();
+    assert_eq!(
+        decoded[4].description,
+        Some(
+            "MRRC p14, 0, r2, r1, c1 accessing DBGDRAR (Debug ROM address register, accesses \
+             trap to EL2 if MDCR_EL2.TDRA or MDCR_EL2.TDE is set, or to EL3 if MDCR_EL3.TDA is \
+             set)"
+                .to_string()
+        )
+    );
+    assert_eq!(decode(0x32000449).unwrap()[4].description, None);
+}
+
+#[test]
+fn vector_catch() {
+    let decoded = decode(0xea000022).unwrap();
+    assert_eq!(
+        decoded[2].description,
+        Some("Vector Catch exception from AArch32 state".to_string())
+    );
+    assert_eq!(
+        decoded[4].subfields[1].description,
+        Some("Debug exception".to_string())
+    );
+    assert!(decode(0xea000062).is_err());
+}
+
+#[test]
+fn msrr() {
+    let decoded = decode(0x52300841).unwrap();
+    let iss = &decoded[4];
+    assert_eq!(iss.subfields[7].name, "Direction");
+    assert_eq!(
+        iss.subfields[7].description,
+        Some("Read from 128-bit system register (MRRS)".to_string())
+    );
+    assert_eq!(iss.description, Some("MRRS x2, x3, TTBR0_EL1".to_string()));
+    assert_eq!(
+        decode(0x52300860).unwrap()[4].description,
+        Some("MSRR TTBR0_EL1, x3, x4 (odd register pair is UNPREDICTABLE)".to_string())
+    );
+    assert_eq!(
+        decode(0x52102040).unwrap()[4].description,
+        Some("SYSP #0, C8, C0, #0, x2, x3".to_string())
+    );
+}
+
+#[test]
+fn gcs() {
+    let decoded = decode(0xb6000062).unwrap();
+    let iss = &decoded[4].subfields;
+    assert_eq!(iss[1].name, "ExType");
+    assert_eq!(iss[4].name, "Rn");
+    assert_eq!(iss[4].description, Some("x3".to_string()));
+    assert_eq!(iss[5].description, Some("RETAA".to_string()));
+
+    let decoded = decode(0xb6200c40).unwrap();
+    let iss = &decoded[4].subfields;
+    assert_eq!(
+        iss[1].description,
+        Some("Trapped GCSSTR or GCSSTTR instruction".to_string())
+    );
+    assert_eq!(iss[3].name, "Raddr");
+    assert_eq!(iss[3].description, Some("x3".to_string()));
+    assert_eq!(iss[4].description, Some("x2".to_string()));
+
+    assert!(matches!(
+        decode(0xb6000007),
+        Err(DecodeError::InvalidIt { it: 0b00111 })
+    ));
+    assert!(matches!(
+        decode(0xb6300000),
+        Err(DecodeError::InvalidExType { ex_type: 0b0011 })
+    ));
+}
+
+#[test]
+fn pmu() {
+    let decoded = decode(0xf6000001).unwrap();
+    assert_eq!(decoded[2].description, Some("PMU exception".to_string()));
+    assert_eq!(decoded[4].subfields[1].name, "SYNC");
+    assert!(decoded[4].subfields[1].as_bit());
+    assert!(decode(0xf6000002).is_err());
+}
+
+#[test]
+fn impdef() {
+    let decoded = decode(0x7e001234).unwrap();
+    assert_eq!(
+        decoded[2].description,
+        Some("IMPLEMENTATION DEFINED exception to EL3".to_string())
+    );
+    assert_eq!(decoded[4].value, 0x1234);
+    assert!(decoded[4].subfields.is_empty());
+
+    fn decode_vendor(iss: u64) -> Result<(Vec<FieldInfo>, Option<String>), DecodeError> {
+        let code = FieldInfo::get(iss, "Code", Some("Vendor error code"), 0, 16);
+        let description = format!("Vendor error {:#x}", code.value);
+        Ok((vec![code], Some(description)))
+    }
+    let decoded = decode_with_options(
+        0x7e001234,
+        &DecodeOptions {
+            impdef_decoder: Some(decode_vendor),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        decoded[4].description,
+        Some("Vendor error 0x1234".to_string())
+    );
+    assert_eq!(decoded[4].subfields[0].name, "Code");
+}
+
+#[test]
+fn data_abort_iss2() {
+    // NoTagAccess fault from an MTE tag store.
+    let decoded = decode(0x0000_0200_9600_004f).unwrap();
+    assert_eq!(decoded[1].name, "ISS2");
+    assert_eq!(decoded[1].subfields[3].name, "TagAccess");
+    assert_eq!(
+        decoded[1].subfields[3].description,
+        Some(
+            "Permission fault on an access to Allocation Tags in a page without tag access \
+             permission (NoTagAccess)"
+                .to_string()
+        )
+    );
+    assert_eq!(decoded[1].subfields[2].description, None);
+
+    // ST64BV with the status result in x7.
+    let decoded = decode(0x0000_0007_9600_0050).unwrap();
+    assert_eq!(decoded[1].subfields[8].value, 7);
+
+    // Permission fault because the HDBSS is full.
+    let decoded = decode(0x0000_0800_9600_004f).unwrap();
+    assert_eq!(decoded[1].subfields[1].name, "HDBSSF");
+    assert!(decoded[1].subfields[1].as_bit());
+
+    // ISS2 isn't decoded for other exception classes.
+    assert!(decode(0x0000_0200_5600_0000).unwrap()[1]
+        .subfields
+        .is_empty());
+    assert!(decode(0x0100_0000_9600_0050).is_err());
+}
+
+#[test]
+fn tag_check_fault() {
+    let decoded = decode(0x92000011).unwrap();
+    assert_eq!(
+        decoded[4].subfields.last().unwrap().description,
+        Some(
+            "Synchronous Tag Check Fault, the Logical Address Tag of the address didn't match \
+             the Allocation Tag of the memory accessed."
+                .to_string()
+        )
+    );
+    assert_eq!(
+        summarize(0x92000011),
+        "Data Abort from a lower Exception level: Synchronous Tag Check Fault, the Logical \
+         Address Tag of the address didn't match the Allocation Tag of the memory accessed, on \
+         read."
+    );
+}
+
+#[test]
+fn data_abort_access_summary() {
+    // LDRSH w18, [x0]
+    assert_eq!(
+        decode(0x93720007).unwrap()[4].description,
+        Some("32-bit sign-extended halfword load into w18, no acquire/release".to_string())
+    );
+    // LDAR x0, [x1]
+    assert_eq!(
+        decode(0x93c0c007).unwrap()[4].description,
+        Some("64-bit doubleword load into x0, with acquire/release semantics".to_string())
+    );
+    assert_eq!(
+        summarize(0x93c0c007),
+        "Data Abort from a lower Exception level: 64-bit doubleword load into x0, with \
+         acquire/release semantics, Translation fault, level 3, on read."
+    );
+    assert_eq!(decode(0x92000007).unwrap()[4].description, None);
+}
+
+#[test]
+fn mcr_cond() {
+    // MRCNE p15, 0, r3, c0, c1, 0
+    let iss = &decode(0x0f100461).unwrap()[4];
+    assert_eq!(iss.subfields[1].name, "COND");
+    assert_eq!(iss.subfields[1].description, Some("NE".to_string()));
+    // MRRC p15, 0, r14, r15, c2
+    assert_eq!(
+        decode(0x13e03dc5).unwrap()[4].subfields[1].description,
+        Some("AL".to_string())
+    );
+    // COND isn't valid.
+    assert_eq!(
+        decode(0x0e100461).unwrap()[4].subfields[1].description,
+        None
+    );
+}
+
+#[test]
+fn cp15_registers() {
+    assert_eq!(
+        decode(0x0fe00401).unwrap()[4].description,
+        Some(
+            "MRC p15, 0, r0, c1, c0, 0 accessing SCTLR (AArch64 equivalent SCTLR_EL1)".to_string()
+        )
+    );
+    // MCR p15, 0, r2, c7, c5, 6
+    assert_eq!(
+        decode(0x0eec1c4a).unwrap()[4].description,
+        Some("MCR p15, 0, r2, c7, c5, 6 accessing BPIALL".to_string())
+    );
+    // The same encoding in the CP14 namespace is a debug register rather than SCTLR.
+    assert!(decode(0x17e00401).unwrap()[4]
+        .description
+        .as_ref()
+        .unwrap()
+        .starts_with("MRC p14, 0, r0, c1, c0, 0 accessing DBGDRAR ("));
+    assert_eq!(decode(0x0fe03c01).unwrap()[4].description, None);
+}
+
+#[test]
+fn cp15_registers_64() {
+    // MRRC p15, 1, r0, r1, c14
+    let iss = &decode(0x1201041d).unwrap()[4];
+    assert_eq!(
+        iss.description,
+        Some(
+            "MRRC p15, 1, r0, r1, c14 accessing CNTVCT (AArch64 equivalent CNTVCT_EL0)".to_string()
+        )
+    );
+    assert_eq!(iss.subfields[2].name, "Opc1");
+    assert_eq!(iss.subfields[2].value, 1);
+    assert_eq!(iss.subfields[4].description, Some("r1".to_string()));
+    assert_eq!(iss.subfields[5].description, Some("r0".to_string()));
+    // MCRR p15, 0, r2, r3, c2
+    assert_eq!(
+        decode(0x12000c44).unwrap()[4].description,
+        Some("MCRR p15, 0, r2, r3, c2 accessing TTBR0 (AArch64 equivalent TTBR0_EL1)".to_string())
+    );
+}
+
+#[test]
+fn data_abort_lst() {
+    // Unsupported Exclusive or Atomic access from ST64BV0.
+    let iss = &decode(0x96001835).unwrap()[4];
+    let lst = iss
+        .subfields
+        .iter()
+        .find(|field| field.start == 11)
+        .unwrap();
+    assert_eq!(lst.name, "LST");
+    assert_eq!(lst.description, Some("ST64BV0 instruction".to_string()));
+    assert_eq!(
+        summarize(0x96001835),
+        "Data Abort taken without a change in Exception level: IMPLEMENTATION DEFINED fault \
+         (Unsupported Exclusive or Atomic access), commonly caused by an exclusive or atomic access \
+         to Device or Non-cacheable memory, or to memory which the interconnect doesn't support \
+         atomics for, on read."
+    );
+    // Bits 11 and 12 are neither SET nor LST for other fault status codes.
+    let iss = &decode(0x96001807).unwrap()[4];
+    let res0 = iss
+        .subfields
+        .iter()
+        .find(|field| field.start == 11)
+        .unwrap();
+    assert_eq!(res0.name, "RES0");
+}
+
+#[test]
+fn stage2_fault_on_stage1_walk() {
+    // Translation fault at level 2 on the stage 2 translation of a stage 1 walk.
+    let decoded = decode(0x92000086).unwrap();
+    let iss = &decoded[4].subfields;
+    let s1ptw = iss.iter().find(|field| field.name == "S1PTW").unwrap();
+    assert!(s1ptw.as_bit());
+    assert_eq!(
+        iss.last().unwrap().description,
+        Some(
+            "Translation fault, level 2, on stage 2 translation for a stage 1 translation table \
+             walk."
+                .to_string()
+        )
+    );
+    assert_eq!(
+        summarize(0x92000086),
+        "Data Abort from a lower Exception level: Translation fault, level 2, on stage 2 \
+         translation for a stage 1 translation table walk, on read."
+    );
+}
+
+#[test]
+fn data_abort_cache_maintenance() {
+    // DC CIVAC causing a permission fault.
+    let iss = &decode(0x9600014f).unwrap()[4].subfields;
+    let cm = iss.iter().find(|field| field.name == "CM").unwrap();
+    assert_eq!(
+        cm.description,
+        Some(
+            "Abort caused by a cache maintenance or address translation instruction, so WnR is \
+             set regardless of the access"
+                .to_string()
+        )
+    );
+    let wnr = iss.iter().find(|field| field.name == "WnR").unwrap();
+    assert_eq!(
+        wnr.description,
+        Some(
+            "Always set for aborts caused by cache maintenance and address translation \
+             instructions"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn ldc_offset() {
+    // LDC with immediate offset addressing, subtracting 16 words from r3.
+    let iss = &decode(0x1be10065).unwrap()[4].subfields;
+    assert_eq!(iss[2].name, "imm8");
+    assert_eq!(iss[2].description, Some("Offset of -64 bytes".to_string()));
+    assert_eq!(
+        iss[2].derived,
+        Some(Derived {
+            value: -64,
+            unit: Unit::Bytes
+        })
+    );
+    // LDC with immediate unindexed addressing.
+    let iss = &decode(0x1be05061).unwrap()[4].subfields;
+    assert_eq!(
+        iss[2].description,
+        Some("Option 5, passed to the coprocessor".to_string())
+    );
+    assert_eq!(iss[2].derived, None);
+    // LDC with literal offset addressing, adding 2 words to the PC.
+    let iss = &decode(0x1be021fd).unwrap()[4].subfields;
+    assert_eq!(iss[2].description, Some("Offset of 8 bytes".to_string()));
+    // LDC with literal unindexed addressing.
+    let iss = &decode(0x1be031f9).unwrap()[4].subfields;
+    assert_eq!(
+        iss[2].description,
+        Some("Option 3, passed to the coprocessor".to_string())
+    );
+}
+
+#[test]
+fn ldc_addressing_mode() {
+    let iss = &decode(0x1be10065).unwrap()[4].subfields;
+    assert_eq!(iss[6].name, "AM");
+    assert_eq!(
+        iss[6].description,
+        Some("Immediate offset, accessing Rn plus the offset without write-back".to_string())
+    );
+    // Pre-indexed.
+    let iss = &decode(0x1be10067).unwrap()[4].subfields;
+    assert_eq!(
+        iss[6].description,
+        Some(
+            "Immediate pre-indexed, accessing Rn plus the offset and writing it back to Rn"
+                .to_string()
+        )
+    );
+    // Literal offset.
+    let iss = &decode(0x1be021fd).unwrap()[4].subfields;
+    assert_eq!(
+        iss[6].description,
+        Some(
+            "Literal offset, accessing the aligned PC plus the offset without write-back \
+             (reserved for trapped STC)"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn fnv_inconsistent() {
+    // FnV set for a translation fault.
+    let iss = &decode(0x96000407).unwrap()[4].subfields;
+    let fnv = iss.iter().find(|field| field.name == "FnV").unwrap();
+    assert_eq!(
+        fnv.description,
+        Some(
+            "FAR is not valid, but FSC 0x7 is not an external abort so FnV should be 0".to_string()
+        )
+    );
+    // FnV set for a synchronous external abort.
+    let iss = &decode(0x96000410).unwrap()[4].subfields;
+    let fnv = iss.iter().find(|field| field.name == "FnV").unwrap();
+    assert_eq!(
+        fnv.description,
+        Some("FAR is not valid, it holds an unknown value".to_string())
+    );
+}
+
+#[test]
+fn summary_with_options() {
+    let options = DecodeOptions {
+        execution_state: Some(ExecutionState::AArch32),
+        ..Default::default()
+    };
+    assert!(summarize(0x93434040).contains("from w3"));
+    assert!(summarize_with_options(0x93434040, &options).contains("from r3"));
+}
+
+#[test]
+fn far_validity() {
+    let far_valid = |esr| far_valid(&decode(esr).unwrap());
+    // Data Abort with FnV clear and set.
+    assert_eq!(far_valid(0x96000050), Some(true));
+    assert_eq!(far_valid(0x96000410), Some(false));
+    // PC alignment fault and Watchpoint.
+    assert_eq!(far_valid(0x8a000000), Some(true));
+    assert_eq!(far_valid(0xd6000062), Some(true));
+    // SVC.
+    assert_eq!(far_valid(0x56000000), None);
+}
@@ -0,0 +1,31 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::common::describe_register;
+use crate::{DecodeError, FieldInfo};
+
+/// Decodes the ISS value for a trapped TSTART instruction.
+pub fn decode_iss_tstart(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0a = FieldInfo::get(iss, "RES0", Some("Reserved"), 10, 25).check_res0()?;
+    let rd = describe_register(FieldInfo::get(
+        iss,
+        "Rd",
+        Some("Destination register"),
+        5,
+        10,
+    ));
+    let res0b = FieldInfo::get(iss, "RES0", Some("Reserved"), 0, 5).check_res0()?;
+
+    Ok(vec![res0a, rd, res0b])
+}
@@ -12,25 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::common::describe_cv;
+use super::common::{describe_cv, get_cond, register_name};
+use super::ExecutionState;
 use crate::{DecodeError, FieldInfo};
 
 /// Decodes the ISS value for a trapped WF* instruction.
-pub fn decode_iss_wf(iss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+pub fn decode_iss_wf(
+    iss: u64,
+    state: Option<ExecutionState>,
+) -> Result<Vec<FieldInfo>, DecodeError> {
     let cv =
         FieldInfo::get_bit(iss, "CV", Some("Condition code valid"), 24).describe_bit(describe_cv);
-    let cond = FieldInfo::get(
-        iss,
-        "COND",
-        Some("Condition code of the trapped instruction"),
-        20,
-        24,
-    );
+    let cond = get_cond(iss, state);
     let res0a = FieldInfo::get(iss, "RES0", Some("Reserved"), 10, 20).check_res0()?;
     let rn = FieldInfo::get(iss, "RN", Some("Register Number"), 5, 10);
     let res0b = FieldInfo::get(iss, "RES0", Some("Reserved"), 3, 5).check_res0()?;
     let rv = FieldInfo::get_bit(iss, "RV", Some("Register Valid"), 2).describe_bit(describe_rv);
-    let ti = FieldInfo::get(iss, "TI", Some("Trapped Instruction"), 0, 2).describe(describe_ti)?;
+    // WFIT and WFET take the timeout in a 64-bit register, and so are only in AArch64 state.
+    let rn = if rv.as_bit() {
+        let name = register_name(rn.value, ExecutionState::AArch64, true);
+        rn.with_description(format!("{}, holding the timeout", name))
+    } else {
+        rn
+    };
+    let ti = FieldInfo::get(iss, "TI", Some("Trapped Instruction"), 0, 2);
+    let ti_description = describe_ti(ti.value);
+    let ti = ti.with_description(ti_description);
 
     Ok(vec![cv, cond, res0a, rn, res0b, rv, ti])
 }
@@ -43,12 +50,22 @@ fn describe_rv(rv: bool) -> &'static str {
     }
 }
 
-fn describe_ti(ti: u64) -> Result<&'static str, DecodeError> {
-    Ok(match ti {
-        0b00 => "WFI trapped",
-        0b01 => "WFE trapped",
-        0b10 => "WFIT trapped",
-        0b11 => "WFET trapped",
+/// Describes the trapped instruction, and which controls can cause it to be trapped.
+fn describe_ti(ti: u64) -> String {
+    let instruction = match ti {
+        0b00 => "WFI",
+        0b01 => "WFE",
+        0b10 => "WFIT",
+        0b11 => "WFET",
         _ => unreachable!(),
-    })
+    };
+    // WFIT and WFET are trapped by the same controls as WFI and WFE.
+    let control = if ti & 0b01 == 0 { "TWI" } else { "TWE" };
+    format!(
+        "{instruction} trapped (from EL0 to EL1 if SCTLR_EL1.n{control} is 0, from EL0 to EL2 if \
+         SCTLR_EL2.n{control} is 0 and HCR_EL2.{{E2H,TGE}} is {{1,1}}, from EL0 or EL1 to EL2 if \
+         HCR_EL2.{control} is 1, or from EL0, EL1 or EL2 to EL3 if SCR_EL3.{control} is 1)",
+        instruction = instruction,
+        control = control
+    )
 }
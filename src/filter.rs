@@ -0,0 +1,354 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Filter expressions over decoded fields, such as `EC==0x24 && ISS.WnR==1`.
+
+use crate::{parse_number, FieldInfo};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// A boolean expression over the fields of a decoded register value.
+///
+/// An expression consists of comparisons between a field path and a number, combined with `&&`,
+/// `||`, `!` and parentheses. A field path is a sequence of field names separated by `.`, such as
+/// `ISS.DFSC`. Where several fields at the same level have the same name, such as `RES0`, a name
+/// may be qualified with the field's bit range to choose between them, e.g. `ISS.RES0[13:6]`.
+/// Otherwise the first field with the name is used. The comparison operators are `==`, `!=`, `<`,
+/// `<=`, `>` and `>=`, and numbers may be decimal, hexadecimal with a `0x` prefix, or `true` or
+/// `false`. A comparison with a field which doesn't exist in the decoded value is false.
+///
+/// # Example
+///
+/// ```
+/// use aarch64_esr_decoder::{decode, Filter};
+///
+/// let filter: Filter = "EC==0x25 && ISS.WnR==1".parse().unwrap();
+/// assert!(filter.matches(&decode(0x96000045).unwrap()));
+/// assert!(!filter.matches(&decode(0x96000005).unwrap()));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Filter(Expression);
+
+impl Filter {
+    /// Returns whether the given decoded fields match the filter.
+    pub fn matches(&self, fields: &[FieldInfo]) -> bool {
+        self.0.evaluate(fields)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = ParseFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let expression = parser.parse_or()?;
+        match parser.next() {
+            None => Ok(Self(expression)),
+            Some(token) => Err(ParseFilterError::UnexpectedToken(token.to_string())),
+        }
+    }
+}
+
+/// An error parsing a filter expression.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum ParseFilterError {
+    /// The expression contained a character which isn't part of any token.
+    #[error("Unexpected character {0:?} in filter")]
+    UnexpectedCharacter(char),
+    /// A number in the expression couldn't be parsed.
+    #[error("Invalid number {0:?} in filter")]
+    InvalidNumber(String),
+    /// A token appeared where it wasn't expected.
+    #[error("Unexpected {0:?} in filter")]
+    UnexpectedToken(String),
+    /// The expression ended before it was complete.
+    #[error("Unexpected end of filter")]
+    UnexpectedEnd,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Expression {
+    Or(Box<Expression>, Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Compare {
+        path: Vec<String>,
+        operator: Operator,
+        value: u64,
+    },
+}
+
+impl Expression {
+    fn evaluate(&self, fields: &[FieldInfo]) -> bool {
+        match self {
+            Self::Or(left, right) => left.evaluate(fields) || right.evaluate(fields),
+            Self::And(left, right) => left.evaluate(fields) && right.evaluate(fields),
+            Self::Not(inner) => !inner.evaluate(fields),
+            Self::Compare {
+                path,
+                operator,
+                value,
+            } => match find_field(fields, path) {
+                Some(field) => operator.compare(field.value, *value),
+                None => false,
+            },
+        }
+    }
+}
+
+/// Finds the field with the given path, if it exists.
+pub(crate) fn find_field<'a>(fields: &'a [FieldInfo], path: &[String]) -> Option<&'a FieldInfo> {
+    let (first, rest) = path.split_first()?;
+    let field = fields.iter().find(|field| field.has_name(first))?;
+    if rest.is_empty() {
+        Some(field)
+    } else {
+        find_field(&field.subfields, rest)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Operator {
+    fn compare(self, left: u64, right: u64) -> bool {
+        match self {
+            Self::Equal => left == right,
+            Self::NotEqual => left != right,
+            Self::Less => left < right,
+            Self::LessOrEqual => left <= right,
+            Self::Greater => left > right,
+            Self::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    Path(Vec<String>),
+    Number(u64),
+    Operator(Operator),
+    And,
+    Or,
+    Not,
+    OpenParen,
+    CloseParen,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{}", path.join(".")),
+            Self::Number(number) => write!(f, "{:#x}", number),
+            Self::Operator(Operator::Equal) => f.write_str("=="),
+            Self::Operator(Operator::NotEqual) => f.write_str("!="),
+            Self::Operator(Operator::Less) => f.write_str("<"),
+            Self::Operator(Operator::LessOrEqual) => f.write_str("<="),
+            Self::Operator(Operator::Greater) => f.write_str(">"),
+            Self::Operator(Operator::GreaterOrEqual) => f.write_str(">="),
+            Self::And => f.write_str("&&"),
+            Self::Or => f.write_str("||"),
+            Self::Not => f.write_str("!"),
+            Self::OpenParen => f.write_str("("),
+            Self::CloseParen => f.write_str(")"),
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, ParseFilterError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            _ if c.is_whitespace() => continue,
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '&' if chars.next_if(|&(_, c)| c == '&').is_some() => Token::And,
+            '|' if chars.next_if(|&(_, c)| c == '|').is_some() => Token::Or,
+            '=' if chars.next_if(|&(_, c)| c == '=').is_some() => Token::Operator(Operator::Equal),
+            '!' if chars.next_if(|&(_, c)| c == '=').is_some() => {
+                Token::Operator(Operator::NotEqual)
+            }
+            '!' => Token::Not,
+            '<' if chars.next_if(|&(_, c)| c == '=').is_some() => {
+                Token::Operator(Operator::LessOrEqual)
+            }
+            '<' => Token::Operator(Operator::Less),
+            '>' if chars.next_if(|&(_, c)| c == '=').is_some() => {
+                Token::Operator(Operator::GreaterOrEqual)
+            }
+            '>' => Token::Operator(Operator::Greater),
+            _ if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((index, c)) =
+                    chars.next_if(|&(_, c)| c.is_ascii_alphanumeric() || "_.[:]".contains(c))
+                {
+                    end = index + c.len_utf8();
+                }
+                let word = &s[start..end];
+                if c.is_ascii_digit() {
+                    Token::Number(
+                        parse_number(word)
+                            .map_err(|_| ParseFilterError::InvalidNumber(word.to_owned()))?,
+                    )
+                } else if word.eq_ignore_ascii_case("true") {
+                    Token::Number(1)
+                } else if word.eq_ignore_ascii_case("false") {
+                    Token::Number(0)
+                } else {
+                    Token::Path(word.split('.').map(ToOwned::to_owned).collect())
+                }
+            }
+            _ => return Err(ParseFilterError::UnexpectedCharacter(c)),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser for filter expressions.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn next_if(&mut self, expected: &Token) -> bool {
+        if self.tokens.get(self.position) == Some(expected) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expression, ParseFilterError> {
+        let mut expression = self.parse_and()?;
+        while self.next_if(&Token::Or) {
+            expression = Expression::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, ParseFilterError> {
+        let mut expression = self.parse_unary()?;
+        while self.next_if(&Token::And) {
+            expression = Expression::And(Box::new(expression), Box::new(self.parse_unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, ParseFilterError> {
+        match self.next().ok_or(ParseFilterError::UnexpectedEnd)? {
+            Token::Not => Ok(Expression::Not(Box::new(self.parse_unary()?))),
+            Token::OpenParen => {
+                let expression = self.parse_or()?;
+                match self.next() {
+                    Some(Token::CloseParen) => Ok(expression),
+                    Some(token) => Err(ParseFilterError::UnexpectedToken(token.to_string())),
+                    None => Err(ParseFilterError::UnexpectedEnd),
+                }
+            }
+            Token::Path(path) => {
+                let operator = match self.next().ok_or(ParseFilterError::UnexpectedEnd)? {
+                    Token::Operator(operator) => *operator,
+                    token => return Err(ParseFilterError::UnexpectedToken(token.to_string())),
+                };
+                let value = match self.next().ok_or(ParseFilterError::UnexpectedEnd)? {
+                    Token::Number(value) => *value,
+                    token => return Err(ParseFilterError::UnexpectedToken(token.to_string())),
+                };
+                Ok(Expression::Compare {
+                    path: path.clone(),
+                    operator,
+                    value,
+                })
+            }
+            token => Err(ParseFilterError::UnexpectedToken(token.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode;
+
+    fn matches(filter: &str, esr: u64) -> bool {
+        filter
+            .parse::<Filter>()
+            .unwrap()
+            .matches(&decode(esr).unwrap())
+    }
+
+    #[test]
+    fn filter() {
+        assert!(matches("EC==0x25", 0x96000045));
+        assert!(!matches("EC==0x24", 0x96000045));
+        assert!(matches("EC==0x25 && ISS.WnR==1", 0x96000045));
+        assert!(!matches("EC==0x25 && ISS.WnR==true", 0x96000005));
+        assert!(matches("EC==0x24 || iss.dfsc>=5", 0x96000045));
+        assert!(matches("!(EC==0x24) && ISS.DFSC<0x10", 0x96000045));
+        assert!(matches("ISS.DFSC != 7", 0x96000045));
+        // Fields which don't exist never match.
+        assert!(!matches("ISS.imm16==0", 0x96000045));
+        assert!(matches("!(ISS.imm16==0)", 0x96000045));
+        // Fields with the same name can be distinguished by their bit range.
+        assert!(matches("ISS.RES0==0 && ISS.RES0[8]==0", 0x82001e10));
+        assert!(matches("ISS.RES0[24:13]==0 && ISS.res0[6]==0", 0x82001e10));
+        assert!(!matches("ISS.RES0[7]==0", 0x82001e10));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "EC==".parse::<Filter>(),
+            Err(ParseFilterError::UnexpectedEnd)
+        );
+        assert_eq!(
+            "EC=0x24".parse::<Filter>(),
+            Err(ParseFilterError::UnexpectedCharacter('='))
+        );
+        assert_eq!(
+            "EC==0x2g".parse::<Filter>(),
+            Err(ParseFilterError::InvalidNumber("0x2g".to_string()))
+        );
+        assert_eq!(
+            "EC==1 EC".parse::<Filter>(),
+            Err(ParseFilterError::UnexpectedToken("EC".to_string()))
+        );
+        assert_eq!(
+            "(EC==1".parse::<Filter>(),
+            Err(ParseFilterError::UnexpectedEnd)
+        );
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoders for the AArch32 fault status registers.
+
+use super::{DecodeError, FieldInfo, Unit};
+
+/// Decodes the given AArch32 Data Fault Status Register value, in either the short-descriptor or
+/// long-descriptor (LPAE) format according to its LPAE bit, or returns an error if it is not valid.
+pub fn decode_dfsr(dfsr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(dfsr, "RES0", Some("Reserved"), 17, 64).check_res0()?;
+    let fnv = FieldInfo::get_bit(dfsr, "FnV", Some("FAR not Valid"), 16).describe_bit(describe_fnv);
+    let aet = FieldInfo::get(dfsr, "AET", Some("Asynchronous Error Type"), 14, 16);
+    let cm =
+        FieldInfo::get_bit(dfsr, "CM", Some("Cache Maintenance"), 13).describe_bit(describe_cm);
+    let ext = ext(dfsr);
+    let wnr =
+        FieldInfo::get_bit(dfsr, "WnR", Some("Write not Read"), 11).describe_bit(describe_wnr);
+    let mut fields = vec![res0, fnv, aet, cm, ext, wnr];
+    fields.extend(decode_status(dfsr, true)?);
+    Ok(fields)
+}
+
+/// Decodes the given AArch32 Instruction Fault Status Register value, in either the
+/// short-descriptor or long-descriptor (LPAE) format according to its LPAE bit, or returns an
+/// error if it is not valid.
+pub fn decode_ifsr(ifsr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0a = FieldInfo::get(ifsr, "RES0", Some("Reserved"), 17, 64).check_res0()?;
+    let fnv = FieldInfo::get_bit(ifsr, "FnV", Some("FAR not Valid"), 16).describe_bit(describe_fnv);
+    let aet = FieldInfo::get(ifsr, "AET", Some("Asynchronous Error Type"), 14, 16);
+    let res0b = FieldInfo::get_bit(ifsr, "RES0", Some("Reserved"), 13).check_res0()?;
+    let ext = ext(ifsr);
+    let res0c = FieldInfo::get_bit(ifsr, "RES0", Some("Reserved"), 11).check_res0()?;
+    let mut fields = vec![res0a, fnv, aet, res0b, ext, res0c];
+    fields.extend(decode_status(ifsr, false)?);
+    Ok(fields)
+}
+
+fn ext(fsr: u64) -> FieldInfo {
+    FieldInfo::get_bit(fsr, "ExT", Some("External abort type"), 12)
+        .with_description("IMPLEMENTATION DEFINED classification of external aborts".to_string())
+}
+
+/// Decodes bits 0 to 10 of a DFSR or IFSR value, which hold the fault status code and depend on
+/// whether the register uses the long-descriptor format.
+///
+/// The short-descriptor format DFSR also has the domain of the fault in bits 4 to 7.
+fn decode_status(fsr: u64, data: bool) -> Result<Vec<FieldInfo>, DecodeError> {
+    let lpae = FieldInfo::get_bit(fsr, "LPAE", Some("Large Physical Address Extension"), 9)
+        .describe_bit(describe_lpae);
+    if lpae.as_bit() {
+        let res0a = FieldInfo::get_bit(fsr, "RES0", Some("Reserved"), 10).check_res0()?;
+        let res0b = FieldInfo::get(fsr, "RES0", Some("Reserved"), 6, 9).check_res0()?;
+        let status = FieldInfo::get(fsr, "STATUS", Some("Fault status"), 0, 6)
+            .describe(describe_long_status)?;
+        let status = match long_status_level(status.value) {
+            Some(level) => status.with_derived(level, Unit::Level),
+            None => status,
+        };
+        Ok(vec![res0a, lpae, res0b, status])
+    } else {
+        let fs_high = FieldInfo::get_bit(fsr, "FS", Some("Fault status bit 4"), 10);
+        let res0 = FieldInfo::get_bit(fsr, "RES0", Some("Reserved"), 8).check_res0()?;
+        let fs_low = FieldInfo::get(fsr, "FS", Some("Fault status bits 3 to 0"), 0, 4);
+        let fs = fs_high.value << 4 | fs_low.value;
+        let fs_low = fs_low.with_description(describe_short_status(fs)?.to_string());
+        if data {
+            let domain = FieldInfo::get(fsr, "Domain", None, 4, 8);
+            let domain_description = format!("Domain {}", domain.value);
+            let domain = domain.with_description(domain_description);
+            Ok(vec![fs_high, lpae, res0, domain, fs_low])
+        } else {
+            let res0b = FieldInfo::get(fsr, "RES0", Some("Reserved"), 4, 8).check_res0()?;
+            Ok(vec![fs_high, lpae, res0, res0b, fs_low])
+        }
+    }
+}
+
+/// Describes the 5-bit fault status code of a short-descriptor format DFSR or IFSR.
+fn describe_short_status(fs: u64) -> Result<&'static str, DecodeError> {
+    Ok(match fs {
+        0b00001 => "Alignment fault",
+        0b00010 => "Debug exception",
+        0b00011 => "Access flag fault, section",
+        0b00100 => "Fault on instruction cache maintenance",
+        0b00101 => "Translation fault, section",
+        0b00110 => "Access flag fault, page",
+        0b00111 => "Translation fault, page",
+        0b01000 => "Synchronous External abort, not on translation table walk",
+        0b01001 => "Domain fault, section",
+        0b01011 => "Domain fault, page",
+        0b01100 => "Synchronous External abort on translation table walk, level 1",
+        0b01101 => "Permission fault, section",
+        0b01110 => "Synchronous External abort on translation table walk, level 2",
+        0b01111 => "Permission fault, page",
+        0b10000 => "TLB conflict abort",
+        0b10100 => "IMPLEMENTATION DEFINED fault (Lockdown)",
+        0b10101 => "IMPLEMENTATION DEFINED fault (Unsupported Exclusive access)",
+        0b10110 => "SError interrupt",
+        0b11000 => "SError interrupt, from a parity or ECC error on memory access",
+        0b11001 => {
+            "Synchronous parity or ECC error on memory access, not on translation table walk"
+        }
+        0b11100 => "Synchronous parity or ECC error on translation table walk, level 1",
+        0b11110 => "Synchronous parity or ECC error on translation table walk, level 2",
+        _ => return Err(DecodeError::InvalidFsc { fsc: fs }),
+    })
+}
+
+/// Describes the 6-bit fault status code of a long-descriptor format DFSR or IFSR.
+fn describe_long_status(status: u64) -> Result<&'static str, DecodeError> {
+    Ok(match status {
+        0b000000 => "Address size fault, level 0",
+        0b000001 => "Address size fault, level 1",
+        0b000010 => "Address size fault, level 2",
+        0b000011 => "Address size fault, level 3",
+        0b000101 => "Translation fault, level 1",
+        0b000110 => "Translation fault, level 2",
+        0b000111 => "Translation fault, level 3",
+        0b001001 => "Access flag fault, level 1",
+        0b001010 => "Access flag fault, level 2",
+        0b001011 => "Access flag fault, level 3",
+        0b001101 => "Permission fault, level 1",
+        0b001110 => "Permission fault, level 2",
+        0b001111 => "Permission fault, level 3",
+        0b010000 => "Synchronous External abort, not on translation table walk",
+        0b010001 => "SError interrupt",
+        0b010101 => "Synchronous External abort on translation table walk, level 1",
+        0b010110 => "Synchronous External abort on translation table walk, level 2",
+        0b010111 => "Synchronous External abort on translation table walk, level 3",
+        0b011000 => {
+            "Synchronous parity or ECC error on memory access, not on translation table walk"
+        }
+        0b011001 => "SError interrupt, from a parity or ECC error on memory access",
+        0b011101 => "Synchronous parity or ECC error on translation table walk, level 1",
+        0b011110 => "Synchronous parity or ECC error on translation table walk, level 2",
+        0b011111 => "Synchronous parity or ECC error on translation table walk, level 3",
+        0b100001 => "Alignment fault",
+        0b100010 => "Debug exception",
+        0b110000 => "TLB conflict abort",
+        0b110100 => "IMPLEMENTATION DEFINED fault (Lockdown)",
+        0b110101 => "IMPLEMENTATION DEFINED fault (Unsupported Exclusive access)",
+        0b111101 => "Domain fault, section",
+        0b111110 => "Domain fault, page",
+        _ => return Err(DecodeError::InvalidFsc { fsc: status }),
+    })
+}
+
+/// Returns the level of translation table lookup which the given long-descriptor fault status
+/// code was for, if it includes one.
+fn long_status_level(status: u64) -> Option<i64> {
+    match status {
+        0b000000..=0b001111 | 0b010100..=0b010111 | 0b011100..=0b011111 => {
+            Some(status as i64 & 0b11)
+        }
+        _ => None,
+    }
+}
+
+fn describe_fnv(fnv: bool) -> &'static str {
+    if fnv {
+        "FAR is not valid, it holds an unknown value"
+    } else {
+        "FAR is valid"
+    }
+}
+
+fn describe_cm(cm: bool) -> &'static str {
+    if cm {
+        "Abort caused by a cache maintenance instruction"
+    } else {
+        "Abort not caused by a cache maintenance instruction"
+    }
+}
+
+fn describe_wnr(wnr: bool) -> &'static str {
+    if wnr {
+        "Abort caused by a write instruction"
+    } else {
+        "Abort caused by a read instruction"
+    }
+}
+
+fn describe_lpae(lpae: bool) -> &'static str {
+    if lpae {
+        "Long-descriptor translation table format"
+    } else {
+        "Short-descriptor translation table format"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derived;
+
+    #[test]
+    fn dfsr_short() {
+        // Permission fault on a write to a page in domain 3.
+        let decoded = decode_dfsr(0x83f).unwrap();
+        assert_eq!(decoded.len(), 11);
+        assert_eq!(decoded[5].name, "WnR");
+        assert_eq!(decoded[5].value, 1);
+        assert_eq!(decoded[9].name, "Domain");
+        assert_eq!(decoded[9].description, Some("Domain 3".to_string()));
+        assert_eq!(decoded[10].name, "FS");
+        assert_eq!(
+            decoded[10].description,
+            Some("Permission fault, page".to_string())
+        );
+        // FS[4] is set for TLB conflict aborts.
+        assert_eq!(
+            decode_dfsr(0x400).unwrap()[10].description,
+            Some("TLB conflict abort".to_string())
+        );
+        assert!(matches!(
+            decode_dfsr(0x0),
+            Err(DecodeError::InvalidFsc { fsc: 0 })
+        ));
+    }
+
+    #[test]
+    fn dfsr_long() {
+        // Translation fault at level 2, on a read.
+        let decoded = decode_dfsr(0x206).unwrap();
+        assert_eq!(decoded.len(), 10);
+        assert_eq!(decoded[7].name, "LPAE");
+        assert_eq!(
+            decoded[7].description,
+            Some("Long-descriptor translation table format".to_string())
+        );
+        assert_eq!(decoded[9].name, "STATUS");
+        assert_eq!(
+            decoded[9].description,
+            Some("Translation fault, level 2".to_string())
+        );
+        assert_eq!(
+            decoded[9].derived,
+            Some(Derived {
+                value: 2,
+                unit: Unit::Level
+            })
+        );
+    }
+
+    #[test]
+    fn ifsr() {
+        let decoded = decode_ifsr(0x5).unwrap();
+        assert_eq!(decoded.len(), 11);
+        assert_eq!(
+            decoded[10].description,
+            Some("Translation fault, section".to_string())
+        );
+        let decoded = decode_ifsr(0x1210).unwrap();
+        assert_eq!(decoded[4].name, "ExT");
+        assert_eq!(decoded[4].value, 1);
+        assert_eq!(
+            decoded[9].description,
+            Some("Synchronous External abort, not on translation table walk".to_string())
+        );
+        // Bits 4 to 7 are RES0 in the IFSR.
+        assert!(matches!(
+            decode_ifsr(0x35),
+            Err(DecodeError::InvalidRes0 { res0: 3 })
+        ));
+    }
+}
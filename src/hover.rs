@@ -0,0 +1,150 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lookup of the field containing a particular bit, for showing in tooltips.
+
+use crate::{decode, spec_reference, DecodeError, FieldInfo, SpecReference};
+use std::fmt::{self, Display, Formatter};
+
+/// The innermost field containing a particular bit of a register value, with the path of field
+/// names leading to it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Hover {
+    /// The names of the fields containing the bit, from the outermost to the innermost, e.g.
+    /// `["ISS", "DFSC"]`.
+    pub path: Vec<&'static str>,
+    /// The innermost field containing the bit, without its subfields. Its `start` is the absolute
+    /// bit position in the register, even for a subfield.
+    pub field: FieldInfo,
+    /// The document which the field's definition comes from, if known.
+    pub reference: Option<&'static SpecReference>,
+}
+
+impl Hover {
+    /// Finds the innermost field among the given decoded fields which contains the given bit, or
+    /// returns `None` if no field contains it.
+    pub fn find(fields: &[FieldInfo], bit: usize) -> Option<Self> {
+        Self::find_at(fields, bit, 0)
+    }
+
+    /// Decodes the given ESR value and finds the innermost field containing the given bit, along
+    /// with a reference to the revision of the Arm ARM which it is decoded according to.
+    pub fn find_in_esr(esr: u64, bit: usize) -> Result<Option<Self>, DecodeError> {
+        let fields = decode(esr)?;
+        Ok(Self::find(&fields, bit).map(|hover| Self {
+            reference: spec_reference("ESR"),
+            ..hover
+        }))
+    }
+
+    /// Finds the innermost field containing the given bit, where the positions of the given fields
+    /// are relative to the given offset.
+    fn find_at(fields: &[FieldInfo], bit: usize, offset: usize) -> Option<Self> {
+        let field = fields.iter().find(|field| {
+            offset + field.start <= bit && bit < offset + field.start + field.width
+        })?;
+        Some(
+            match Self::find_at(&field.subfields, bit, offset + field.start) {
+                Some(mut inner) => {
+                    inner.path.insert(0, field.name);
+                    inner
+                }
+                None => Self {
+                    path: vec![field.name],
+                    field: FieldInfo {
+                        start: offset + field.start,
+                        subfields: vec![],
+                        ..field.clone()
+                    },
+                    reference: None,
+                },
+            },
+        )
+    }
+}
+
+impl Display for Hover {
+    /// Formats the hover information as a few lines of plain text, suitable for a tooltip.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.path.join("."))?;
+        if let Some(long_name) = self.field.long_name {
+            write!(f, " ({})", long_name)?;
+        }
+        if self.field.width == 1 {
+            writeln!(f, ", bit {}", self.field.start)?;
+        } else {
+            writeln!(
+                f,
+                ", bits {}..{}",
+                self.field.start,
+                self.field.start + self.field.width - 1
+            )?;
+        }
+        write!(f, "{}", self.field)?;
+        if let Some(description) = &self.field.description {
+            write!(f, "\n{}", description)?;
+        }
+        if let Some(reference) = self.reference {
+            write!(f, "\nSee {}, {}", reference.title, reference.revision)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode;
+
+    #[test]
+    fn data_abort() {
+        let decoded = decode(0x96000050).unwrap();
+
+        let hover = Hover::find(&decoded, 4).unwrap();
+        assert_eq!(hover.path, vec!["ISS", "DFSC"]);
+        assert_eq!(hover.field.start, 0);
+        assert_eq!(hover.field.width, 6);
+        assert_eq!(
+            hover.to_string(),
+            "ISS.DFSC (Data Fault Status Code), bits 0..5\n\
+             DFSC: 0x10 0b010000\n\
+             Synchronous External abort, not on translation table walk or hardware update of \
+             translation table."
+        );
+
+        let hover = Hover::find(&decoded, 26).unwrap();
+        assert_eq!(hover.path, vec!["EC"]);
+
+        assert_eq!(Hover::find(&decoded, 64), None);
+    }
+
+    #[test]
+    fn iss2_subfield() {
+        // ISS2.Xs is bits 0..4 of ISS2, which is bits 32..36 of the ESR.
+        let decoded = decode(0x0000_0005_9600_0050).unwrap();
+        let hover = Hover::find(&decoded, 33).unwrap();
+        assert_eq!(hover.path, vec!["ISS2", "Xs"]);
+        assert_eq!(hover.field.start, 32);
+        assert!(hover.to_string().contains(", bits 32..36\n"));
+    }
+
+    #[test]
+    fn reference() {
+        let hover = Hover::find_in_esr(0x96000050, 4).unwrap().unwrap();
+        assert_eq!(hover.reference, spec_reference("ESR"));
+        assert!(hover.to_string().ends_with(
+            "\nSee Arm Architecture Reference Manual for A-profile architecture, DDI 0487 K.a"
+        ));
+    }
+}
@@ -0,0 +1,1207 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoders for the AArch64 ID registers which identify the features an implementation supports.
+
+use super::{DecodeError, FieldInfo};
+
+/// A 4-bit field of an ID register, with the features which each of its values indicates.
+///
+/// Values not listed are reserved, unless the field has a describer which describes them. Fields
+/// with no values listed and no describer aren't described.
+struct IdField {
+    name: &'static str,
+    start: usize,
+    values: &'static [(u64, &'static [&'static str])],
+    /// Describes values of fields which indicate more than which features are implemented, such as
+    /// a size, or returns `None` to describe the value by its features.
+    describe: Option<fn(u64) -> Option<&'static str>>,
+}
+
+/// A RES0 field of an ID register.
+const fn res0(start: usize) -> IdField {
+    IdField {
+        name: "RES0",
+        start,
+        values: &[],
+        describe: None,
+    }
+}
+
+const ID_AA64ISAR0_EL1: &[IdField] = &[
+    IdField {
+        name: "RNDR",
+        start: 60,
+        values: &[(0b0001, &["FEAT_RNG"])],
+        describe: None,
+    },
+    IdField {
+        name: "TLB",
+        start: 56,
+        values: &[
+            (0b0001, &["FEAT_TLBIOS"]),
+            (0b0010, &["FEAT_TLBIOS", "FEAT_TLBIRANGE"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "TS",
+        start: 52,
+        values: &[
+            (0b0001, &["FEAT_FlagM"]),
+            (0b0010, &["FEAT_FlagM", "FEAT_FlagM2"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "FHM",
+        start: 48,
+        values: &[(0b0001, &["FEAT_FHM"])],
+        describe: None,
+    },
+    IdField {
+        name: "DP",
+        start: 44,
+        values: &[(0b0001, &["FEAT_DotProd"])],
+        describe: None,
+    },
+    IdField {
+        name: "SM4",
+        start: 40,
+        values: &[(0b0001, &["FEAT_SM4"])],
+        describe: None,
+    },
+    IdField {
+        name: "SM3",
+        start: 36,
+        values: &[(0b0001, &["FEAT_SM3"])],
+        describe: None,
+    },
+    IdField {
+        name: "SHA3",
+        start: 32,
+        values: &[(0b0001, &["FEAT_SHA3"])],
+        describe: None,
+    },
+    IdField {
+        name: "RDM",
+        start: 28,
+        values: &[(0b0001, &["FEAT_RDM"])],
+        describe: None,
+    },
+    IdField {
+        name: "TME",
+        start: 24,
+        values: &[(0b0001, &["FEAT_TME"])],
+        describe: None,
+    },
+    IdField {
+        name: "Atomic",
+        start: 20,
+        values: &[
+            (0b0010, &["FEAT_LSE"]),
+            (0b0011, &["FEAT_LSE", "FEAT_LSE128"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "CRC32",
+        start: 16,
+        values: &[(0b0001, &["FEAT_CRC32"])],
+        describe: None,
+    },
+    IdField {
+        name: "SHA2",
+        start: 12,
+        values: &[
+            (0b0001, &["FEAT_SHA256"]),
+            (0b0010, &["FEAT_SHA256", "FEAT_SHA512"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "SHA1",
+        start: 8,
+        values: &[(0b0001, &["FEAT_SHA1"])],
+        describe: None,
+    },
+    IdField {
+        name: "AES",
+        start: 4,
+        values: &[
+            (0b0001, &["FEAT_AES"]),
+            (0b0010, &["FEAT_AES", "FEAT_PMULL"]),
+        ],
+        describe: None,
+    },
+    res0(0),
+];
+
+const ID_AA64ISAR1_EL1: &[IdField] = &[
+    IdField {
+        name: "LS64",
+        start: 60,
+        values: &[
+            (0b0001, &["FEAT_LS64"]),
+            (0b0010, &["FEAT_LS64", "FEAT_LS64_V"]),
+            (0b0011, &["FEAT_LS64", "FEAT_LS64_V", "FEAT_LS64_ACCDATA"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "XS",
+        start: 56,
+        values: &[(0b0001, &["FEAT_XS"])],
+        describe: None,
+    },
+    IdField {
+        name: "I8MM",
+        start: 52,
+        values: &[(0b0001, &["FEAT_I8MM"])],
+        describe: None,
+    },
+    IdField {
+        name: "DGH",
+        start: 48,
+        values: &[(0b0001, &["FEAT_DGH"])],
+        describe: None,
+    },
+    IdField {
+        name: "BF16",
+        start: 44,
+        values: &[
+            (0b0001, &["FEAT_BF16"]),
+            (0b0010, &["FEAT_BF16", "FEAT_EBF16"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "SPECRES",
+        start: 40,
+        values: &[
+            (0b0001, &["FEAT_SPECRES"]),
+            (0b0010, &["FEAT_SPECRES", "FEAT_SPECRES2"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "SB",
+        start: 36,
+        values: &[(0b0001, &["FEAT_SB"])],
+        describe: None,
+    },
+    IdField {
+        name: "FRINTTS",
+        start: 32,
+        values: &[(0b0001, &["FEAT_FRINTTS"])],
+        describe: None,
+    },
+    IdField {
+        name: "GPI",
+        start: 28,
+        values: &[(0b0001, &["FEAT_PACIMP"])],
+        describe: None,
+    },
+    IdField {
+        name: "GPA",
+        start: 24,
+        values: &[(0b0001, &["FEAT_PACQARMA5"])],
+        describe: None,
+    },
+    IdField {
+        name: "LRCPC",
+        start: 20,
+        values: &[
+            (0b0001, &["FEAT_LRCPC"]),
+            (0b0010, &["FEAT_LRCPC", "FEAT_LRCPC2"]),
+            (0b0011, &["FEAT_LRCPC", "FEAT_LRCPC2", "FEAT_LRCPC3"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "FCMA",
+        start: 16,
+        values: &[(0b0001, &["FEAT_FCMA"])],
+        describe: None,
+    },
+    IdField {
+        name: "JSCVT",
+        start: 12,
+        values: &[(0b0001, &["FEAT_JSCVT"])],
+        describe: None,
+    },
+    IdField {
+        name: "API",
+        start: 8,
+        values: &[
+            (0b0001, &["FEAT_PAuth", "FEAT_PACIMP"]),
+            (0b0010, &["FEAT_PAuth", "FEAT_PACIMP", "FEAT_EPAC"]),
+            (0b0011, &["FEAT_PAuth", "FEAT_PACIMP", "FEAT_PAuth2"]),
+            (
+                0b0100,
+                &["FEAT_PAuth", "FEAT_PACIMP", "FEAT_PAuth2", "FEAT_FPAC"],
+            ),
+            (
+                0b0101,
+                &[
+                    "FEAT_PAuth",
+                    "FEAT_PACIMP",
+                    "FEAT_PAuth2",
+                    "FEAT_FPAC",
+                    "FEAT_FPACCOMBINE",
+                ],
+            ),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "APA",
+        start: 4,
+        values: &[
+            (0b0001, &["FEAT_PAuth", "FEAT_PACQARMA5"]),
+            (0b0010, &["FEAT_PAuth", "FEAT_PACQARMA5", "FEAT_EPAC"]),
+            (0b0011, &["FEAT_PAuth", "FEAT_PACQARMA5", "FEAT_PAuth2"]),
+            (
+                0b0100,
+                &["FEAT_PAuth", "FEAT_PACQARMA5", "FEAT_PAuth2", "FEAT_FPAC"],
+            ),
+            (
+                0b0101,
+                &[
+                    "FEAT_PAuth",
+                    "FEAT_PACQARMA5",
+                    "FEAT_PAuth2",
+                    "FEAT_FPAC",
+                    "FEAT_FPACCOMBINE",
+                ],
+            ),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "DPB",
+        start: 0,
+        values: &[
+            (0b0001, &["FEAT_DPB"]),
+            (0b0010, &["FEAT_DPB", "FEAT_DPB2"]),
+        ],
+        describe: None,
+    },
+];
+
+const ID_AA64ISAR2_EL1: &[IdField] = &[
+    IdField {
+        name: "ATS1A",
+        start: 60,
+        values: &[(0b0001, &["FEAT_ATS1A"])],
+        describe: None,
+    },
+    IdField {
+        name: "LUT",
+        start: 56,
+        values: &[(0b0001, &["FEAT_LUT"])],
+        describe: None,
+    },
+    IdField {
+        name: "CSSC",
+        start: 52,
+        values: &[(0b0001, &["FEAT_CSSC"])],
+        describe: None,
+    },
+    IdField {
+        name: "RPRFM",
+        start: 48,
+        values: &[(0b0001, &["FEAT_RPRFM"])],
+        describe: None,
+    },
+    IdField {
+        name: "PCDPHINT",
+        start: 44,
+        values: &[(0b0001, &["FEAT_PCDPHINT"])],
+        describe: None,
+    },
+    IdField {
+        name: "PRFMSLC",
+        start: 40,
+        values: &[(0b0001, &["FEAT_PRFMSLC"])],
+        describe: None,
+    },
+    IdField {
+        name: "SYSINSTR_128",
+        start: 36,
+        values: &[(0b0001, &["FEAT_SYSINSTR128"])],
+        describe: None,
+    },
+    IdField {
+        name: "SYSREG_128",
+        start: 32,
+        values: &[(0b0001, &["FEAT_SYSREG128"])],
+        describe: None,
+    },
+    IdField {
+        name: "CLRBHB",
+        start: 28,
+        values: &[(0b0001, &["FEAT_CLRBHB"])],
+        describe: None,
+    },
+    IdField {
+        name: "PAC_frac",
+        start: 24,
+        values: &[(0b0001, &["FEAT_CONSTPACFIELD"])],
+        describe: None,
+    },
+    IdField {
+        name: "BC",
+        start: 20,
+        values: &[(0b0001, &["FEAT_HBC"])],
+        describe: None,
+    },
+    IdField {
+        name: "MOPS",
+        start: 16,
+        values: &[(0b0001, &["FEAT_MOPS"])],
+        describe: None,
+    },
+    IdField {
+        name: "APA3",
+        start: 12,
+        values: &[
+            (0b0001, &["FEAT_PAuth", "FEAT_PACQARMA3"]),
+            (0b0010, &["FEAT_PAuth", "FEAT_PACQARMA3", "FEAT_EPAC"]),
+            (0b0011, &["FEAT_PAuth", "FEAT_PACQARMA3", "FEAT_PAuth2"]),
+            (
+                0b0100,
+                &["FEAT_PAuth", "FEAT_PACQARMA3", "FEAT_PAuth2", "FEAT_FPAC"],
+            ),
+            (
+                0b0101,
+                &[
+                    "FEAT_PAuth",
+                    "FEAT_PACQARMA3",
+                    "FEAT_PAuth2",
+                    "FEAT_FPAC",
+                    "FEAT_FPACCOMBINE",
+                ],
+            ),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "GPA3",
+        start: 8,
+        values: &[(0b0001, &["FEAT_PACQARMA3"])],
+        describe: None,
+    },
+    IdField {
+        name: "RPRES",
+        start: 4,
+        values: &[(0b0001, &["FEAT_RPRES"])],
+        describe: None,
+    },
+    IdField {
+        name: "WFxT",
+        start: 0,
+        values: &[(0b0010, &["FEAT_WFxT"])],
+        describe: None,
+    },
+];
+
+const ID_AA64MMFR0_EL1: &[IdField] = &[
+    IdField {
+        name: "ECV",
+        start: 60,
+        values: &[
+            (0b0001, &["FEAT_ECV"]),
+            (0b0010, &["FEAT_ECV", "FEAT_ECV_POFF"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "FGT",
+        start: 56,
+        values: &[
+            (0b0001, &["FEAT_FGT"]),
+            (0b0010, &["FEAT_FGT", "FEAT_FGT2"]),
+        ],
+        describe: None,
+    },
+    res0(52),
+    res0(48),
+    IdField {
+        name: "ExS",
+        start: 44,
+        values: &[(0b0001, &["FEAT_ExS"])],
+        describe: None,
+    },
+    IdField {
+        name: "TGran4_2",
+        start: 40,
+        values: &[(0b0011, &["FEAT_LPA2"])],
+        describe: Some(describe_tgran_2),
+    },
+    IdField {
+        name: "TGran64_2",
+        start: 36,
+        values: &[],
+        describe: Some(describe_tgran_2),
+    },
+    IdField {
+        name: "TGran16_2",
+        start: 32,
+        values: &[(0b0011, &["FEAT_LPA2"])],
+        describe: Some(describe_tgran_2),
+    },
+    IdField {
+        name: "TGran4",
+        start: 28,
+        values: &[(0b0001, &["FEAT_LPA2"])],
+        describe: Some(describe_tgran4),
+    },
+    IdField {
+        name: "TGran64",
+        start: 24,
+        values: &[],
+        describe: Some(describe_tgran64),
+    },
+    IdField {
+        name: "TGran16",
+        start: 20,
+        values: &[
+            (0b0001, &["FEAT_TGran16K"]),
+            (0b0010, &["FEAT_TGran16K", "FEAT_LPA2"]),
+        ],
+        describe: Some(describe_tgran16),
+    },
+    IdField {
+        name: "BigEndEL0",
+        start: 16,
+        values: &[],
+        describe: Some(describe_bigendel0),
+    },
+    IdField {
+        name: "SNSMem",
+        start: 12,
+        values: &[],
+        describe: Some(describe_snsmem),
+    },
+    IdField {
+        name: "BigEnd",
+        start: 8,
+        values: &[(0b0001, &["FEAT_MixedEnd"])],
+        describe: Some(describe_bigend),
+    },
+    IdField {
+        name: "ASIDBits",
+        start: 4,
+        values: &[],
+        describe: Some(describe_asidbits),
+    },
+    IdField {
+        name: "PARange",
+        start: 0,
+        values: &[
+            (0b0110, &["FEAT_LPA"]),
+            (0b0111, &["FEAT_LPA", "FEAT_D128"]),
+        ],
+        describe: Some(describe_parange),
+    },
+];
+
+const ID_AA64MMFR1_EL1: &[IdField] = &[
+    IdField {
+        name: "ECBHB",
+        start: 60,
+        values: &[(0b0001, &["FEAT_ECBHB"])],
+        describe: None,
+    },
+    IdField {
+        name: "CMOW",
+        start: 56,
+        values: &[(0b0001, &["FEAT_CMOW"])],
+        describe: None,
+    },
+    IdField {
+        name: "TIDCP1",
+        start: 52,
+        values: &[(0b0001, &["FEAT_TIDCP1"])],
+        describe: None,
+    },
+    IdField {
+        name: "nTLBPA",
+        start: 48,
+        values: &[(0b0001, &["FEAT_nTLBPA"])],
+        describe: None,
+    },
+    IdField {
+        name: "AFP",
+        start: 44,
+        values: &[(0b0001, &["FEAT_AFP"])],
+        describe: None,
+    },
+    IdField {
+        name: "HCX",
+        start: 40,
+        values: &[(0b0001, &["FEAT_HCX"])],
+        describe: None,
+    },
+    IdField {
+        name: "ETS",
+        start: 36,
+        values: &[
+            (0b0001, &["FEAT_ETS"]),
+            (0b0010, &["FEAT_ETS2"]),
+            (0b0011, &["FEAT_ETS2", "FEAT_ETS3"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "TWED",
+        start: 32,
+        values: &[(0b0001, &["FEAT_TWED"])],
+        describe: None,
+    },
+    IdField {
+        name: "XNX",
+        start: 28,
+        values: &[(0b0001, &["FEAT_XNX"])],
+        describe: None,
+    },
+    IdField {
+        name: "SpecSEI",
+        start: 24,
+        values: &[],
+        describe: Some(describe_specsei),
+    },
+    IdField {
+        name: "PAN",
+        start: 20,
+        values: &[
+            (0b0001, &["FEAT_PAN"]),
+            (0b0010, &["FEAT_PAN", "FEAT_PAN2"]),
+            (0b0011, &["FEAT_PAN", "FEAT_PAN2", "FEAT_PAN3"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "LO",
+        start: 16,
+        values: &[(0b0001, &["FEAT_LOR"])],
+        describe: None,
+    },
+    IdField {
+        name: "HPDS",
+        start: 12,
+        values: &[
+            (0b0001, &["FEAT_HPDS"]),
+            (0b0010, &["FEAT_HPDS", "FEAT_HPDS2"]),
+        ],
+        describe: None,
+    },
+    IdField {
+        name: "VH",
+        start: 8,
+        values: &[(0b0001, &["FEAT_VHE"])],
+        describe: None,
+    },
+    IdField {
+        name: "VMIDBits",
+        start: 4,
+        values: &[(0b0010, &["FEAT_VMID16"])],
+        describe: Some(describe_vmidbits),
+    },
+    IdField {
+        name: "HAFDBS",
+        start: 0,
+        values: &[
+            (0b0001, &["FEAT_HAFDBS"]),
+            (0b0010, &["FEAT_HAFDBS"]),
+            (0b0011, &["FEAT_HAFDBS", "FEAT_HAFT"]),
+        ],
+        describe: Some(describe_hafdbs),
+    },
+];
+
+const ID_AA64MMFR2_EL1: &[IdField] = &[
+    IdField {
+        name: "E0PD",
+        start: 60,
+        values: &[(0b0001, &["FEAT_E0PD"])],
+        describe: None,
+    },
+    IdField {
+        name: "EVT",
+        start: 56,
+        values: &[(0b0001, &["FEAT_EVT"]), (0b0010, &["FEAT_EVT"])],
+        describe: Some(describe_evt),
+    },
+    IdField {
+        name: "BBM",
+        start: 52,
+        values: &[(0b0001, &["FEAT_BBM"]), (0b0010, &["FEAT_BBM"])],
+        describe: Some(describe_bbm),
+    },
+    IdField {
+        name: "TTL",
+        start: 48,
+        values: &[(0b0001, &["FEAT_TTL"])],
+        describe: None,
+    },
+    res0(44),
+    IdField {
+        name: "FWB",
+        start: 40,
+        values: &[(0b0001, &["FEAT_S2FWB"])],
+        describe: None,
+    },
+    IdField {
+        name: "IDS",
+        start: 36,
+        values: &[(0b0001, &["FEAT_IDST"])],
+        describe: None,
+    },
+    IdField {
+        name: "AT",
+        start: 32,
+        values: &[(0b0001, &["FEAT_LSE2"])],
+        describe: None,
+    },
+    IdField {
+        name: "ST",
+        start: 28,
+        values: &[(0b0001, &["FEAT_TTST"])],
+        describe: None,
+    },
+    IdField {
+        name: "NV",
+        start: 24,
+        values: &[(0b0001, &["FEAT_NV"]), (0b0010, &["FEAT_NV", "FEAT_NV2"])],
+        describe: None,
+    },
+    IdField {
+        name: "CCIDX",
+        start: 20,
+        values: &[(0b0001, &["FEAT_CCIDX"])],
+        describe: None,
+    },
+    IdField {
+        name: "VARange",
+        start: 16,
+        values: &[
+            (0b0001, &["FEAT_LVA"]),
+            (0b0010, &["FEAT_LVA", "FEAT_LVA3"]),
+        ],
+        describe: Some(describe_varange),
+    },
+    IdField {
+        name: "IESB",
+        start: 12,
+        values: &[(0b0001, &["FEAT_IESB"])],
+        describe: None,
+    },
+    IdField {
+        name: "LSM",
+        start: 8,
+        values: &[(0b0001, &["FEAT_LSMAOC"])],
+        describe: None,
+    },
+    IdField {
+        name: "UAO",
+        start: 4,
+        values: &[(0b0001, &["FEAT_UAO"])],
+        describe: None,
+    },
+    IdField {
+        name: "CnP",
+        start: 0,
+        values: &[(0b0001, &["FEAT_TTCNP"])],
+        describe: None,
+    },
+];
+
+const ID_AA64MMFR3_EL1: &[IdField] = &[
+    IdField {
+        name: "Spec_FPACC",
+        start: 60,
+        values: &[],
+        describe: None,
+    },
+    IdField {
+        name: "ADERR",
+        start: 56,
+        values: &[],
+        describe: None,
+    },
+    IdField {
+        name: "SDERR",
+        start: 52,
+        values: &[],
+        describe: None,
+    },
+    res0(48),
+    IdField {
+        name: "ANERR",
+        start: 44,
+        values: &[],
+        describe: None,
+    },
+    IdField {
+        name: "SNERR",
+        start: 40,
+        values: &[],
+        describe: None,
+    },
+    IdField {
+        name: "D128_2",
+        start: 36,
+        values: &[(0b0001, &["FEAT_D128"])],
+        describe: None,
+    },
+    IdField {
+        name: "D128",
+        start: 32,
+        values: &[(0b0001, &["FEAT_D128"])],
+        describe: None,
+    },
+    IdField {
+        name: "MEC",
+        start: 28,
+        values: &[(0b0001, &["FEAT_MEC"])],
+        describe: None,
+    },
+    IdField {
+        name: "AIE",
+        start: 24,
+        values: &[(0b0001, &["FEAT_AIE"])],
+        describe: None,
+    },
+    IdField {
+        name: "S2POE",
+        start: 20,
+        values: &[(0b0001, &["FEAT_S2POE"])],
+        describe: None,
+    },
+    IdField {
+        name: "S1POE",
+        start: 16,
+        values: &[(0b0001, &["FEAT_S1POE"])],
+        describe: None,
+    },
+    IdField {
+        name: "S2PIE",
+        start: 12,
+        values: &[(0b0001, &["FEAT_S2PIE"])],
+        describe: None,
+    },
+    IdField {
+        name: "S1PIE",
+        start: 8,
+        values: &[(0b0001, &["FEAT_S1PIE"])],
+        describe: None,
+    },
+    IdField {
+        name: "SCTLRX",
+        start: 4,
+        values: &[(0b0001, &["FEAT_SCTLR2"])],
+        describe: None,
+    },
+    IdField {
+        name: "TCRX",
+        start: 0,
+        values: &[(0b0001, &["FEAT_TCR2"])],
+        describe: None,
+    },
+];
+
+/// Decodes the given AArch64 Instruction Set Attribute Register 0 value, describing which features
+/// each field indicates, or returns an error if it is not valid.
+pub fn decode_id_aa64isar0(id_aa64isar0: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    decode_id_register(id_aa64isar0, ID_AA64ISAR0_EL1)
+}
+
+/// Decodes the given AArch64 Instruction Set Attribute Register 1 value, describing which features
+/// each field indicates.
+pub fn decode_id_aa64isar1(id_aa64isar1: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    decode_id_register(id_aa64isar1, ID_AA64ISAR1_EL1)
+}
+
+/// Decodes the given AArch64 Instruction Set Attribute Register 2 value, describing which features
+/// each field indicates.
+pub fn decode_id_aa64isar2(id_aa64isar2: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    decode_id_register(id_aa64isar2, ID_AA64ISAR2_EL1)
+}
+
+/// Decodes the given AArch64 Memory Model Feature Register 0 value, describing which features
+/// each field indicates, or returns an error if it is not valid.
+pub fn decode_id_aa64mmfr0(id_aa64mmfr0: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    decode_id_register(id_aa64mmfr0, ID_AA64MMFR0_EL1)
+}
+
+/// Decodes the given AArch64 Memory Model Feature Register 1 value, describing which features
+/// each field indicates, or returns an error if it is not valid.
+pub fn decode_id_aa64mmfr1(id_aa64mmfr1: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    decode_id_register(id_aa64mmfr1, ID_AA64MMFR1_EL1)
+}
+
+/// Decodes the given AArch64 Memory Model Feature Register 2 value, describing which features
+/// each field indicates, or returns an error if it is not valid.
+pub fn decode_id_aa64mmfr2(id_aa64mmfr2: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    decode_id_register(id_aa64mmfr2, ID_AA64MMFR2_EL1)
+}
+
+/// Decodes the given AArch64 Memory Model Feature Register 3 value, describing which features
+/// each field indicates, or returns an error if it is not valid.
+pub fn decode_id_aa64mmfr3(id_aa64mmfr3: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    decode_id_register(id_aa64mmfr3, ID_AA64MMFR3_EL1)
+}
+
+/// The ID registers which can be decoded, with their fields.
+const ID_REGISTERS: &[(&str, &[IdField])] = &[
+    ("ID_AA64ISAR0_EL1", ID_AA64ISAR0_EL1),
+    ("ID_AA64ISAR1_EL1", ID_AA64ISAR1_EL1),
+    ("ID_AA64ISAR2_EL1", ID_AA64ISAR2_EL1),
+    ("ID_AA64MMFR0_EL1", ID_AA64MMFR0_EL1),
+    ("ID_AA64MMFR1_EL1", ID_AA64MMFR1_EL1),
+    ("ID_AA64MMFR2_EL1", ID_AA64MMFR2_EL1),
+    ("ID_AA64MMFR3_EL1", ID_AA64MMFR3_EL1),
+];
+
+/// Returns the `FEAT_*` features which the given value of the ID register with the given name
+/// indicates, e.g. `["FEAT_AES", "FEAT_PMULL"]`, or `None` if it isn't a known ID register.
+///
+/// Reserved field values are ignored.
+pub fn id_register_features(register: &str, value: u64) -> Option<Vec<&'static str>> {
+    let (_, fields) = ID_REGISTERS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(register))?;
+    let mut implemented = vec![];
+    for field in fields.iter() {
+        for feature in features(field, value >> field.start & 0b1111).unwrap_or_default() {
+            if !implemented.contains(feature) {
+                implemented.push(*feature);
+            }
+        }
+    }
+    Some(implemented)
+}
+
+/// Decodes the given ID register value according to the given table of fields.
+fn decode_id_register(value: u64, fields: &[IdField]) -> Result<Vec<FieldInfo>, DecodeError> {
+    fields
+        .iter()
+        .map(|field| {
+            let info = FieldInfo::get(value, field.name, None, field.start, field.start + 4);
+            if field.name == "RES0" {
+                return info.check_res0();
+            }
+            let description = match field.describe.and_then(|describe| describe(info.value)) {
+                Some(description) => description.to_string(),
+                None if field.values.is_empty() => return Ok(info),
+                None if info.value == 0 => "Not implemented".to_string(),
+                None => match features(field, info.value) {
+                    Some(features) => features.join(", "),
+                    None => "Reserved".to_string(),
+                },
+            };
+            Ok(info.with_description(description))
+        })
+        .collect()
+}
+
+/// Returns the features which the given value of the given field indicates, if it is not reserved.
+fn features(field: &IdField, value: u64) -> Option<&'static [&'static str]> {
+    field
+        .values
+        .iter()
+        .find(|(field_value, _)| *field_value == value)
+        .map(|(_, features)| *features)
+}
+
+fn describe_tgran_2(tgran: u64) -> Option<&'static str> {
+    Some(match tgran {
+        0b0000 => "Support at stage 2 is identified by the stage 1 field",
+        0b0001 => "Not supported at stage 2",
+        0b0010 => "Supported at stage 2",
+        0b0011 => "Supported at stage 2 with 52-bit input and output addresses",
+        _ => return None,
+    })
+}
+
+fn describe_tgran4(tgran4: u64) -> Option<&'static str> {
+    Some(match tgran4 {
+        0b0000 => "4KB granule supported",
+        0b0001 => "4KB granule supported with 52-bit input and output addresses",
+        0b1111 => "4KB granule not supported",
+        _ => return None,
+    })
+}
+
+fn describe_tgran64(tgran64: u64) -> Option<&'static str> {
+    Some(match tgran64 {
+        0b0000 => "64KB granule supported",
+        0b1111 => "64KB granule not supported",
+        _ => return None,
+    })
+}
+
+fn describe_tgran16(tgran16: u64) -> Option<&'static str> {
+    Some(match tgran16 {
+        0b0000 => "16KB granule not supported",
+        0b0001 => "16KB granule supported",
+        0b0010 => "16KB granule supported with 52-bit input and output addresses",
+        _ => return None,
+    })
+}
+
+fn describe_bigendel0(bigendel0: u64) -> Option<&'static str> {
+    Some(match bigendel0 {
+        0b0000 => "Mixed-endian support at EL0 not implemented",
+        0b0001 => "Mixed-endian support at EL0",
+        _ => return None,
+    })
+}
+
+fn describe_snsmem(snsmem: u64) -> Option<&'static str> {
+    Some(match snsmem {
+        0b0000 => "No distinction between Secure and Non-secure memory",
+        0b0001 => "Distinction between Secure and Non-secure memory",
+        _ => return None,
+    })
+}
+
+fn describe_bigend(bigend: u64) -> Option<&'static str> {
+    Some(match bigend {
+        0b0000 => "Mixed-endian support not implemented",
+        0b0001 => "FEAT_MixedEnd, mixed-endian support",
+        _ => return None,
+    })
+}
+
+fn describe_asidbits(asidbits: u64) -> Option<&'static str> {
+    Some(match asidbits {
+        0b0000 => "8-bit ASIDs",
+        0b0010 => "16-bit ASIDs",
+        _ => return None,
+    })
+}
+
+fn describe_parange(parange: u64) -> Option<&'static str> {
+    Some(match parange {
+        0b0000 => "32-bit physical addresses, 4GB",
+        0b0001 => "36-bit physical addresses, 64GB",
+        0b0010 => "40-bit physical addresses, 1TB",
+        0b0011 => "42-bit physical addresses, 4TB",
+        0b0100 => "44-bit physical addresses, 16TB",
+        0b0101 => "48-bit physical addresses, 256TB",
+        0b0110 => "FEAT_LPA, 52-bit physical addresses, 4PB",
+        0b0111 => "FEAT_D128, 56-bit physical addresses, 64PB",
+        _ => return None,
+    })
+}
+
+fn describe_specsei(specsei: u64) -> Option<&'static str> {
+    Some(match specsei {
+        0b0000 => "SError interrupts are not generated on speculative reads",
+        0b0001 => "SError interrupts may be generated on speculative reads",
+        _ => return None,
+    })
+}
+
+fn describe_vmidbits(vmidbits: u64) -> Option<&'static str> {
+    Some(match vmidbits {
+        0b0000 => "8-bit VMIDs",
+        0b0010 => "FEAT_VMID16, 16-bit VMIDs",
+        _ => return None,
+    })
+}
+
+fn describe_hafdbs(hafdbs: u64) -> Option<&'static str> {
+    Some(match hafdbs {
+        0b0000 => "Hardware updates of the Access flag and dirty state not implemented",
+        0b0001 => "FEAT_HAFDBS, hardware updates of the Access flag",
+        0b0010 => "FEAT_HAFDBS, hardware updates of the Access flag and dirty state",
+        0b0011 => {
+            "FEAT_HAFDBS and FEAT_HAFT, hardware updates of the Access flag and dirty state, and \
+             of the Access flag in table descriptors"
+        }
+        _ => return None,
+    })
+}
+
+fn describe_evt(evt: u64) -> Option<&'static str> {
+    Some(match evt {
+        0b0000 => "Enhanced Virtualization Traps not implemented",
+        0b0001 => "FEAT_EVT, without the HCR_EL2.TTLBOS and TTLBIS traps",
+        0b0010 => "FEAT_EVT",
+        _ => return None,
+    })
+}
+
+fn describe_bbm(bbm: u64) -> Option<&'static str> {
+    Some(match bbm {
+        0b0000 => "Level 0 support for changing block size",
+        0b0001 => "FEAT_BBM, level 1 support for changing block size",
+        0b0010 => "FEAT_BBM, level 2 support for changing block size",
+        _ => return None,
+    })
+}
+
+fn describe_varange(varange: u64) -> Option<&'static str> {
+    Some(match varange {
+        0b0000 => "48-bit virtual addresses",
+        0b0001 => "FEAT_LVA, 52-bit virtual addresses with the 64KB granule",
+        0b0010 => "FEAT_LVA3, 56-bit virtual addresses",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isar0() {
+        // Neoverse N1.
+        let decoded = decode_id_aa64isar0(0x0000_1000_1021_1120).unwrap();
+        assert_eq!(decoded.len(), 16);
+        assert_eq!(decoded[0].name, "RNDR");
+        assert_eq!(decoded[0].description, Some("Not implemented".to_string()));
+        assert_eq!(decoded[4].name, "DP");
+        assert_eq!(decoded[4].description, Some("FEAT_DotProd".to_string()));
+        assert_eq!(decoded[10].name, "Atomic");
+        assert_eq!(decoded[10].description, Some("FEAT_LSE".to_string()));
+        assert_eq!(decoded[14].name, "AES");
+        assert_eq!(
+            decoded[14].description,
+            Some("FEAT_AES, FEAT_PMULL".to_string())
+        );
+        // Atomic value 0b0001 is reserved.
+        assert_eq!(
+            decode_id_aa64isar0(0x0010_0000).unwrap()[10].description,
+            Some("Reserved".to_string())
+        );
+        assert!(matches!(
+            decode_id_aa64isar0(0x1),
+            Err(DecodeError::InvalidRes0 { res0: 1 })
+        ));
+    }
+
+    #[test]
+    fn isar1() {
+        // Neoverse N1.
+        let decoded = decode_id_aa64isar1(0x0010_0001).unwrap();
+        assert_eq!(decoded.len(), 16);
+        assert_eq!(decoded[10].name, "LRCPC");
+        assert_eq!(decoded[10].description, Some("FEAT_LRCPC".to_string()));
+        assert_eq!(decoded[15].name, "DPB");
+        assert_eq!(decoded[15].description, Some("FEAT_DPB".to_string()));
+        assert_eq!(decoded[14].name, "APA");
+        assert_eq!(decoded[14].description, Some("Not implemented".to_string()));
+        assert_eq!(
+            decode_id_aa64isar1(0x50).unwrap()[14].description,
+            Some(
+                "FEAT_PAuth, FEAT_PACQARMA5, FEAT_PAuth2, FEAT_FPAC, FEAT_FPACCOMBINE".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn isar2() {
+        let decoded = decode_id_aa64isar2(0x0001_0002).unwrap();
+        assert_eq!(decoded.len(), 16);
+        assert_eq!(decoded[11].name, "MOPS");
+        assert_eq!(decoded[11].description, Some("FEAT_MOPS".to_string()));
+        assert_eq!(decoded[15].name, "WFxT");
+        assert_eq!(decoded[15].description, Some("FEAT_WFxT".to_string()));
+        // WFxT value 0b0001 is reserved.
+        assert_eq!(
+            decode_id_aa64isar2(0x1).unwrap()[15].description,
+            Some("Reserved".to_string())
+        );
+    }
+
+    #[test]
+    fn mmfr0() {
+        // Neoverse N1.
+        let decoded = decode_id_aa64mmfr0(0x0000_0000_0010_1125).unwrap();
+        assert_eq!(decoded.len(), 16);
+        let field = |name| decoded.iter().find(|field| field.name == name).unwrap();
+        assert_eq!(
+            field("PARange").description,
+            Some("48-bit physical addresses, 256TB".to_string())
+        );
+        assert_eq!(
+            field("ASIDBits").description,
+            Some("16-bit ASIDs".to_string())
+        );
+        assert_eq!(
+            field("TGran4").description,
+            Some("4KB granule supported".to_string())
+        );
+        assert_eq!(
+            field("TGran16").description,
+            Some("16KB granule supported".to_string())
+        );
+        assert_eq!(
+            field("ECV").description,
+            Some("Not implemented".to_string())
+        );
+    }
+
+    #[test]
+    fn mmfr1_to_3() {
+        let decoded = decode_id_aa64mmfr1(0x0000_0000_1021_1122).unwrap();
+        assert_eq!(decoded[13].name, "VH");
+        assert_eq!(decoded[13].description, Some("FEAT_VHE".to_string()));
+        assert_eq!(decoded[10].name, "PAN");
+        assert_eq!(
+            decoded[10].description,
+            Some("FEAT_PAN, FEAT_PAN2".to_string())
+        );
+        let decoded = decode_id_aa64mmfr2(0x0000_0000_0000_1011).unwrap();
+        assert_eq!(decoded[15].name, "CnP");
+        assert_eq!(decoded[15].description, Some("FEAT_TTCNP".to_string()));
+        assert!(matches!(
+            decode_id_aa64mmfr2(0x1000_0000_0000),
+            Err(DecodeError::InvalidRes0 { res0: 1 })
+        ));
+        let decoded = decode_id_aa64mmfr3(0x1000_0000_0000_0011).unwrap();
+        assert_eq!(decoded[0].name, "Spec_FPACC");
+        assert_eq!(decoded[0].description, None);
+        assert_eq!(decoded[15].name, "TCRX");
+        assert_eq!(decoded[15].description, Some("FEAT_TCR2".to_string()));
+    }
+
+    #[test]
+    fn feature_list() {
+        assert_eq!(
+            id_register_features("ID_AA64ISAR0_EL1", 0x0000_1000_1021_1120),
+            Some(vec![
+                "FEAT_DotProd",
+                "FEAT_RDM",
+                "FEAT_LSE",
+                "FEAT_CRC32",
+                "FEAT_SHA256",
+                "FEAT_SHA1",
+                "FEAT_AES",
+                "FEAT_PMULL"
+            ])
+        );
+        assert_eq!(
+            id_register_features("id_aa64mmfr3_el1", 0x11),
+            Some(vec!["FEAT_SCTLR2", "FEAT_TCR2"])
+        );
+        assert_eq!(id_register_features("ID_AA64MMFR0_EL1", 0), Some(vec![]));
+        assert_eq!(id_register_features("MIDR_EL1", 0), None);
+    }
+}
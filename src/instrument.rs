@@ -0,0 +1,64 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Optional instrumentation of the parsing pipelines with the `tracing` crate.
+//!
+//! The macros here expand to nothing unless the `tracing` feature is enabled, so their arguments
+//! must not have side effects.
+
+/// Logs a `tracing` event at debug level, if the `tracing` feature is enabled.
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// Logs a `tracing` event at trace level, if the `tracing` feature is enabled.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+/// A stage of a parsing pipeline, which is logged as a `tracing` span along with how long it took.
+///
+/// This does nothing unless the `tracing` feature is enabled.
+pub(crate) struct Stage {
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+    #[cfg(feature = "tracing")]
+    start: std::time::Instant,
+}
+
+impl Stage {
+    /// Enters a new stage with the given name.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub fn enter(name: &'static str) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            _span: tracing::debug_span!("stage", name).entered(),
+            #[cfg(feature = "tracing")]
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for Stage {
+    fn drop(&mut self) {
+        tracing::debug!(elapsed = ?self.start.elapsed(), "Stage finished");
+    }
+}
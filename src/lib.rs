@@ -14,17 +14,95 @@
 
 //! Library for decoding aarch64 Exception Syndrome Register and Main ID Register values.
 
+#[macro_use]
+mod instrument;
+
+mod cache;
+mod cptr;
+mod descriptor;
+mod dump;
 mod esr;
+mod filter;
+mod fsr;
+mod hover;
+mod idreg;
 mod midr;
+mod mte;
+mod par;
+mod pmu;
+mod record;
+#[cfg(feature = "devtools")]
+mod reference;
+mod registry;
+mod render;
+mod report;
+mod scan;
+mod sctlr;
+mod selftest;
 mod smccc;
+mod spec;
+mod stats;
+#[cfg(feature = "devtools")]
+mod sysreg_xml;
+mod trace;
+#[cfg(feature = "profiling")]
+mod trbsr;
+mod ttbr;
+mod veclen;
 
 use bit_field::BitField;
-pub use esr::decode;
-pub use midr::decode_midr;
+pub use cache::{decode_ccsidr_el1, decode_clidr_el1, decode_ctr_el0, decode_dczid_el0};
+pub use cptr::{decode_cpacr_el1, decode_cptr_el2, decode_cptr_el3};
+pub use descriptor::{decode_descriptor, TranslationGranule};
+pub use dump::{analyze_register_dump, parse_register_dump, DumpError, DumpReport, DumpedRegister};
+pub use esr::{
+    check_coverage, check_round_trip, decode, decode_lenient, decode_nested, decode_with_options,
+    encode_fields, severity, summarize, summarize_fields, summarize_with_options,
+    validate_forwarded, DataAbort, DecodeOptions, Decoded, EncodeError, ExecutionState, Fault,
+    ForwardingIssue, ImpdefIssDecoder, Injection, InstructionSyndrome, NestedIssue, NestedReport,
+    ParseSeverityError, RoundTripError, Severity, SyndromeAccessSize, Warning,
+};
+pub use filter::{Filter, ParseFilterError};
+pub use fsr::{decode_dfsr, decode_ifsr};
+pub use hover::Hover;
+pub use idreg::{
+    decode_id_aa64isar0, decode_id_aa64isar1, decode_id_aa64isar2, decode_id_aa64mmfr0,
+    decode_id_aa64mmfr1, decode_id_aa64mmfr2, decode_id_aa64mmfr3, id_register_features,
+};
+pub use midr::{decode_midr, midr_core_name};
+pub use mte::{decode_gcr_el1, decode_tfsr};
+pub use par::decode_par;
+pub use pmu::{decode_pmccfiltr_el0, decode_pmcr_el0};
+pub use record::{decode_to_json, FieldRecord, Record, RecordError, SCHEMA_VERSION};
+#[cfg(feature = "devtools")]
+pub use reference::{compare_reference, Discrepancy, Mismatch, ReferenceError, ReferenceReport};
+pub use registry::{register_decoder, RegisterDecoder, REGISTER_DECODERS};
+pub use render::{bits, highlight_field, render_bits, HighlightStyle, ParseHighlightStyleError};
+pub use report::decode_to_string;
+pub use scan::{
+    group_esr_values, scan_esr_values, scan_esr_values_with, GroupedValue, LogFormat, LogFormats,
+    ScannedValue,
+};
+pub use sctlr::{decode_sctlr_el2, decode_sctlr_el3};
+pub use selftest::{self_test, SelfTestReport, SuiteResult};
+use serde::{Deserialize, Serialize};
 pub use smccc::decode_smccc;
+pub use spec::{spec_reference, SpecReference, SPEC_REFERENCES};
+pub use stats::Stats;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::num::ParseIntError;
+use std::str::FromStr;
+#[cfg(feature = "devtools")]
+pub use sysreg_xml::{
+    field_decoder, parse_sysreg_xml, sysreg_name_arms, FieldDefinition, ImportError,
+    SysRegDefinition, SysRegEncoding,
+};
 use thiserror::Error;
+pub use trace::{check_trace, parse_trace, TraceAnomaly, TraceError, TraceEvent, TraceIssue};
+#[cfg(feature = "profiling")]
+pub use trbsr::decode_trbsr;
+pub use ttbr::decode_ttbr;
+pub use veclen::{decode_smcr, decode_zcr};
 
 /// Information about a particular field.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -41,10 +119,44 @@ pub struct FieldInfo {
     pub value: u64,
     /// A description explaining the field value, if available.
     pub description: Option<String>,
+    /// The quantity which the field value encodes, if it encodes one, e.g. a size in bytes.
+    pub derived: Option<Derived>,
     /// Any sub-fields.
     pub subfields: Vec<FieldInfo>,
 }
 
+/// A quantity encoded by a field value, scaled or interpreted according to the field's definition.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Derived {
+    /// The quantity, in the given unit.
+    pub value: i64,
+    /// The unit of the quantity.
+    pub unit: Unit,
+}
+
+impl Display for Derived {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.unit {
+            Unit::Bytes => write!(f, "{} bytes", self.value),
+            Unit::Bits => write!(f, "{} bits", self.value),
+            Unit::Level => write!(f, "level {}", self.value),
+        }
+    }
+}
+
+/// The unit of a [`Derived`] quantity.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    /// A size in bytes.
+    Bytes,
+    /// A size in bits, such as an address size.
+    Bits,
+    /// A level of translation table lookup, from -1 to 3. This is how programs should find the
+    /// level of a fault from a DFSC or IFSC field, rather than parsing its description.
+    Level,
+}
+
 impl FieldInfo {
     fn get(
         register: u64,
@@ -61,6 +173,7 @@ impl FieldInfo {
             width: end - start,
             value,
             description: None,
+            derived: None,
             subfields: vec![],
         }
     }
@@ -81,6 +194,13 @@ impl FieldInfo {
         }
     }
 
+    fn with_derived(self, value: i64, unit: Unit) -> Self {
+        Self {
+            derived: Some(Derived { value, unit }),
+            ..self
+        }
+    }
+
     fn as_bit(&self) -> bool {
         assert!(self.width == 1);
         self.value == 1
@@ -119,7 +239,7 @@ impl FieldInfo {
         if self.width == 1 {
             if self.value == 1 { "true" } else { "false" }.to_string()
         } else {
-            format!("{:#01$x}", self.value, (self.width + 3) / 4 + 2,)
+            format!("{:#01$x}", self.value, self.width.div_ceil(4) + 2,)
         }
     }
 
@@ -127,6 +247,50 @@ impl FieldInfo {
     pub fn value_binary_string(&self) -> String {
         format!("{:#01$b}", self.value, self.width + 2)
     }
+
+    /// Returns the name of the field qualified with its bit range within its parent, e.g.
+    /// "RES0[8]" or "RES0[13:6]".
+    pub fn qualified_name(&self) -> String {
+        if self.width == 1 {
+            format!("{}[{}]", self.name, self.start)
+        } else {
+            format!(
+                "{}[{}:{}]",
+                self.name,
+                self.start + self.width - 1,
+                self.start
+            )
+        }
+    }
+
+    /// Returns the name which uniquely identifies the field among the given fields at the same
+    /// level, which should include it.
+    ///
+    /// This is the plain name if no other field has the same name, such as "ISS", or the qualified
+    /// name otherwise, such as "RES0[13:6]".
+    pub fn canonical_name(&self, siblings: &[FieldInfo]) -> String {
+        if siblings
+            .iter()
+            .filter(|sibling| sibling.name == self.name)
+            .count()
+            > 1
+        {
+            self.qualified_name()
+        } else {
+            self.name.to_owned()
+        }
+    }
+
+    /// Returns whether the given name refers to this field, either as its plain name or its
+    /// qualified name, ignoring case.
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name) || self.qualified_name().eq_ignore_ascii_case(name)
+    }
+
+    /// Returns the first of the given fields with exactly the given name, if any.
+    pub(crate) fn find<'a>(fields: &'a [Self], name: &str) -> Option<&'a Self> {
+        fields.iter().find(|field| field.name == name)
+    }
 }
 
 impl Display for FieldInfo {
@@ -174,8 +338,118 @@ pub enum DecodeError {
     /// The ISS field has an invalid value for a trapped LD64B or ST64B* exception.
     #[error("Invalid ISS {iss:#x} for trapped LD64B or ST64B*")]
     InvalidLd64bIss { iss: u64 },
+    /// The SMTC field had an invalid value.
+    #[error("Invalid SMTC {smtc:#x}")]
+    InvalidSmtc { smtc: u64 },
+    /// The GPCSC field had an invalid value.
+    #[error("Invalid GPCSC {gpcsc:#x}")]
+    InvalidGpcsc { gpcsc: u64 },
+    /// The ExType field of a Guarded Control Stack exception had an invalid value.
+    #[error("Invalid ExType {ex_type:#x}")]
+    InvalidExType { ex_type: u64 },
+    /// The IT field of a Guarded Control Stack data check exception had an invalid value.
+    #[error("Invalid IT {it:#x}")]
+    InvalidIt { it: u64 },
+    /// The translation table lookup level is not valid for the granule size.
+    #[error("Invalid translation table level {level}")]
+    InvalidLevel { level: u64 },
+}
+
+/// A version of the Arm A-profile architecture.
+///
+/// Some fields are only defined, or have a different meaning, from a particular version of the
+/// architecture.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ArchVersion {
+    Armv8_0,
+    Armv8_1,
+    Armv8_2,
+    Armv8_3,
+    Armv8_4,
+    Armv8_5,
+    Armv8_6,
+    Armv8_7,
+    Armv8_8,
+    Armv8_9,
+    Armv9_0,
+    Armv9_1,
+    Armv9_2,
+    Armv9_3,
+    Armv9_4,
+    Armv9_5,
 }
 
+impl ArchVersion {
+    /// The latest version of the architecture which this crate knows about.
+    pub const LATEST: Self = Self::Armv9_5;
+
+    const ALL: [Self; 16] = [
+        Self::Armv8_0,
+        Self::Armv8_1,
+        Self::Armv8_2,
+        Self::Armv8_3,
+        Self::Armv8_4,
+        Self::Armv8_5,
+        Self::Armv8_6,
+        Self::Armv8_7,
+        Self::Armv8_8,
+        Self::Armv8_9,
+        Self::Armv9_0,
+        Self::Armv9_1,
+        Self::Armv9_2,
+        Self::Armv9_3,
+        Self::Armv9_4,
+        Self::Armv9_5,
+    ];
+
+    /// Returns the major and minor version numbers, e.g. `(8, 2)` for Armv8.2.
+    pub fn number(self) -> (u8, u8) {
+        let index = self as u8;
+        if index < Self::Armv9_0 as u8 {
+            (8, index)
+        } else {
+            (9, index - Self::Armv9_0 as u8)
+        }
+    }
+}
+
+impl Default for ArchVersion {
+    fn default() -> Self {
+        Self::LATEST
+    }
+}
+
+impl Display for ArchVersion {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let (major, minor) = self.number();
+        write!(f, "Armv{}.{}", major, minor)
+    }
+}
+
+impl FromStr for ArchVersion {
+    type Err = ParseArchVersionError;
+
+    /// Parses an architecture version such as "Armv8.2", "v9.4" or "8.5".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        let number = lower.strip_prefix("arm").unwrap_or(&lower);
+        let number = number.strip_prefix('v').unwrap_or(number);
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|version| {
+                let (major, minor) = version.number();
+                number == format!("{}.{}", major, minor)
+            })
+            .ok_or_else(|| ParseArchVersionError(s.to_owned()))
+    }
+}
+
+/// An error parsing an architecture version.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[error("Unknown architecture version {0:?}")]
+pub struct ParseArchVersionError(String);
+
 /// Parses a decimal or hexadecimal number from a string.
 ///
 /// If the string starts with `"0x"` then it will be parsed as hexadecimal, otherwise it will be
@@ -206,4 +480,37 @@ mod tests {
     fn parse_invalid() {
         assert!(parse_number("123abc").is_err());
     }
+
+    #[test]
+    fn parse_arch_version() {
+        assert_eq!("Armv8.2".parse(), Ok(ArchVersion::Armv8_2));
+        assert_eq!("v9.0".parse(), Ok(ArchVersion::Armv9_0));
+        assert_eq!("9.5".parse(), Ok(ArchVersion::Armv9_5));
+        assert!("8.10".parse::<ArchVersion>().is_err());
+        assert_eq!(ArchVersion::Armv8_7.to_string(), "Armv8.7");
+    }
+
+    #[test]
+    fn canonical_names() {
+        let decoded = decode(0x82001e10).unwrap();
+        let iss = &decoded[4].subfields;
+        let names: Vec<_> = iss.iter().map(|field| field.canonical_name(iss)).collect();
+        assert_eq!(
+            names,
+            vec![
+                "RES0[24:13]",
+                "SET",
+                "FnV",
+                "EA",
+                "RES0[8]",
+                "S1PTW",
+                "RES0[6]",
+                "IFSC"
+            ]
+        );
+        assert_eq!(decoded[0].canonical_name(&decoded), "RES0");
+        assert!(iss[4].has_name("res0[8]"));
+        assert!(iss[4].has_name("RES0"));
+        assert!(!iss[4].has_name("RES0[6]"));
+    }
 }
@@ -12,9 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use aarch64_esr_decoder::{decode, parse_number, FieldInfo};
+use aarch64_esr_decoder::{
+    analyze_register_dump, check_trace, decode, decode_lenient, decode_nested, decode_with_options,
+    group_esr_values, highlight_field, parse_number, parse_register_dump, parse_trace,
+    register_decoder, scan_esr_values_with, self_test, severity, spec_reference, summarize_fields,
+    summarize_with_options, validate_forwarded, ArchVersion, DataAbort, DecodeOptions,
+    ExecutionState, Fault, FieldInfo, Filter, HighlightStyle, Injection, LogFormats, Record,
+    Severity, Stats, REGISTER_DECODERS,
+};
 use std::env;
-use std::process::exit;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::{exit, Command, Stdio};
 
 fn main() {
     let args = match parse_args() {
@@ -22,17 +31,406 @@ fn main() {
         Err(error_code) => exit(error_code),
     };
 
-    let esr = parse_number(&args.esr).unwrap();
-    let decoded = decode(esr).unwrap();
+    let worst = match &args.input {
+        Input::Value(value) if args.register.is_some() => {
+            let value = parse_number(value).unwrap_or_else(|e| {
+                eprintln!("Invalid register value {:?}: {}", value, e);
+                exit(1);
+            });
+            decode_register(&args, value);
+            None
+        }
+        Input::Value(esr) => {
+            let esr = parse_number(esr).unwrap();
+            decode_value(&args, esr);
+            Some(severity(esr))
+        }
+        Input::Encode(instruction) => {
+            let esr = encode_instruction(&args, instruction);
+            decode_value(&args, esr);
+            Some(severity(esr))
+        }
+        Input::Inject(injection) => {
+            let esr = injection.encode().unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(1);
+            });
+            decode_value(&args, esr);
+            Some(severity(esr))
+        }
+        Input::Dump(path) => {
+            decode_dump(&args, path);
+            None
+        }
+        Input::Trace(path) => {
+            check_trace_file(path);
+            None
+        }
+        Input::Scan(path) => scan_file(&args, path),
+        Input::ListRegisters => {
+            for decoder in REGISTER_DECODERS {
+                println!("{}: {}", decoder.name, decoder.long_name);
+            }
+            None
+        }
+        Input::SelfTest => {
+            let report = self_test();
+            print!("{}", report);
+            if !report.passed() {
+                exit(1);
+            }
+            None
+        }
+    };
+    if let (Some(fail_on), Some(worst)) = (args.fail_on, worst) {
+        if worst >= fail_on {
+            eprintln!(
+                "Found exception of severity {}, which is at least {}.",
+                worst, fail_on
+            );
+            exit(2);
+        }
+    }
+}
+
+/// Decodes and prints a single ESR value.
+fn decode_value(args: &Args, esr: u64) {
+    if let Some(esr_el2) = args.esr_el2 {
+        let report = decode_nested(esr, esr_el2).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            exit(1);
+        });
+        println!("ESR_EL2 {:#034x}:", esr_el2);
+        print_decoded(esr_el2, &report.esr_el2, args, 0, 0);
+        println!("ESR_EL1 {:#034x}:", esr);
+        print_decoded(esr, &report.esr_el1, args, 0, 0);
+        println!("# {}", report.description);
+        if args.forwarded {
+            let issues = validate_forwarded(esr_el2, esr).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                exit(1);
+            });
+            for issue in issues {
+                println!("! {}", issue);
+            }
+        } else {
+            for issue in &report.issues {
+                println!("! {}", issue);
+            }
+        }
+        return;
+    }
+    let options = DecodeOptions {
+        execution_state: args.spsr.map(ExecutionState::from_spsr),
+        arch_version: args.arch_version,
+        ..Default::default()
+    };
+    if args.json {
+        let fields = decode_with_options(esr, &options).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            exit(1);
+        });
+        let mut record = Record {
+            summary: Some(summarize_fields(&fields)),
+            ..Record::new("ESR", esr, &fields)
+        };
+        if let Some(command) = &args.exec {
+            annotate(command, &mut record);
+        }
+        println!("{}", record.to_json());
+        return;
+    }
     println!("ESR {:#034x}:", esr);
-    print_decoded(&decoded, args.verbose, 0);
+    if args.lenient {
+        println!("# {}", summarize_with_options(esr, &options));
+        print_spec(args);
+        print_lenient(esr, &options, args);
+    } else {
+        let decoded = decode_with_options(esr, &options).unwrap();
+        println!("# {}", summarize_fields(&decoded));
+        print_spec(args);
+        print_decoded(esr, &decoded, args, 0, 0);
+    }
+    print_annotations(args, esr);
+}
+
+/// Decodes and prints a single value of the register given on the command line.
+fn decode_register(args: &Args, value: u64) {
+    // The argument parser ensures that the register is a known one.
+    let decoder = register_decoder(args.register.as_ref().unwrap()).unwrap();
+    let fields = (decoder.decode)(value).unwrap_or_else(|e| {
+        eprintln!("{} {:#034x}: {}", decoder.name, value, e);
+        exit(1);
+    });
+    let mut record = Record::new(decoder.name, value, &fields);
+    if let Some(command) = &args.exec {
+        annotate(command, &mut record);
+    }
+    if args.json {
+        println!("{}", record.to_json());
+    } else {
+        println!("{} {:#034x}:", decoder.name, value);
+        print_decoded(value, &fields, args, 0, 0);
+        for annotation in &record.annotations {
+            println!("> {}", annotation);
+        }
+    }
 }
 
-fn print_decoded(fields: &[FieldInfo], verbose: bool, level: usize) {
+/// In verbose mode, prints which architecture version ESR values are decoded according to, and
+/// which revision of the Arm ARM the tables reflect.
+fn print_spec(args: &Args) {
+    if args.verbose {
+        println!(
+            "# Decoded as {} according to {}",
+            args.arch_version,
+            spec_reference("ESR").unwrap().revision
+        );
+    }
+}
+
+/// Decodes the given ESR value leniently, and prints the result along with any warnings.
+fn print_lenient(esr: u64, options: &DecodeOptions, args: &Args) {
+    let decoded = decode_lenient(esr, options);
+    print_decoded(esr, &decoded.fields, args, 0, 0);
+    for warning in &decoded.warnings {
+        println!("! {}", warning);
+    }
+}
+
+/// Constructs the ESR value for a data abort caused by the given instruction, or exits with an
+/// error message if it can't.
+fn encode_instruction(args: &Args, instruction: &str) -> u64 {
+    // The argument parser ensures that a fault is given with an instruction.
+    let fault = args.fault.unwrap();
+    match DataAbort::from_instruction(instruction, fault) {
+        Ok(abort) => DataAbort {
+            lower_el: args.lower_el,
+            ..abort
+        }
+        .encode(),
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Reads a register dump from the given file, and decodes and prints all the registers in it.
+fn decode_dump(args: &Args, path: &str) {
+    let dump = read_file(path);
+    let registers = match parse_register_dump(&dump) {
+        Ok(registers) => registers,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            exit(1);
+        }
+    };
+    let report = analyze_register_dump(&registers);
+    for register in &report.registers {
+        match &register.decoded {
+            Some(Ok(fields)) => {
+                println!("{} {:#034x}:", register.name, register.value);
+                print_decoded(register.value, fields, args, 0, 0);
+            }
+            Some(Err(e)) => println!("{} {:#034x}: {}", register.name, register.value, e),
+            None => println!("{} {:#034x}", register.name, register.value),
+        }
+    }
+    for note in &report.notes {
+        println!("# {}", note);
+    }
+}
+
+/// Reads a CSV trace of exception events from the given file, and checks each event for
+/// consistency.
+fn check_trace_file(path: &str) {
+    let trace = read_file(path);
+    let events = match parse_trace(&trace) {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            exit(1);
+        }
+    };
+    let anomalies = check_trace(&events);
+    for anomaly in &anomalies {
+        println!("{}: {}", path, anomaly);
+    }
+    println!(
+        "Checked {} events, found {} anomalies.",
+        events.len(),
+        anomalies.len()
+    );
+    if !anomalies.is_empty() {
+        exit(2);
+    }
+}
+
+/// Scans the given file (or standard input if the path is `-`) for ESR values, and either decodes
+/// each of them or prints statistics about them.
+///
+/// Returns the highest severity of any value found.
+fn scan_file(args: &Args, path: &str) -> Option<Severity> {
+    let text = read_file(path);
+    let formats = LogFormats::default();
+    let format = match &args.format {
+        Some(name) => formats.get(name).unwrap(),
+        None => formats.detect(&text),
+    };
+    let mut values = scan_esr_values_with(&text, format);
+    if !args.stats {
+        print_spec(args);
+    }
+    if let Some(filter) = &args.filter {
+        values.retain(|value| {
+            decode(value.esr)
+                .map(|fields| filter.matches(&fields))
+                .unwrap_or(false)
+        });
+    }
+    let worst = values.iter().map(|value| severity(value.esr)).max();
+    if args.stats {
+        let mut stats = Stats::default();
+        stats.extend(values.iter().map(|value| value.esr));
+        print!("{}", stats);
+        return worst;
+    }
+    if args.group {
+        for group in group_esr_values(&values) {
+            println!("ESR {:#034x}, {}:", group.esr, describe_lines(&group.lines));
+            decode_scanned(args, group.esr);
+        }
+        return worst;
+    }
+    for value in &values {
+        println!("Line {}: ESR {:#034x}:", value.line, value.esr);
+        decode_scanned(args, value.esr);
+    }
+    worst
+}
+
+/// Decodes and prints an ESR value found by scanning.
+fn decode_scanned(args: &Args, esr: u64) {
+    let options = DecodeOptions {
+        arch_version: args.arch_version,
+        ..Default::default()
+    };
+    if args.lenient {
+        println!("# {}", summarize_with_options(esr, &options));
+        print_lenient(esr, &options, args);
+        print_annotations(args, esr);
+        return;
+    }
+    match decode_with_options(esr, &options) {
+        Ok(decoded) => {
+            println!("# {}", summarize_fields(&decoded));
+            print_decoded(esr, &decoded, args, 0, 0);
+            print_annotations(args, esr);
+        }
+        Err(e) => println!("# {}", e),
+    }
+}
+
+/// If an `--exec` command was given, runs it with a JSON record of the given ESR value on its
+/// standard input, and prints each line which it writes to its standard output as an annotation.
+fn print_annotations(args: &Args, esr: u64) {
+    let command = match &args.exec {
+        Some(command) => command,
+        None => return,
+    };
+    // Values which can't be decoded strictly have no record to pass on.
+    let mut record = match Record::from_esr(esr) {
+        Ok(record) => record,
+        Err(_) => return,
+    };
+    annotate(command, &mut record);
+    for annotation in &record.annotations {
+        println!("> {}", annotation);
+    }
+}
+
+/// Runs the given `--exec` command with the given record, and adds its output to the record's
+/// annotations, or exits with an error message if it fails.
+fn annotate(command: &str, record: &mut Record) {
+    match run_exec(command, record) {
+        Ok(output) => record.merge_annotations(&output),
+        Err(e) => {
+            eprintln!("Failed to run {:?}: {}", command, e);
+            exit(1);
+        }
+    }
+}
+
+/// Runs the given shell command with the JSON form of the given record on its standard input, and
+/// returns what it writes to its standard output.
+fn run_exec(command: &str, record: &Record) -> io::Result<String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    writeln!(child.stdin.take().unwrap(), "{}", record.to_json())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!("command {}", output.status)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Maximum number of line numbers to list for a group of identical values.
+const MAX_LINES: usize = 10;
+
+/// Returns a description of the number of occurrences on the given lines, with the list of line
+/// numbers truncated if it is too long.
+fn describe_lines(lines: &[usize]) -> String {
+    let listed = lines
+        .iter()
+        .take(MAX_LINES)
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if lines.len() > MAX_LINES {
+        format!(
+            "{} occurrences on lines {} and {} more",
+            lines.len(),
+            listed,
+            lines.len() - MAX_LINES
+        )
+    } else if lines.len() == 1 {
+        format!("1 occurrence on line {}", listed)
+    } else {
+        format!("{} occurrences on lines {}", lines.len(), listed)
+    }
+}
+
+/// Reads the given file, or standard input if the path is `-`, or exits with an error message if it
+/// can't be read.
+fn read_file(path: &str) -> String {
+    let result = if path == "-" {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents).map(|_| contents)
+    } else {
+        fs::read_to_string(path)
+    };
+    match result {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Error reading {}: {}", path, e);
+            exit(1);
+        }
+    }
+}
+
+/// Prints the given decoded fields of the given register value.
+///
+/// `offset` is the index of the lowest bit of the fields' parent within the register.
+fn print_decoded(register: u64, fields: &[FieldInfo], args: &Args, level: usize, offset: usize) {
     let indentation = " ".repeat(level * 2);
     for field in fields {
         let verbose_name = match field.long_name {
-            Some(long_name) if verbose => format!(" ({})", long_name),
+            Some(long_name) if args.verbose => format!(" ({})", long_name),
             _ => "".to_string(),
         };
         if field.width == 1 {
@@ -50,37 +448,363 @@ fn print_decoded(fields: &[FieldInfo], verbose: bool, level: usize) {
                 verbose_name,
             );
         }
+        if let Some(style) = args.bits {
+            println!(
+                "{}  @ {}",
+                indentation,
+                highlight_field(register, field, offset, style)
+            );
+        }
         if let Some(description) = &field.description {
             println!("{}  # {}", indentation, description);
         }
+        if let (true, Some(derived)) = (args.derived, &field.derived) {
+            println!("{}  = {}", indentation, derived);
+        }
 
-        print_decoded(&field.subfields, verbose, level + 1);
+        print_decoded(
+            register,
+            &field.subfields,
+            args,
+            level + 1,
+            offset + field.start,
+        );
     }
 }
 
 /// Parse and return command-line arguments, or an error code to return.
 fn parse_args() -> Result<Args, i32> {
-    let mut args: Vec<_> = env::args().collect();
-    match args.len() {
-        2 => Ok(Args {
-            verbose: false,
-            esr: args.remove(1),
-        }),
-        3 => Ok(Args {
-            verbose: true,
-            esr: args.remove(2),
-        }),
-        _ => {
-            eprintln!("Usage:");
-            eprintln!("  {} [-v] <ESR value>", args[0]);
-            Err(1)
+    let args: Vec<_> = env::args().collect();
+    let mut verbose = false;
+    let mut stats = false;
+    let mut group = false;
+    let mut fail_on = None;
+    let mut filter = None;
+    let mut format = None;
+    let mut bits = None;
+    let mut fault = None;
+    let mut lower_el = false;
+    let mut forwarded = false;
+    let mut lenient = false;
+    let mut derived = false;
+    let mut json = false;
+    let mut exec = None;
+    let mut spsr = None;
+    let mut arch_version = None;
+    let mut esr_el2 = None;
+    let mut register = None;
+    let mut input = None;
+    let mut remaining = args.iter().skip(1);
+    while let Some(arg) = remaining.next() {
+        match arg.as_str() {
+            "-v" => verbose = true,
+            "--stats" => stats = true,
+            "--group" => group = true,
+            "--spsr" if spsr.is_none() => match remaining.next().map(|v| parse_number(v)) {
+                Some(Ok(value)) => spsr = Some(value),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--arch" if arch_version.is_none() => match remaining.next().map(|v| v.parse()) {
+                Some(Ok(version)) => arch_version = Some(version),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--fail-on" if fail_on.is_none() => match remaining.next().map(|v| v.parse()) {
+                Some(Ok(severity)) => fail_on = Some(severity),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--filter" if filter.is_none() => match remaining.next().map(|v| v.parse()) {
+                Some(Ok(expression)) => filter = Some(expression),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--format" if format.is_none() => match remaining.next() {
+                Some(name) if LogFormats::default().get(name).is_some() => {
+                    format = Some(name.to_owned())
+                }
+                Some(name) => {
+                    eprintln!(
+                        "Unknown log format {:?}, expected one of {}",
+                        name,
+                        LogFormats::default().names().join(", ")
+                    );
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--bits" if bits.is_none() => match remaining.next().map(|v| v.parse()) {
+                Some(Ok(style)) => bits = Some(style),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--fault" if fault.is_none() => match remaining.next().map(|v| v.parse()) {
+                Some(Ok(value)) => fault = Some(value),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--lower-el" => lower_el = true,
+            "--forwarded" => forwarded = true,
+            "--lenient" => lenient = true,
+            "--derived" => derived = true,
+            "--json" => json = true,
+            "--exec" if exec.is_none() => match remaining.next() {
+                Some(command) => exec = Some(command.to_owned()),
+                None => return Err(usage(&args[0])),
+            },
+            #[cfg(feature = "tracing")]
+            "--log" => match remaining.next().map(|v| v.parse::<tracing::Level>()) {
+                Some(Ok(level)) => tracing_subscriber::fmt()
+                    .with_max_level(level)
+                    .with_writer(std::io::stderr)
+                    .init(),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--encode" if input.is_none() => match remaining.next() {
+                Some(instruction) => input = Some(Input::Encode(instruction.to_owned())),
+                None => return Err(usage(&args[0])),
+            },
+            "--inject" if input.is_none() => match remaining.next().map(|v| v.parse()) {
+                Some(Ok(injection)) => input = Some(Input::Inject(injection)),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--el2" if esr_el2.is_none() => match remaining.next().map(|v| parse_number(v)) {
+                Some(Ok(value)) => esr_el2 = Some(value),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--register" if register.is_none() => match remaining.next() {
+                Some(name) if register_decoder(name).is_some() => register = Some(name.to_owned()),
+                Some(name) => {
+                    eprintln!(
+                        "Unknown register {:?}, run with `registers` to list them",
+                        name
+                    );
+                    return Err(usage(&args[0]));
+                }
+                None => return Err(usage(&args[0])),
+            },
+            "--dump" if input.is_none() => match remaining.next() {
+                Some(path) => input = Some(Input::Dump(path.to_owned())),
+                None => return Err(usage(&args[0])),
+            },
+            "--trace" if input.is_none() => match remaining.next() {
+                Some(path) => input = Some(Input::Trace(path.to_owned())),
+                None => return Err(usage(&args[0])),
+            },
+            "--scan" if input.is_none() => match remaining.next() {
+                Some(path) => input = Some(Input::Scan(path.to_owned())),
+                None => return Err(usage(&args[0])),
+            },
+            "selftest" if input.is_none() => input = Some(Input::SelfTest),
+            "registers" if input.is_none() => input = Some(Input::ListRegisters),
+            _ if input.is_none() => input = Some(Input::Value(arg.to_owned())),
+            _ => return Err(usage(&args[0])),
         }
     }
+    if (stats || group || filter.is_some() || format.is_some())
+        && !matches!(input, Some(Input::Scan(_)))
+        || fail_on.is_some()
+            && matches!(
+                input,
+                Some(Input::Dump(_)) | Some(Input::Trace(_)) | Some(Input::SelfTest)
+            )
+    {
+        return Err(usage(&args[0]));
+    }
+    if forwarded && esr_el2.is_none() {
+        return Err(usage(&args[0]));
+    }
+    if exec.is_some()
+        && (stats
+            || esr_el2.is_some()
+            || matches!(
+                input,
+                Some(Input::Dump(_)) | Some(Input::Trace(_)) | Some(Input::SelfTest)
+            ))
+    {
+        return Err(usage(&args[0]));
+    }
+    if (fault.is_some() || lower_el) != matches!(input, Some(Input::Encode(_))) {
+        return Err(usage(&args[0]));
+    }
+    if json
+        && (lenient
+            || bits.is_some()
+            || esr_el2.is_some()
+            || !matches!(
+                input,
+                Some(Input::Value(_)) | Some(Input::Encode(_)) | Some(Input::Inject(_))
+            ))
+    {
+        return Err(usage(&args[0]));
+    }
+    if register.is_some()
+        && (!matches!(input, Some(Input::Value(_)))
+            || spsr.is_some()
+            || esr_el2.is_some()
+            || fail_on.is_some()
+            || lenient)
+    {
+        return Err(usage(&args[0]));
+    }
+    match input {
+        Some(input) => Ok(Args {
+            verbose,
+            stats,
+            group,
+            fail_on,
+            filter,
+            format,
+            bits,
+            fault,
+            lower_el,
+            forwarded,
+            lenient,
+            derived,
+            json,
+            exec,
+            spsr,
+            arch_version: arch_version.unwrap_or_default(),
+            esr_el2,
+            register,
+            input,
+        }),
+        None => Err(usage(&args[0])),
+    }
+}
+
+/// Prints usage information, and returns the error code to exit with.
+fn usage(binary: &str) -> i32 {
+    eprintln!("Usage:");
+    eprintln!(
+        "  {} [-v] [--spsr <SPSR value>] [--arch <version>] \
+         [--el2 <ESR_EL2 value> [--forwarded]] [--fail-on <severity>] <ESR value>",
+        binary
+    );
+    eprintln!("  {} [-v] --register <register name> <value>", binary);
+    eprintln!("  {} registers", binary);
+    eprintln!("  {} [-v] --dump <register dump file>", binary);
+    eprintln!("  {} --trace <CSV trace file>", binary);
+    eprintln!("  {} selftest", binary);
+    eprintln!(
+        "  {} [-v] [--arch <version>] [--stats | --group] [--filter <expression>] \
+         [--format <log format>] [--fail-on <severity>] --scan <log file or ->",
+        binary
+    );
+    eprintln!(
+        "  {} [-v] --encode <load or store instruction> --fault <fault> [--lower-el]",
+        binary
+    );
+    eprintln!(
+        "  {} [-v] --inject <kind>,<fault>[,write][,far-invalid][,el0]",
+        binary
+    );
+    eprintln!("Severities are info, warning, error and fatal.");
+    eprintln!(
+        "Faults are address-size-<level>, translation-<level>, access-flag-<level>, \
+         permission-<level>, external, tag-check, ecc, alignment and tlb-conflict."
+    );
+    eprintln!("Add --lenient to decode as much as possible of invalid values.");
+    eprintln!("Add --derived to show the quantities which field values encode, such as sizes.");
+    eprintln!("Add --json to print a single value as a JSON record rather than as text.");
+    eprintln!("Add --exec <command> to annotate each decoded value with the output of a command.");
+    eprintln!("Add --bits <brackets|color> to show the bits of each field in the register value.");
+    #[cfg(feature = "tracing")]
+    eprintln!("Add --log <trace|debug|info> to log how input files are parsed.");
+    1
 }
 
 /// Command-line arguments.
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct Args {
     verbose: bool,
-    esr: String,
+    /// Whether to print statistics about scanned values rather than decoding each of them.
+    stats: bool,
+    /// Whether to group identical scanned values together rather than decoding each occurrence.
+    group: bool,
+    /// The minimum severity of decoded exception which should cause a non-zero exit code.
+    fail_on: Option<Severity>,
+    /// An expression to filter scanned values by.
+    filter: Option<Filter>,
+    /// The name of the log format to scan, rather than detecting it.
+    format: Option<String>,
+    /// How to highlight the bits of each field in the register value, if they should be shown.
+    bits: Option<HighlightStyle>,
+    /// The fault for which to construct an ESR value.
+    fault: Option<Fault>,
+    /// Whether the constructed ESR value should be for an exception from a lower Exception level.
+    lower_el: bool,
+    /// Whether the main ESR value was forwarded to the guest as a re-encoding of the ESR_EL2 value,
+    /// rather than injected as the consequence of it.
+    forwarded: bool,
+    /// Whether to decode as much as possible of invalid values, and print warnings about them.
+    lenient: bool,
+    /// Whether to print the quantities which field values encode, such as sizes in bytes.
+    derived: bool,
+    /// Whether to print the decoded value as a JSON record rather than as text.
+    json: bool,
+    /// A shell command to run with a JSON record of each decoded value, to annotate it.
+    exec: Option<String>,
+    /// The SPSR value for the exception, used to determine the execution state it was taken from.
+    spsr: Option<u64>,
+    /// The version of the architecture to decode according to.
+    arch_version: ArchVersion,
+    /// The ESR_EL2 value for an exception which led to the main ESR value being injected into a
+    /// guest at EL1.
+    esr_el2: Option<u64>,
+    /// The name of the register to decode the value as, rather than an ESR.
+    register: Option<String>,
+    input: Input,
+}
+
+/// What to decode.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Input {
+    /// A single ESR value.
+    Value(String),
+    /// A file containing a register dump.
+    Dump(String),
+    /// A CSV file containing a trace of exception events.
+    Trace(String),
+    /// A load or store instruction for which to construct a data abort ESR value.
+    Encode(String),
+    /// An exception to inject into a guest, for which to construct an ESR_EL1 value.
+    Inject(Injection),
+    /// A log file (or `-` for standard input) to scan for ESR values.
+    Scan(String),
+    /// Run the decoder's self-test.
+    SelfTest,
+    /// List the registers which can be decoded.
+    ListRegisters,
 }
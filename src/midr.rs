@@ -23,6 +23,10 @@ pub fn decode_midr(midr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     let architecture =
         FieldInfo::get(midr, "Architecture", None, 16, 20).describe(describe_architecture)?;
     let part_num = FieldInfo::get(midr, "PartNum", Some("Part number"), 4, 16);
+    let part_num = match part_name(implementer.value, part_num.value) {
+        Some(name) => part_num.with_description(name.to_string()),
+        None => part_num,
+    };
     let revision = FieldInfo::get(midr, "Revision", None, 0, 4);
 
     Ok(vec![
@@ -35,7 +39,100 @@ pub fn decode_midr(midr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
     ])
 }
 
-fn describe_implementer(implementer: u64) -> Result<&'static str, DecodeError> {
+/// Returns the name of the core which the given Main ID Register value identifies along with its
+/// variant and revision, such as "Arm Cortex-A76 r3p0", if it is a known one.
+pub fn midr_core_name(midr: u64) -> Option<String> {
+    let implementer = midr >> 24 & 0xff;
+    let name = part_name(implementer, midr >> 4 & 0xfff)?;
+    Some(format!(
+        "{} {} r{}p{}",
+        implementer_short_name(implementer)?,
+        name,
+        midr >> 20 & 0xf,
+        midr & 0xf
+    ))
+}
+
+/// Returns the short name of the given implementer, as used in core names.
+fn implementer_short_name(implementer: u64) -> Option<&'static str> {
+    Some(match implementer {
+        0x41 => "Arm",
+        0x42 => "Broadcom",
+        0x43 => "Cavium",
+        0x46 => "Fujitsu",
+        0x48 => "HiSilicon",
+        0x4E => "NVIDIA",
+        0x51 => "Qualcomm",
+        0x61 => "Apple",
+        0xC0 => "Ampere",
+        _ => return None,
+    })
+}
+
+/// Returns the name of the part with the given number from the given implementer, if it is a known
+/// one.
+fn part_name(implementer: u64, part_num: u64) -> Option<&'static str> {
+    Some(match (implementer, part_num) {
+        (0x41, 0xD02) => "Cortex-A34",
+        (0x41, 0xD03) => "Cortex-A53",
+        (0x41, 0xD04) => "Cortex-A35",
+        (0x41, 0xD05) => "Cortex-A55",
+        (0x41, 0xD07) => "Cortex-A57",
+        (0x41, 0xD08) => "Cortex-A72",
+        (0x41, 0xD09) => "Cortex-A73",
+        (0x41, 0xD0A) => "Cortex-A75",
+        (0x41, 0xD0B) => "Cortex-A76",
+        (0x41, 0xD0C) => "Neoverse N1",
+        (0x41, 0xD0D) => "Cortex-A77",
+        (0x41, 0xD0E) => "Cortex-A76AE",
+        (0x41, 0xD40) => "Neoverse V1",
+        (0x41, 0xD41) => "Cortex-A78",
+        (0x41, 0xD42) => "Cortex-A78AE",
+        (0x41, 0xD44) => "Cortex-X1",
+        (0x41, 0xD46) => "Cortex-A510",
+        (0x41, 0xD47) => "Cortex-A710",
+        (0x41, 0xD48) => "Cortex-X2",
+        (0x41, 0xD49) => "Neoverse N2",
+        (0x41, 0xD4A) => "Neoverse E1",
+        (0x41, 0xD4B) => "Cortex-A78C",
+        (0x41, 0xD4D) => "Cortex-A715",
+        (0x41, 0xD4E) => "Cortex-X3",
+        (0x41, 0xD4F) => "Neoverse V2",
+        (0x41, 0xD80) => "Cortex-A520",
+        (0x41, 0xD81) => "Cortex-A720",
+        (0x41, 0xD82) => "Cortex-X4",
+        (0x41, 0xD84) => "Neoverse V3",
+        (0x41, 0xD85) => "Cortex-X925",
+        (0x41, 0xD87) => "Cortex-A725",
+        (0x41, 0xD8E) => "Neoverse N3",
+        (0x42, 0x516) => "Vulcan",
+        (0x43, 0x0A1) => "ThunderX",
+        (0x43, 0x0AF) => "ThunderX2",
+        (0x46, 0x001) => "A64FX",
+        (0x48, 0xD01) => "TSV110",
+        (0x4E, 0x003) => "Denver",
+        (0x4E, 0x004) => "Carmel",
+        (0x51, 0x800) => "Kryo 2XX Gold",
+        (0x51, 0x801) => "Kryo 2XX Silver",
+        (0x51, 0x802) => "Kryo 3XX Gold",
+        (0x51, 0x803) => "Kryo 3XX Silver",
+        (0x51, 0x804) => "Kryo 4XX Gold",
+        (0x51, 0x805) => "Kryo 4XX Silver",
+        (0x51, 0xC00) => "Falkor",
+        (0x61, 0x022) => "Icestorm",
+        (0x61, 0x023) => "Firestorm",
+        (0x61, 0x024) => "Icestorm (M1 Pro)",
+        (0x61, 0x025) => "Firestorm (M1 Pro)",
+        (0x61, 0x028) => "Icestorm (M1 Max)",
+        (0x61, 0x029) => "Firestorm (M1 Max)",
+        (0x61, 0x032) => "Blizzard",
+        (0x61, 0x033) => "Avalanche",
+        (0xC0, 0xAC3) => "AmpereOne",
+        _ => return None,
+    })
+}
+
+pub(crate) fn describe_implementer(implementer: u64) -> Result<&'static str, DecodeError> {
     Ok(match implementer {
         0x00 => "Reserved for software use",
         0xC0 => "Ampere Computing",
@@ -44,12 +141,14 @@ fn describe_implementer(implementer: u64) -> Result<&'static str, DecodeError> {
         0x43 => "Cavium Inc.",
         0x44 => "Digital Equipment Corporation",
         0x46 => "Fujitsu Ltd.",
+        0x48 => "HiSilicon Technologies Co., Ltd.",
         0x49 => "Infineon Technologies AG",
         0x4D => "Motorola or Freescale Semiconductor Inc.",
         0x4E => "NVIDIA Corporation",
         0x50 => "Applied Micro Circuits Corporation",
         0x51 => "Qualcomm Inc.",
         0x56 => "Marvell International Ltd.",
+        0x61 => "Apple Inc.",
         0x69 => "Intel Corporation",
         _ => "Unknown",
     })
@@ -68,3 +167,31 @@ fn describe_architecture(architecture: u64) -> Result<&'static str, DecodeError>
         _ => "Reserved",
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_num() {
+        let decoded = decode_midr(0x413fd0c1).unwrap();
+        assert_eq!(decoded[4].name, "PartNum");
+        assert_eq!(decoded[4].description, Some("Neoverse N1".to_string()));
+        assert_eq!(decoded[1].description, Some("Arm Limited".to_string()));
+        assert_eq!(decode_midr(0x410fd990).unwrap()[4].description, None);
+    }
+
+    #[test]
+    fn core_name() {
+        assert_eq!(
+            midr_core_name(0x413fd0b0),
+            Some("Arm Cortex-A76 r3p0".to_string())
+        );
+        assert_eq!(
+            midr_core_name(0x611f0231),
+            Some("Apple Firestorm r1p1".to_string())
+        );
+        assert_eq!(midr_core_name(0x410fd990), None);
+        assert_eq!(midr_core_name(0x00000000), None);
+    }
+}
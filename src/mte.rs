@@ -0,0 +1,111 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoders for the Memory Tagging Extension control and status registers.
+
+use super::{DecodeError, FieldInfo};
+
+/// Decodes the given Tag Fault Status Register value, such as TFSR_EL1 or TFSRE0_EL1, or returns
+/// an error if it is not valid.
+///
+/// These record asynchronous Tag Check Faults, which are not reported through the ESR.
+pub fn decode_tfsr(tfsr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(tfsr, "RES0", Some("Reserved"), 2, 64).check_res0()?;
+    let tf1 =
+        FieldInfo::get_bit(tfsr, "TF1", Some("Tag Check Fault 1"), 1).describe_bit(describe_tf1);
+    let tf0 =
+        FieldInfo::get_bit(tfsr, "TF0", Some("Tag Check Fault 0"), 0).describe_bit(describe_tf0);
+    Ok(vec![res0, tf1, tf0])
+}
+
+/// Decodes the given Tag Control Register value, or returns an error if it is not valid.
+pub fn decode_gcr_el1(gcr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(gcr, "RES0", Some("Reserved"), 17, 64).check_res0()?;
+    let rrnd = FieldInfo::get_bit(gcr, "RRND", Some("Random allocation tag selection"), 16)
+        .describe_bit(describe_rrnd);
+    let exclude = FieldInfo::get(gcr, "Exclude", Some("Allocation tag exclude mask"), 0, 16);
+    let exclude_description = describe_exclude(exclude.value);
+    let exclude = exclude.with_description(exclude_description);
+    Ok(vec![res0, rrnd, exclude])
+}
+
+/// Lists the allocation tags which the given exclude mask prevents IRG from generating.
+fn describe_exclude(exclude: u64) -> String {
+    if exclude == 0 {
+        "No tags excluded".to_string()
+    } else if exclude == 0xffff {
+        "All tags excluded, so IRG generates tag 0".to_string()
+    } else {
+        let tags = (0..16)
+            .filter(|tag| exclude & 1 << tag != 0)
+            .map(|tag| format!("{:#x}", tag))
+            .collect::<Vec<_>>();
+        format!("Tags {} excluded", tags.join(", "))
+    }
+}
+
+fn describe_tf1(tf1: bool) -> &'static str {
+    if tf1 {
+        "Tag Check Fault detected asynchronously for an address in the TTBR1 range"
+    } else {
+        "No asynchronous Tag Check Fault for an address in the TTBR1 range"
+    }
+}
+
+fn describe_tf0(tf0: bool) -> &'static str {
+    if tf0 {
+        "Tag Check Fault detected asynchronously for an address in the TTBR0 range"
+    } else {
+        "No asynchronous Tag Check Fault for an address in the TTBR0 range"
+    }
+}
+
+fn describe_rrnd(rrnd: bool) -> &'static str {
+    if rrnd {
+        "IRG generates tags with an IMPLEMENTATION DEFINED algorithm"
+    } else {
+        "IRG generates tags with the architected pseudo-random algorithm seeded by RGSR_EL1"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tfsr() {
+        let decoded = decode_tfsr(0x2).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert!(decoded[1].as_bit());
+        assert!(!decoded[2].as_bit());
+        assert!(matches!(
+            decode_tfsr(0x4),
+            Err(DecodeError::InvalidRes0 { .. })
+        ));
+    }
+
+    #[test]
+    fn gcr() {
+        let decoded = decode_gcr_el1(0x1_8001).unwrap();
+        assert!(decoded[1].as_bit());
+        assert_eq!(
+            decoded[2].description,
+            Some("Tags 0x0, 0xf excluded".to_string())
+        );
+        assert_eq!(
+            decode_gcr_el1(0).unwrap()[2].description,
+            Some("No tags excluded".to_string())
+        );
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::esr::describe_fsc;
+use super::{DecodeError, FieldInfo};
+
+/// Decodes the given Physical Address Register value, giving either the result of a successful
+/// address translation instruction or the fault it caused, or returns an error if it is not valid.
+pub fn decode_par(par: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let f = FieldInfo::get_bit(par, "F", Some("Fault"), 0).describe_bit(describe_f);
+    let mut fields = if f.as_bit() {
+        let impdefa = FieldInfo::get(par, "IMPDEF", Some("IMPLEMENTATION DEFINED"), 48, 64);
+        let res0a = FieldInfo::get(par, "RES0", Some("Reserved"), 12, 48).check_res0()?;
+        let res1 = FieldInfo::get_bit(par, "RES1", Some("Reserved"), 11);
+        let impdefb = FieldInfo::get_bit(par, "IMPDEF", Some("IMPLEMENTATION DEFINED"), 10);
+        let s = FieldInfo::get_bit(par, "S", Some("Stage of fault"), 9).describe_bit(describe_s);
+        let ptw =
+            FieldInfo::get_bit(par, "PTW", Some("Page Table Walk"), 8).describe_bit(describe_ptw);
+        let res0b = FieldInfo::get_bit(par, "RES0", Some("Reserved"), 7).check_res0()?;
+        let fst =
+            FieldInfo::get(par, "FST", Some("Fault Status Code"), 1, 7).describe(describe_fsc)?;
+        vec![impdefa, res0a, res1, impdefb, s, ptw, res0b, fst]
+    } else {
+        let attr = FieldInfo::get(par, "ATTR", Some("Memory attributes"), 56, 64);
+        let attr_description = describe_attr(attr.value);
+        let attr = attr.with_description(attr_description);
+        let res0a = FieldInfo::get(par, "RES0", Some("Reserved"), 52, 56).check_res0()?;
+        let pa = FieldInfo::get(par, "PA", Some("Physical Address"), 12, 52);
+        let pa_description = format!("{:#x}", pa.value << 12);
+        let pa = pa.with_description(pa_description);
+        let nse = FieldInfo::get_bit(par, "NSE", Some("Non-secure extension"), 11);
+        let impdef = FieldInfo::get_bit(par, "IMPDEF", Some("IMPLEMENTATION DEFINED"), 10);
+        let ns = FieldInfo::get_bit(par, "NS", Some("Non-secure"), 9);
+        let ns_description = describe_pas(nse.as_bit(), ns.as_bit());
+        let ns = ns.with_description(ns_description.to_string());
+        let sh = FieldInfo::get(par, "SH", Some("Shareability"), 7, 9).describe(describe_sh)?;
+        let res0b = FieldInfo::get(par, "RES0", Some("Reserved"), 1, 7).check_res0()?;
+        vec![attr, res0a, pa, nse, impdef, ns, sh, res0b]
+    };
+    fields.push(f);
+    Ok(fields)
+}
+
+/// Describes a memory attribute encoding in the format of MAIR_ELx.
+fn describe_attr(attr: u64) -> String {
+    let (outer, inner) = (attr >> 4, attr & 0b1111);
+    if outer == 0 {
+        match inner {
+            0b0000 => "Device-nGnRnE memory",
+            0b0100 => "Device-nGnRE memory",
+            0b1000 => "Device-nGRE memory",
+            0b1100 => "Device-GRE memory",
+            _ => "Reserved",
+        }
+        .to_string()
+    } else if inner == 0 {
+        "Reserved".to_string()
+    } else {
+        format!(
+            "Normal memory, Outer {}, Inner {}",
+            describe_cacheability(outer),
+            describe_cacheability(inner)
+        )
+    }
+}
+
+/// Describes the cacheability of Normal memory in one half of a memory attribute encoding.
+fn describe_cacheability(cacheability: u64) -> &'static str {
+    match cacheability {
+        0b0100 => "Non-cacheable",
+        0b0000..=0b0011 => "Write-Through Transient",
+        0b0101..=0b0111 => "Write-Back Transient",
+        0b1000..=0b1011 => "Write-Through Non-transient",
+        _ => "Write-Back Non-transient",
+    }
+}
+
+/// Describes the physical address space given by the NSE and NS bits.
+///
+/// NSE is RES1 without FEAT_RME, in which case the address space is given by NS alone.
+fn describe_pas(nse: bool, ns: bool) -> &'static str {
+    match (nse, ns) {
+        (false, false) => "Secure physical address space",
+        (false, true) => "Non-secure physical address space",
+        (true, false) => "Root physical address space, or Secure without FEAT_RME",
+        (true, true) => "Realm physical address space, or Non-secure without FEAT_RME",
+    }
+}
+
+pub(crate) fn describe_sh(sh: u64) -> Result<&'static str, DecodeError> {
+    Ok(match sh {
+        0b00 => "Non-shareable",
+        0b10 => "Outer Shareable",
+        0b11 => "Inner Shareable",
+        _ => "Reserved",
+    })
+}
+
+fn describe_f(f: bool) -> &'static str {
+    if f {
+        "Address translation aborted"
+    } else {
+        "Address translation completed successfully"
+    }
+}
+
+fn describe_s(s: bool) -> &'static str {
+    if s {
+        "Translation aborted because of a fault in the stage 2 translation"
+    } else {
+        "Translation aborted because of a fault in the stage 1 translation"
+    }
+}
+
+fn describe_ptw(ptw: bool) -> &'static str {
+    if ptw {
+        "Stage 2 fault during a stage 1 translation table walk"
+    } else {
+        "Not a stage 2 fault during a stage 1 translation table walk"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translation() {
+        let decoded = decode_par(0xff00_0000_8123_4b80).unwrap();
+        assert_eq!(decoded.len(), 9);
+        assert_eq!(decoded[0].name, "ATTR");
+        assert_eq!(
+            decoded[0].description,
+            Some(
+                "Normal memory, Outer Write-Back Non-transient, Inner Write-Back Non-transient"
+                    .to_string()
+            )
+        );
+        assert_eq!(decoded[2].name, "PA");
+        assert_eq!(decoded[2].description, Some("0x81234000".to_string()));
+        assert_eq!(
+            decoded[5].description,
+            Some("Realm physical address space, or Non-secure without FEAT_RME".to_string())
+        );
+        assert_eq!(decoded[6].description, Some("Inner Shareable".to_string()));
+        assert_eq!(
+            decode_par(0x0000_0000_0900_0800).unwrap()[0].description,
+            Some("Device-nGnRnE memory".to_string())
+        );
+    }
+
+    #[test]
+    fn fault() {
+        // Translation fault at level 3, on the stage 2 translation of a stage 1 walk.
+        let decoded = decode_par(0x0000_0000_0000_0b0f).unwrap();
+        assert_eq!(decoded.len(), 9);
+        assert_eq!(decoded[4].name, "S");
+        assert_eq!(
+            decoded[4].description,
+            Some("Translation aborted because of a fault in the stage 2 translation".to_string())
+        );
+        assert_eq!(decoded[5].name, "PTW");
+        assert_eq!(decoded[5].value, 1);
+        assert_eq!(decoded[7].name, "FST");
+        assert_eq!(
+            decoded[7].description,
+            Some("Translation fault, level 3.".to_string())
+        );
+        // Bit 10 is IMPLEMENTATION DEFINED, so may be set.
+        let decoded = decode_par(0x0000_0000_0000_0f0f).unwrap();
+        assert_eq!(decoded[3].name, "IMPDEF");
+        assert_eq!(decoded[3].value, 1);
+        assert!(matches!(
+            decode_par(0x0000_0000_0000_1001),
+            Err(DecodeError::InvalidRes0 { res0: 1 })
+        ));
+    }
+}
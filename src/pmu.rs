@@ -0,0 +1,240 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoders for the Performance Monitors Extension control registers.
+
+use super::midr::describe_implementer;
+use super::{DecodeError, FieldInfo};
+
+/// Decodes the given Performance Monitors Control Register value, or returns an error if it is not
+/// valid.
+pub fn decode_pmcr_el0(pmcr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0a = FieldInfo::get(pmcr, "RES0", Some("Reserved"), 33, 64).check_res0()?;
+    let fzs =
+        FieldInfo::get_bit(pmcr, "FZS", Some("Freeze-on-SPE event"), 32).describe_bit(describe_fzs);
+    let imp = FieldInfo::get(pmcr, "IMP", Some("Implementer code"), 24, 32)
+        .describe(describe_implementer)?;
+    let idcode = FieldInfo::get(pmcr, "IDCODE", Some("Identification code"), 16, 24);
+    let n = FieldInfo::get(pmcr, "N", Some("Number of event counters"), 11, 16);
+    let n_description = format!("{} event counters", n.value);
+    let n = n.with_description(n_description);
+    let res0b = FieldInfo::get_bit(pmcr, "RES0", Some("Reserved"), 10).check_res0()?;
+    let fzo =
+        FieldInfo::get_bit(pmcr, "FZO", Some("Freeze-on-overflow"), 9).describe_bit(describe_fzo);
+    let res0c = FieldInfo::get_bit(pmcr, "RES0", Some("Reserved"), 8).check_res0()?;
+    let lp = FieldInfo::get_bit(pmcr, "LP", Some("Long event counter enable"), 7)
+        .describe_bit(describe_lp);
+    let lc = FieldInfo::get_bit(pmcr, "LC", Some("Long cycle counter enable"), 6)
+        .describe_bit(describe_lc);
+    let dp = FieldInfo::get_bit(
+        pmcr,
+        "DP",
+        Some("Disable cycle counter when event counting is prohibited"),
+        5,
+    )
+    .describe_bit(describe_dp);
+    let x =
+        FieldInfo::get_bit(pmcr, "X", Some("Enable export of events"), 4).describe_bit(describe_x);
+    let d = FieldInfo::get_bit(pmcr, "D", Some("Clock divider"), 3).describe_bit(describe_d);
+    let c = FieldInfo::get_bit(pmcr, "C", Some("Cycle counter reset"), 2).describe_bit(describe_c);
+    let p = FieldInfo::get_bit(pmcr, "P", Some("Event counter reset"), 1).describe_bit(describe_p);
+    let e = FieldInfo::get_bit(pmcr, "E", Some("Enable"), 0).describe_bit(describe_e);
+
+    Ok(vec![
+        res0a, fzs, imp, idcode, n, res0b, fzo, res0c, lp, lc, dp, x, d, c, p, e,
+    ])
+}
+
+/// Decodes the given Performance Monitors Cycle Count Filter Register value, or returns an error if
+/// it is not valid.
+///
+/// The filtering bits for other Security states and Exception levels are described in terms of
+/// whether the cycle counter counts there, as they only have an effect relative to P, U or NSH.
+pub fn decode_pmccfiltr_el0(pmccfiltr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0a = FieldInfo::get(pmccfiltr, "RES0", Some("Reserved"), 32, 64).check_res0()?;
+    let p = FieldInfo::get_bit(pmccfiltr, "P", Some("Privileged filtering"), 31);
+    let p_description = counted(!p.as_bit(), "EL1");
+    let p = p.with_description(p_description);
+    let u = FieldInfo::get_bit(pmccfiltr, "U", Some("User filtering"), 30);
+    let u_description = counted(!u.as_bit(), "EL0");
+    let u = u.with_description(u_description);
+    let nsk = FieldInfo::get_bit(pmccfiltr, "NSK", Some("Non-secure EL1 filtering"), 29);
+    let nsk_description = counted(nsk.value == p.value, "Non-secure EL1");
+    let nsk = nsk.with_description(nsk_description);
+    let nsu = FieldInfo::get_bit(pmccfiltr, "NSU", Some("Non-secure EL0 filtering"), 28);
+    let nsu_description = counted(nsu.value == u.value, "Non-secure EL0");
+    let nsu = nsu.with_description(nsu_description);
+    let nsh = FieldInfo::get_bit(pmccfiltr, "NSH", Some("Non-secure EL2 filtering"), 27);
+    let nsh_description = counted(nsh.as_bit(), "Non-secure EL2");
+    let nsh = nsh.with_description(nsh_description);
+    let m = FieldInfo::get_bit(pmccfiltr, "M", Some("EL3 filtering"), 26);
+    let m_description = counted(m.value == p.value, "EL3");
+    let m = m.with_description(m_description);
+    let res0b = FieldInfo::get_bit(pmccfiltr, "RES0", Some("Reserved"), 25).check_res0()?;
+    let sh = FieldInfo::get_bit(pmccfiltr, "SH", Some("Secure EL2 filtering"), 24);
+    let sh_description = counted(sh.value != nsh.value, "Secure EL2");
+    let sh = sh.with_description(sh_description);
+    let t = FieldInfo::get_bit(pmccfiltr, "T", Some("Transactional state filtering"), 23);
+    let rlk = FieldInfo::get_bit(pmccfiltr, "RLK", Some("Realm EL1 filtering"), 22);
+    let rlu = FieldInfo::get_bit(pmccfiltr, "RLU", Some("Realm EL0 filtering"), 21);
+    let rlh = FieldInfo::get_bit(pmccfiltr, "RLH", Some("Realm EL2 filtering"), 20);
+    let res0c = FieldInfo::get(pmccfiltr, "RES0", Some("Reserved"), 0, 20).check_res0()?;
+
+    Ok(vec![
+        res0a, p, u, nsk, nsu, nsh, m, res0b, sh, t, rlk, rlu, rlh, res0c,
+    ])
+}
+
+/// Describes whether the cycle counter counts at the given Exception level.
+fn counted(counted: bool, level: &str) -> String {
+    if counted {
+        format!("Cycles counted at {}", level)
+    } else {
+        format!("Cycles not counted at {}", level)
+    }
+}
+
+fn describe_fzs(fzs: bool) -> &'static str {
+    if fzs {
+        "Event counters freeze on a Statistical Profiling buffer management event"
+    } else {
+        "Event counters do not freeze on a Statistical Profiling buffer management event"
+    }
+}
+
+fn describe_fzo(fzo: bool) -> &'static str {
+    if fzo {
+        "Event counters freeze when an overflow status bit is set"
+    } else {
+        "Event counters do not freeze on overflow"
+    }
+}
+
+fn describe_lp(lp: bool) -> &'static str {
+    if lp {
+        "Event counter overflow on increment that changes bit 63 from 1 to 0"
+    } else {
+        "Event counter overflow on increment that changes bit 31 from 1 to 0"
+    }
+}
+
+fn describe_lc(lc: bool) -> &'static str {
+    if lc {
+        "Cycle counter overflow on increment that changes bit 63 from 1 to 0"
+    } else {
+        "Cycle counter overflow on increment that changes bit 31 from 1 to 0"
+    }
+}
+
+fn describe_dp(dp: bool) -> &'static str {
+    if dp {
+        "Cycle counter disabled where event counting is prohibited"
+    } else {
+        "Cycle counter not affected by prohibition of event counting"
+    }
+}
+
+fn describe_x(x: bool) -> &'static str {
+    if x {
+        "Events exported to an external monitoring block"
+    } else {
+        "Events not exported"
+    }
+}
+
+fn describe_d(d: bool) -> &'static str {
+    if d {
+        "Cycle counter counts once every 64 clock cycles"
+    } else {
+        "Cycle counter counts every clock cycle"
+    }
+}
+
+fn describe_c(c: bool) -> &'static str {
+    if c {
+        "Reset PMCCNTR_EL0 to zero"
+    } else {
+        "No action"
+    }
+}
+
+fn describe_p(p: bool) -> &'static str {
+    if p {
+        "Reset all event counters, not including PMCCNTR_EL0, to zero"
+    } else {
+        "No action"
+    }
+}
+
+fn describe_e(e: bool) -> &'static str {
+    if e {
+        "Counters enabled, subject to PMCNTENSET_EL0"
+    } else {
+        "Counters disabled"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pmcr() {
+        let decoded = decode_pmcr_el0(0x4104_3041).unwrap();
+        assert_eq!(decoded.len(), 16);
+        assert_eq!(decoded[2].description, Some("Arm Limited".to_string()));
+        assert_eq!(decoded[3].value, 0x04);
+        assert_eq!(decoded[4].description, Some("6 event counters".to_string()));
+        assert_eq!(
+            decoded[9].description,
+            Some("Cycle counter overflow on increment that changes bit 63 from 1 to 0".to_string())
+        );
+        assert!(decoded[15].as_bit());
+        assert!(matches!(
+            decode_pmcr_el0(1 << 10),
+            Err(DecodeError::InvalidRes0 { .. })
+        ));
+    }
+
+    #[test]
+    fn pmccfiltr() {
+        // Exclude EL1 and, as NSK is 0, Non-secure EL1, but count at Non-secure EL2.
+        let decoded = decode_pmccfiltr_el0(0x8800_0000).unwrap();
+        assert_eq!(decoded.len(), 14);
+        assert_eq!(
+            decoded[1].description,
+            Some("Cycles not counted at EL1".to_string())
+        );
+        assert_eq!(
+            decoded[3].description,
+            Some("Cycles not counted at Non-secure EL1".to_string())
+        );
+        assert_eq!(
+            decoded[4].description,
+            Some("Cycles counted at Non-secure EL0".to_string())
+        );
+        assert_eq!(
+            decoded[5].description,
+            Some("Cycles counted at Non-secure EL2".to_string())
+        );
+        assert_eq!(
+            decoded[6].description,
+            Some("Cycles not counted at EL3".to_string())
+        );
+        assert_eq!(
+            decoded[8].description,
+            Some("Cycles counted at Secure EL2".to_string())
+        );
+    }
+}
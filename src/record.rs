@@ -0,0 +1,256 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Machine-readable records of decoded register values, with a versioned schema.
+
+use crate::{decode, summarize, DecodeError, Derived, FieldInfo};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The version of the schema used for machine-readable output.
+///
+/// This is incremented whenever a change is made which could break an existing consumer, such as
+/// removing or renaming a field. Adding a new optional field doesn't change the version. Records
+/// from any earlier schema version can still be read by [`Record::from_json`].
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// An error reading a machine-readable record.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum RecordError {
+    /// The record wasn't valid JSON, or didn't match the schema.
+    #[error("Invalid record: {0}")]
+    Invalid(String),
+    /// The record was written with a newer schema version than this version of the crate supports.
+    #[error(
+        "Unsupported schema version {version}, expected at most {}",
+        SCHEMA_VERSION
+    )]
+    UnsupportedVersion { version: u32 },
+}
+
+/// A machine-readable record of a decoded register value.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Record {
+    /// The schema version which the record was written with.
+    pub schema_version: u32,
+    /// The name of the register, e.g. "ESR".
+    pub register: String,
+    /// The raw value of the register.
+    pub value: u64,
+    /// A one-sentence summary of the value, if available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// The decoded fields of the register.
+    pub fields: Vec<FieldRecord>,
+    /// Notes added by external tools, such as known bugs or board-specific explanations.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<String>,
+}
+
+/// A machine-readable record of a single decoded field.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FieldRecord {
+    /// The short name of the field, e.g. "ISS".
+    pub name: String,
+    /// The name which uniquely identifies the field among the fields at the same level. This is
+    /// the same as `name` unless several fields have that name, in which case it is qualified with
+    /// the bit range, e.g. "RES0[13:6]". It is empty in records written before it was added.
+    #[serde(default)]
+    pub id: String,
+    /// The long name of the field, e.g. "Instruction Specific Syndrome".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_name: Option<String>,
+    /// The index of the lowest bit of the field.
+    pub start: usize,
+    /// The number of bits in the field.
+    pub width: usize,
+    /// The value of the field.
+    pub value: u64,
+    /// A description explaining the field value, if available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The quantity which the field value encodes, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derived: Option<Derived>,
+    /// Any sub-fields.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subfields: Vec<FieldRecord>,
+}
+
+impl FieldRecord {
+    /// Creates records of the given fields at the same level, and their sub-fields.
+    pub fn from_fields(fields: &[FieldInfo]) -> Vec<Self> {
+        fields
+            .iter()
+            .map(|field| Self {
+                name: field.name.to_owned(),
+                id: field.canonical_name(fields),
+                long_name: field.long_name.map(ToOwned::to_owned),
+                start: field.start,
+                width: field.width,
+                value: field.value,
+                description: field.description.clone(),
+                derived: field.derived,
+                subfields: Self::from_fields(&field.subfields),
+            })
+            .collect()
+    }
+}
+
+/// Decodes the given ESR value and returns a JSON record of it, including its summary.
+///
+/// This is a shortcut for [`Record::from_esr`] followed by [`Record::to_json`].
+pub fn decode_to_json(esr: u64) -> Result<String, DecodeError> {
+    Record::from_esr(esr).map(|record| record.to_json())
+}
+
+impl Record {
+    /// Creates a record of the given decoded register value, with the current schema version.
+    pub fn new(register: &str, value: u64, fields: &[FieldInfo]) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            register: register.to_owned(),
+            value,
+            summary: None,
+            fields: FieldRecord::from_fields(fields),
+            annotations: vec![],
+        }
+    }
+
+    /// Decodes the given ESR value and creates a record of it, including its summary.
+    pub fn from_esr(esr: u64) -> Result<Self, DecodeError> {
+        let fields = decode(esr)?;
+        Ok(Self {
+            summary: Some(summarize(esr)),
+            ..Self::new("ESR", esr, &fields)
+        })
+    }
+
+    /// Adds each non-empty line of the given output of an external tool as an annotation.
+    pub fn merge_annotations(&mut self, output: &str) {
+        self.annotations.extend(
+            output
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(ToOwned::to_owned),
+        );
+    }
+
+    /// Serialises the record as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Record serialisation can't fail")
+    }
+
+    /// Parses a record from JSON written with the current or any earlier schema version.
+    pub fn from_json(json: &str) -> Result<Self, RecordError> {
+        let record: Self =
+            serde_json::from_str(json).map_err(|e| RecordError::Invalid(e.to_string()))?;
+        if record.schema_version > SCHEMA_VERSION {
+            return Err(RecordError::UnsupportedVersion {
+                version: record.schema_version,
+            });
+        }
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records written with each earlier schema version, which must still be readable.
+    const GOLDEN: &[(u64, &str)] = &[
+        (
+            0x96000050,
+            include_str!("../testdata/schema-v1/data_abort.json"),
+        ),
+        (0x56000001, include_str!("../testdata/schema-v1/svc.json")),
+    ];
+
+    #[test]
+    fn golden_records_parse() {
+        for &(esr, json) in GOLDEN {
+            let record = Record::from_json(json).unwrap();
+            assert!(record.schema_version <= SCHEMA_VERSION);
+            assert_eq!(record.register, "ESR");
+            assert_eq!(record.value, esr);
+            assert!(!record.fields.is_empty());
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for &(esr, _) in GOLDEN {
+            let record = Record::from_esr(esr).unwrap();
+            assert_eq!(record.schema_version, SCHEMA_VERSION);
+            assert_eq!(Record::from_json(&record.to_json()), Ok(record));
+        }
+    }
+
+    #[test]
+    fn to_json() {
+        let json = decode_to_json(0x96000050).unwrap();
+        assert_eq!(
+            Record::from_json(&json),
+            Ok(Record::from_esr(0x96000050).unwrap())
+        );
+        assert!(decode_to_json(0x1).is_err());
+    }
+
+    #[test]
+    fn unique_ids() {
+        let record = Record::from_esr(0x82001e10).unwrap();
+        let ids: Vec<_> = record.fields[4]
+            .subfields
+            .iter()
+            .map(|field| field.id.as_str())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "RES0[24:13]",
+                "SET",
+                "FnV",
+                "EA",
+                "RES0[8]",
+                "S1PTW",
+                "RES0[6]",
+                "IFSC"
+            ]
+        );
+        assert_eq!(record.fields[0].id, "RES0");
+    }
+
+    #[test]
+    fn annotations() {
+        let mut record = Record::from_esr(0x96000050).unwrap();
+        record.merge_annotations("Known erratum 1234\n\n  Board: foo  \n");
+        assert_eq!(record.annotations, vec!["Known erratum 1234", "Board: foo"]);
+        assert_eq!(Record::from_json(&record.to_json()), Ok(record));
+    }
+
+    #[test]
+    fn newer_version() {
+        let json = r#"{"schema_version":999,"register":"ESR","value":0,"fields":[]}"#;
+        assert_eq!(
+            Record::from_json(json),
+            Err(RecordError::UnsupportedVersion { version: 999 })
+        );
+        assert!(matches!(
+            Record::from_json("{}"),
+            Err(RecordError::Invalid(_))
+        ));
+    }
+}
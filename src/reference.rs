@@ -0,0 +1,257 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Differential testing of decoding against reference data from another source of truth.
+
+use crate::filter::find_field;
+use crate::{decode, parse_number};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// An error parsing reference data.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ReferenceError {
+    /// A line wasn't a valid JSON expectation.
+    #[error("Invalid expectation on line {line}: {message}")]
+    InvalidExpectation { line: usize, message: String },
+    /// A value in an expectation couldn't be parsed.
+    #[error("Invalid number {value:?} on line {line}")]
+    InvalidNumber { line: usize, value: String },
+}
+
+/// A way in which the decoding of a value differs from what the reference expects.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum Mismatch {
+    /// The reference says the value is valid, but it failed to decode.
+    #[error("Expected valid but failed to decode: {0}")]
+    UnexpectedError(String),
+    /// The reference says the value is invalid, but it decoded successfully.
+    #[error("Expected invalid but decoded successfully")]
+    UnexpectedSuccess,
+    /// The reference expects a field which wasn't in the decoded fields.
+    #[error("Expected {path} but it wasn't decoded")]
+    MissingField { path: String },
+    /// A field was decoded with a different value to what the reference expects.
+    #[error("Expected {path} to be {expected:#x} but decoded {actual:#x}")]
+    FieldValue {
+        path: String,
+        expected: u64,
+        actual: u64,
+    },
+}
+
+/// A discrepancy between the reference and the decoding of a particular value.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("Line {line}: ESR {esr:#x}{}: {mismatch}", origin_suffix(.origin))]
+pub struct Discrepancy {
+    /// The line number of the expectation in the reference data, starting from 1.
+    pub line: usize,
+    /// The ESR value which was decoded.
+    pub esr: u64,
+    /// Where the reference got the expectation from, if it says.
+    pub origin: Option<String>,
+    /// How the decoding differs from the expectation.
+    pub mismatch: Mismatch,
+}
+
+fn origin_suffix(origin: &Option<String>) -> String {
+    origin
+        .as_ref()
+        .map_or_else(String::new, |origin| format!(" (from {})", origin))
+}
+
+/// The result of comparing decoding against reference data.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReferenceReport {
+    /// The number of expectations which were checked.
+    pub checked: usize,
+    /// All the discrepancies found, in the order of the expectations.
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// A number in reference data, which may be given either as a JSON number or as a string to be
+/// parsed with [`parse_number`].
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Integer(u64),
+    Text(String),
+}
+
+impl Number {
+    fn parse(&self, line: usize) -> Result<u64, ReferenceError> {
+        match self {
+            Self::Integer(value) => Ok(*value),
+            Self::Text(text) => parse_number(text).map_err(|_| ReferenceError::InvalidNumber {
+                line,
+                value: text.clone(),
+            }),
+        }
+    }
+}
+
+/// A single expectation about how an ESR value should be decoded.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expectation {
+    esr: Number,
+    #[serde(default)]
+    valid: Option<bool>,
+    #[serde(default)]
+    fields: BTreeMap<String, Number>,
+    #[serde(default)]
+    source: Option<String>,
+}
+
+/// Compares the decoding of ESR values against the given reference data, and reports any
+/// discrepancies.
+///
+/// The reference data has one JSON object per line, such as
+/// `{"esr": "0x96000045", "fields": {"EC": "0x25", "ISS.WnR": 1}}`. The `esr` is required; `valid`
+/// may say whether it should decode successfully, `fields` may give the expected values of fields
+/// by path, and `source` may say where the expectation came from. Numbers may be given as JSON
+/// numbers or as strings to be parsed with [`parse_number`]. Empty lines and lines starting with
+/// `#` are ignored.
+pub fn compare_reference(reference: &str) -> Result<ReferenceReport, ReferenceError> {
+    let mut report = ReferenceReport::default();
+    for (index, text) in reference.lines().enumerate() {
+        let line = index + 1;
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let expectation: Expectation =
+            serde_json::from_str(text).map_err(|e| ReferenceError::InvalidExpectation {
+                line,
+                message: e.to_string(),
+            })?;
+        let esr = expectation.esr.parse(line)?;
+        let mut expected_fields = vec![];
+        for (path, value) in &expectation.fields {
+            expected_fields.push((path, value.parse(line)?));
+        }
+
+        report.checked += 1;
+        let mut report_mismatch = |mismatch| {
+            report.discrepancies.push(Discrepancy {
+                line,
+                esr,
+                origin: expectation.source.clone(),
+                mismatch,
+            })
+        };
+        let fields = match decode(esr) {
+            Ok(fields) => {
+                if expectation.valid == Some(false) {
+                    report_mismatch(Mismatch::UnexpectedSuccess);
+                    continue;
+                }
+                fields
+            }
+            Err(e) => {
+                if expectation.valid != Some(false) {
+                    report_mismatch(Mismatch::UnexpectedError(e.to_string()));
+                }
+                continue;
+            }
+        };
+        for (path, expected) in expected_fields {
+            let components: Vec<String> = path.split('.').map(ToOwned::to_owned).collect();
+            match find_field(&fields, &components) {
+                Some(field) if field.value == expected => {}
+                Some(field) => report_mismatch(Mismatch::FieldValue {
+                    path: path.clone(),
+                    expected,
+                    actual: field.value,
+                }),
+                None => report_mismatch(Mismatch::MissingField { path: path.clone() }),
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare() {
+        let reference = "# Data aborts\n\
+                         {\"esr\": \"0x96000045\", \"fields\": {\"EC\": \"0x25\", \"ISS.WnR\": 1}}\n\
+                         {\"esr\": 2516582469, \"fields\": {\"ISS.DFSC\": 4}, \"source\": \"test\"}\n\
+                         \n\
+                         {\"esr\": \"0x96000045\", \"fields\": {\"ISS.Foo\": 0}}\n\
+                         {\"esr\": \"0xffffffff\", \"valid\": false}\n\
+                         {\"esr\": \"0xffffffff\"}\n\
+                         {\"esr\": \"0x96000045\", \"valid\": false}\n";
+        let report = compare_reference(reference).unwrap();
+        assert_eq!(report.checked, 6);
+        assert_eq!(
+            report.discrepancies,
+            vec![
+                Discrepancy {
+                    line: 3,
+                    esr: 0x96000045,
+                    origin: Some("test".to_string()),
+                    mismatch: Mismatch::FieldValue {
+                        path: "ISS.DFSC".to_string(),
+                        expected: 4,
+                        actual: 5,
+                    },
+                },
+                Discrepancy {
+                    line: 5,
+                    esr: 0x96000045,
+                    origin: None,
+                    mismatch: Mismatch::MissingField {
+                        path: "ISS.Foo".to_string()
+                    },
+                },
+                Discrepancy {
+                    line: 7,
+                    esr: 0xffffffff,
+                    origin: None,
+                    mismatch: Mismatch::UnexpectedError("Invalid EC 0x3f".to_string()),
+                },
+                Discrepancy {
+                    line: 8,
+                    esr: 0x96000045,
+                    origin: None,
+                    mismatch: Mismatch::UnexpectedSuccess,
+                },
+            ]
+        );
+        assert_eq!(
+            report.discrepancies[0].to_string(),
+            "Line 3: ESR 0x96000045 (from test): Expected ISS.DFSC to be 0x4 but decoded 0x5"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            compare_reference("{\"esr\": \"0x1\"}\nnot json\n"),
+            Err(ReferenceError::InvalidExpectation { line: 2, .. })
+        ));
+        assert_eq!(
+            compare_reference("{\"esr\": \"0xfoo\"}\n"),
+            Err(ReferenceError::InvalidNumber {
+                line: 1,
+                value: "0xfoo".to_string()
+            })
+        );
+    }
+}
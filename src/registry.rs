@@ -0,0 +1,281 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A registry of the decoders for each System register, keyed by register name.
+
+#[cfg(feature = "profiling")]
+use crate::decode_trbsr;
+use crate::{
+    decode, decode_ccsidr_el1, decode_clidr_el1, decode_cpacr_el1, decode_cptr_el2,
+    decode_cptr_el3, decode_ctr_el0, decode_dczid_el0, decode_dfsr, decode_gcr_el1,
+    decode_id_aa64isar0, decode_id_aa64isar1, decode_id_aa64isar2, decode_id_aa64mmfr0,
+    decode_id_aa64mmfr1, decode_id_aa64mmfr2, decode_id_aa64mmfr3, decode_ifsr, decode_midr,
+    decode_par, decode_pmccfiltr_el0, decode_pmcr_el0, decode_sctlr_el2, decode_sctlr_el3,
+    decode_smcr, decode_tfsr, decode_ttbr, decode_zcr, DecodeError, FieldInfo,
+};
+
+/// A decoder for the values of a particular register.
+#[derive(Clone, Copy, Debug)]
+pub struct RegisterDecoder {
+    /// The name of the register, e.g. "MIDR_EL1".
+    pub name: &'static str,
+    /// The full name of the register, e.g. "Main ID Register".
+    pub long_name: &'static str,
+    /// Decodes a value of the register, or returns an error if it is not valid.
+    ///
+    /// Where the layout of a register depends on other registers, this assumes their reset values,
+    /// e.g. that HCR_EL2.E2H is 0 and that no TCR value is known.
+    pub decode: fn(u64) -> Result<Vec<FieldInfo>, DecodeError>,
+}
+
+/// All the registers which the crate can decode, in alphabetical order.
+pub const REGISTER_DECODERS: &[RegisterDecoder] = &[
+    RegisterDecoder {
+        name: "CCSIDR_EL1",
+        long_name: "Current Cache Size ID Register",
+        decode: |value| decode_ccsidr_el1(value, false),
+    },
+    RegisterDecoder {
+        name: "CLIDR_EL1",
+        long_name: "Cache Level ID Register",
+        decode: decode_clidr_el1,
+    },
+    RegisterDecoder {
+        name: "CPACR_EL1",
+        long_name: "Architectural Feature Access Control Register",
+        decode: decode_cpacr_el1,
+    },
+    RegisterDecoder {
+        name: "CPTR_EL2",
+        long_name: "Architectural Feature Trap Register (EL2)",
+        decode: |value| decode_cptr_el2(value, false),
+    },
+    RegisterDecoder {
+        name: "CPTR_EL3",
+        long_name: "Architectural Feature Trap Register (EL3)",
+        decode: decode_cptr_el3,
+    },
+    RegisterDecoder {
+        name: "CTR_EL0",
+        long_name: "Cache Type Register",
+        decode: decode_ctr_el0,
+    },
+    RegisterDecoder {
+        name: "DCZID_EL0",
+        long_name: "Data Cache Zero ID Register",
+        decode: decode_dczid_el0,
+    },
+    RegisterDecoder {
+        name: "DFSR",
+        long_name: "Data Fault Status Register",
+        decode: decode_dfsr,
+    },
+    RegisterDecoder {
+        name: "ESR_EL1",
+        long_name: "Exception Syndrome Register (EL1)",
+        decode,
+    },
+    RegisterDecoder {
+        name: "ESR_EL2",
+        long_name: "Exception Syndrome Register (EL2)",
+        decode,
+    },
+    RegisterDecoder {
+        name: "ESR_EL3",
+        long_name: "Exception Syndrome Register (EL3)",
+        decode,
+    },
+    RegisterDecoder {
+        name: "GCR_EL1",
+        long_name: "Tag Control Register",
+        decode: decode_gcr_el1,
+    },
+    RegisterDecoder {
+        name: "ID_AA64ISAR0_EL1",
+        long_name: "AArch64 Instruction Set Attribute Register 0",
+        decode: decode_id_aa64isar0,
+    },
+    RegisterDecoder {
+        name: "ID_AA64ISAR1_EL1",
+        long_name: "AArch64 Instruction Set Attribute Register 1",
+        decode: decode_id_aa64isar1,
+    },
+    RegisterDecoder {
+        name: "ID_AA64ISAR2_EL1",
+        long_name: "AArch64 Instruction Set Attribute Register 2",
+        decode: decode_id_aa64isar2,
+    },
+    RegisterDecoder {
+        name: "ID_AA64MMFR0_EL1",
+        long_name: "AArch64 Memory Model Feature Register 0",
+        decode: decode_id_aa64mmfr0,
+    },
+    RegisterDecoder {
+        name: "ID_AA64MMFR1_EL1",
+        long_name: "AArch64 Memory Model Feature Register 1",
+        decode: decode_id_aa64mmfr1,
+    },
+    RegisterDecoder {
+        name: "ID_AA64MMFR2_EL1",
+        long_name: "AArch64 Memory Model Feature Register 2",
+        decode: decode_id_aa64mmfr2,
+    },
+    RegisterDecoder {
+        name: "ID_AA64MMFR3_EL1",
+        long_name: "AArch64 Memory Model Feature Register 3",
+        decode: decode_id_aa64mmfr3,
+    },
+    RegisterDecoder {
+        name: "IFSR",
+        long_name: "Instruction Fault Status Register",
+        decode: decode_ifsr,
+    },
+    RegisterDecoder {
+        name: "MIDR_EL1",
+        long_name: "Main ID Register",
+        decode: decode_midr,
+    },
+    RegisterDecoder {
+        name: "PAR_EL1",
+        long_name: "Physical Address Register",
+        decode: decode_par,
+    },
+    RegisterDecoder {
+        name: "PMCCFILTR_EL0",
+        long_name: "Performance Monitors Cycle Count Filter Register",
+        decode: decode_pmccfiltr_el0,
+    },
+    RegisterDecoder {
+        name: "PMCR_EL0",
+        long_name: "Performance Monitors Control Register",
+        decode: decode_pmcr_el0,
+    },
+    RegisterDecoder {
+        name: "SCTLR_EL2",
+        long_name: "System Control Register (EL2)",
+        decode: decode_sctlr_el2,
+    },
+    RegisterDecoder {
+        name: "SCTLR_EL3",
+        long_name: "System Control Register (EL3)",
+        decode: decode_sctlr_el3,
+    },
+    RegisterDecoder {
+        name: "SMCR_EL1",
+        long_name: "SME Control Register (EL1)",
+        decode: decode_smcr,
+    },
+    RegisterDecoder {
+        name: "SMCR_EL2",
+        long_name: "SME Control Register (EL2)",
+        decode: decode_smcr,
+    },
+    RegisterDecoder {
+        name: "SMCR_EL3",
+        long_name: "SME Control Register (EL3)",
+        decode: decode_smcr,
+    },
+    RegisterDecoder {
+        name: "TFSRE0_EL1",
+        long_name: "Tag Fault Status Register (EL0)",
+        decode: decode_tfsr,
+    },
+    RegisterDecoder {
+        name: "TFSR_EL1",
+        long_name: "Tag Fault Status Register (EL1)",
+        decode: decode_tfsr,
+    },
+    RegisterDecoder {
+        name: "TFSR_EL2",
+        long_name: "Tag Fault Status Register (EL2)",
+        decode: decode_tfsr,
+    },
+    RegisterDecoder {
+        name: "TFSR_EL3",
+        long_name: "Tag Fault Status Register (EL3)",
+        decode: decode_tfsr,
+    },
+    #[cfg(feature = "profiling")]
+    RegisterDecoder {
+        name: "TRBSR_EL1",
+        long_name: "Trace Buffer Status/syndrome Register",
+        decode: decode_trbsr,
+    },
+    RegisterDecoder {
+        name: "TTBR0_EL1",
+        long_name: "Translation Table Base Register 0 (EL1)",
+        decode: |value| decode_ttbr(value, None),
+    },
+    RegisterDecoder {
+        name: "TTBR0_EL2",
+        long_name: "Translation Table Base Register 0 (EL2)",
+        decode: |value| decode_ttbr(value, None),
+    },
+    RegisterDecoder {
+        name: "TTBR1_EL1",
+        long_name: "Translation Table Base Register 1 (EL1)",
+        decode: |value| decode_ttbr(value, None),
+    },
+    RegisterDecoder {
+        name: "TTBR1_EL2",
+        long_name: "Translation Table Base Register 1 (EL2)",
+        decode: |value| decode_ttbr(value, None),
+    },
+    RegisterDecoder {
+        name: "ZCR_EL1",
+        long_name: "SVE Control Register (EL1)",
+        decode: decode_zcr,
+    },
+    RegisterDecoder {
+        name: "ZCR_EL2",
+        long_name: "SVE Control Register (EL2)",
+        decode: decode_zcr,
+    },
+    RegisterDecoder {
+        name: "ZCR_EL3",
+        long_name: "SVE Control Register (EL3)",
+        decode: decode_zcr,
+    },
+];
+
+/// Returns the decoder for the register with the given name, ignoring case, if there is one.
+pub fn register_decoder(name: &str) -> Option<&'static RegisterDecoder> {
+    REGISTER_DECODERS
+        .iter()
+        .find(|decoder| decoder.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted() {
+        for pair in REGISTER_DECODERS.windows(2) {
+            assert!(
+                pair[0].name < pair[1].name,
+                "{} is out of order",
+                pair[1].name
+            );
+        }
+    }
+
+    #[test]
+    fn lookup() {
+        let decoder = register_decoder("midr_el1").unwrap();
+        assert_eq!(decoder.name, "MIDR_EL1");
+        assert_eq!((decoder.decode)(0x413fd0c1).unwrap()[1].value, 0x41);
+        assert!(register_decoder("X0").is_none());
+    }
+}
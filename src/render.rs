@@ -0,0 +1,151 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rendering of register values as bits, for diagrams and annotated output.
+
+use crate::FieldInfo;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// How to emphasize the bits of a field when rendering a register value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HighlightStyle {
+    /// Surround the bits with square brackets.
+    Brackets,
+    /// Show the bits in bold colour with ANSI escape codes, for terminals.
+    Color,
+}
+
+impl HighlightStyle {
+    fn start(self) -> &'static str {
+        match self {
+            Self::Brackets => "[",
+            Self::Color => "\x1b[1;31m",
+        }
+    }
+
+    fn end(self) -> &'static str {
+        match self {
+            Self::Brackets => "]",
+            Self::Color => "\x1b[0m",
+        }
+    }
+}
+
+impl FromStr for HighlightStyle {
+    type Err = ParseHighlightStyleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "brackets" => Ok(Self::Brackets),
+            "color" | "colour" => Ok(Self::Color),
+            _ => Err(ParseHighlightStyleError(s.to_owned())),
+        }
+    }
+}
+
+/// An error parsing a highlight style.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[error("Unknown highlight style {0:?}")]
+pub struct ParseHighlightStyleError(String);
+
+/// Returns the lowest `width` bits of the given value, most significant first.
+///
+/// This is the common basis of the textual rendering here and the bit diagram of the web page.
+///
+/// Panics if `width > 64`.
+pub fn bits(value: u64, width: usize) -> impl Iterator<Item = bool> {
+    assert!(width <= 64, "Can't render {} bits of a 64-bit value", width);
+    (0..width).rev().map(move |bit| value & (1 << bit) != 0)
+}
+
+/// Renders the lowest `width` bits of the given value in binary, most significant first, in groups
+/// of 8 bits separated by spaces.
+///
+/// If a range of bits is given then those bits are emphasized with the given style.
+///
+/// Panics if `width > 64`.
+pub fn render_bits(
+    value: u64,
+    width: usize,
+    highlight: Option<(usize, usize)>,
+    style: HighlightStyle,
+) -> String {
+    let mut rendered = String::new();
+    for (bit, set) in (0..width).rev().zip(bits(value, width)) {
+        if highlight.is_some_and(|(_, end)| bit + 1 == end) {
+            rendered.push_str(style.start());
+        }
+        rendered.push(if set { '1' } else { '0' });
+        if highlight.is_some_and(|(start, _)| bit == start) {
+            rendered.push_str(style.end());
+        }
+        if bit != 0 && bit % 8 == 0 {
+            rendered.push(' ');
+        }
+    }
+    rendered
+}
+
+/// Renders the given 64-bit register value with the bits of the given field emphasized.
+///
+/// `offset` is the index of the lowest bit of the field's parent within the register, as the
+/// `start` of a subfield is relative to its parent.
+pub fn highlight_field(
+    register: u64,
+    field: &FieldInfo,
+    offset: usize,
+    style: HighlightStyle,
+) -> String {
+    let start = offset + field.start;
+    render_bits(register, 64, Some((start, start + field.width)), style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode;
+
+    #[test]
+    fn render() {
+        assert_eq!(
+            render_bits(0x96, 16, None, HighlightStyle::Brackets),
+            "00000000 10010110"
+        );
+        assert_eq!(
+            render_bits(0x96, 16, Some((4, 10)), HighlightStyle::Brackets),
+            "000000[00 1001]0110"
+        );
+        assert_eq!(
+            render_bits(0x5, 4, Some((0, 1)), HighlightStyle::Color),
+            "010\x1b[1;31m1\x1b[0m"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn render_too_wide() {
+        render_bits(0, 65, None, HighlightStyle::Brackets);
+    }
+
+    #[test]
+    fn highlight_ec() {
+        let decoded = decode(0x96000045).unwrap();
+        assert_eq!(decoded[2].name, "EC");
+        assert_eq!(
+            highlight_field(0x96000045, &decoded[2], 0, HighlightStyle::Brackets),
+            "00000000 00000000 00000000 00000000 [100101]10 00000000 00000000 01000101"
+        );
+    }
+}
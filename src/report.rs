@@ -0,0 +1,110 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Human-readable reports of decoded register values.
+
+use crate::{decode, summarize, DecodeError, FieldInfo};
+use std::fmt::Write;
+
+/// Decodes the given ESR value and returns a human-readable report of it, in the same format as
+/// the command-line app prints by default.
+///
+/// This is a shortcut for [`decode`] and [`summarize`], for callers which only want to show the
+/// result rather than inspect the individual fields.
+pub fn decode_to_string(esr: u64) -> Result<String, DecodeError> {
+    let fields = decode(esr)?;
+    let mut report = format!("ESR {:#034x}:\n# {}\n", esr, summarize(esr));
+    write_fields(&mut report, &fields, 0);
+    Ok(report)
+}
+
+/// Appends a line for each of the given fields and their sub-fields to the report, indented by the
+/// given level.
+fn write_fields(report: &mut String, fields: &[FieldInfo], level: usize) {
+    let indentation = " ".repeat(level * 2);
+    for field in fields {
+        if field.width == 1 {
+            writeln!(report, "{}{:02}     {}", indentation, field.start, field).unwrap();
+        } else {
+            writeln!(
+                report,
+                "{}{:02}..{:02} {}",
+                indentation,
+                field.start,
+                field.start + field.width - 1,
+                field
+            )
+            .unwrap();
+        }
+        if let Some(description) = &field.description {
+            writeln!(report, "{}  # {}", indentation, description).unwrap();
+        }
+        write_fields(report, &field.subfields, level + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_abort() {
+        assert_eq!(
+            decode_to_string(0x96000050).unwrap(),
+            "ESR 0x00000000000000000000000096000050:
+# Data Abort taken without a change in Exception level: Synchronous External abort, not on translation table walk or hardware update of translation table, on write.
+56..63 RES0: 0x00 0b00000000
+32..55 ISS2: 0x000000 0b000000000000000000000000
+  12..23 RES0: 0x000 0b000000000000
+  11     HDBSSF: false
+  10     TnD: false
+  09     TagAccess: false
+  08     GCS: false
+  07     AssuredOnly: false
+  06     Overlay: false
+  05     DirtyBit: false
+  00..04 Xs: 0x00 0b00000
+26..31 EC: 0x25 0b100101
+  # Data Abort taken without a change in Exception level
+25     IL: true
+  # 32-bit instruction trapped
+00..24 ISS: 0x0000050 0b0000000000000000001010000
+  24     ISV: false
+    # No valid instruction syndrome
+  14..23 RES0: 0x000 0b0000000000
+  13     VNCR: false
+    # Fault not from a System register access redirected by VNCR_EL2
+  11..12 SET: 0x0 0b00
+    # Recoverable state (UER)
+  10     FnV: false
+    # FAR is valid
+  09     EA: false
+    # IMPLEMENTATION DEFINED classification of external aborts
+  08     CM: false
+    # Abort not caused by a cache maintenance or address translation instruction
+  07     S1PTW: false
+    # Fault not on a stage 2 translation for a stage 1 translation table walk
+  06     WnR: true
+    # Abort caused by writing to memory
+  00..05 DFSC: 0x10 0b010000
+    # Synchronous External abort, not on translation table walk or hardware update of translation table.
+"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(decode_to_string(0x1).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scanning of text such as logs for ESR values.
+
+mod format;
+
+pub use self::format::{LogFormat, LogFormats};
+use crate::instrument::Stage;
+use std::collections::HashMap;
+
+/// An ESR value found while scanning some text.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScannedValue {
+    /// The line number on which the value was found, starting from 1.
+    pub line: usize,
+    /// The ESR value.
+    pub esr: u64,
+}
+
+/// Scans the given text for ESR values, one per line at most.
+///
+/// The format of the text is detected from the built-in [`LogFormats`], falling back to the
+/// generic format which finds values on any kind of line.
+pub fn scan_esr_values(text: &str) -> Vec<ScannedValue> {
+    let formats = LogFormats::default();
+    scan_esr_values_with(text, formats.detect(text))
+}
+
+/// Scans the given text in the given log format for ESR values, one per line at most.
+pub fn scan_esr_values_with(text: &str, format: &dyn LogFormat) -> Vec<ScannedValue> {
+    let _stage = Stage::enter("scan");
+    debug!(format = format.name(), "Scanning");
+    text.lines()
+        .enumerate()
+        .filter_map(|(index, line)| match format.scan_line(line) {
+            Ok(esr) => {
+                debug!(line = index + 1, esr = %format_args!("{:#x}", esr), "Found ESR value");
+                Some(ScannedValue {
+                    line: index + 1,
+                    esr,
+                })
+            }
+            Err(_reason) => {
+                trace!(line = index + 1, reason = _reason, "Skipped line");
+                None
+            }
+        })
+        .collect()
+}
+
+/// A unique ESR value found one or more times while scanning some text.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupedValue {
+    /// The ESR value.
+    pub esr: u64,
+    /// The line numbers on which the value was found, in order.
+    pub lines: Vec<usize>,
+}
+
+/// Groups identical ESR values together, in order of their first occurrence.
+pub fn group_esr_values(values: &[ScannedValue]) -> Vec<GroupedValue> {
+    let mut groups: Vec<GroupedValue> = Vec::new();
+    let mut indices = HashMap::new();
+    for value in values {
+        let index = *indices.entry(value.esr).or_insert_with(|| {
+            groups.push(GroupedValue {
+                esr: value.esr,
+                lines: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        groups[index].lines.push(value.line);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan() {
+        let log = "[    1.234] Unable to handle kernel paging request\n\
+                   [    1.235] Mem abort info:\n\
+                   [    1.236]   ESR = 0x0000000096000045\n\
+                   0x92000047\n\
+                   1234\n\
+                   \"Synchronous Abort\" handler, esr 0x96000004\n\
+                   ESR_EL2: 5e000000\n\
+                   ESR: nothing here\n";
+        assert_eq!(
+            scan_esr_values(log),
+            vec![
+                ScannedValue {
+                    line: 3,
+                    esr: 0x96000045
+                },
+                ScannedValue {
+                    line: 4,
+                    esr: 0x92000047
+                },
+                ScannedValue {
+                    line: 6,
+                    esr: 0x96000004
+                },
+                ScannedValue {
+                    line: 7,
+                    esr: 0x5e000000
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn group() {
+        let log = "ESR = 0x96000045\n\
+                   ESR = 0x92000047\n\
+                   something else\n\
+                   ESR = 0x96000045\n\
+                   ESR = 0x96000045\n";
+        assert_eq!(
+            group_esr_values(&scan_esr_values(log)),
+            vec![
+                GroupedValue {
+                    esr: 0x96000045,
+                    lines: vec![1, 4, 5],
+                },
+                GroupedValue {
+                    esr: 0x92000047,
+                    lines: vec![2],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,398 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Log formats which ESR values can be scanned from, and detection of which format a log is in.
+
+/// A format of log output which ESR values can be found in, such as kernel or firmware console
+/// output.
+///
+/// Downstream crates can implement this for their own formats, and add them to a [`LogFormats`]
+/// registry to have them detected automatically.
+pub trait LogFormat {
+    /// A short name for the format, e.g. "dmesg".
+    fn name(&self) -> &str;
+
+    /// Returns whether the given line is characteristic of this format.
+    ///
+    /// This is used to detect which format a log is in, so should not match lines which are likely
+    /// to appear in other formats too.
+    fn recognises(&self, line: &str) -> bool;
+
+    /// Returns the ESR value from the given line, or the reason it doesn't contain one.
+    fn scan_line(&self, line: &str) -> Result<u64, &'static str>;
+}
+
+/// A registry of log formats, which can detect which of them some text is in.
+pub struct LogFormats {
+    formats: Vec<Box<dyn LogFormat>>,
+}
+
+impl Default for LogFormats {
+    /// Returns a registry of all the built-in formats.
+    fn default() -> Self {
+        Self {
+            formats: vec![
+                Box::new(Dmesg),
+                Box::new(Qemu),
+                Box::new(Xen),
+                Box::new(Tombstone),
+                Box::new(UBoot),
+            ],
+        }
+    }
+}
+
+impl LogFormats {
+    /// Adds the given format to the registry.
+    ///
+    /// If two formats recognise the same number of lines of some text, the one registered first is
+    /// preferred.
+    pub fn register(&mut self, format: Box<dyn LogFormat>) {
+        self.formats.push(format);
+    }
+
+    /// Returns the format with the given name, if there is one.
+    ///
+    /// "generic" is always available, and matches any line with an ESR value on it.
+    pub fn get(&self, name: &str) -> Option<&dyn LogFormat> {
+        if name == Generic.name() {
+            return Some(&Generic);
+        }
+        self.formats
+            .iter()
+            .map(Box::as_ref)
+            .find(|format| format.name() == name)
+    }
+
+    /// Returns the names of all the formats in the registry, including "generic".
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = vec![Generic.name()];
+        names.extend(self.formats.iter().map(|format| format.name()));
+        names
+    }
+
+    /// Returns the format which recognises the most lines of the given text, or the generic format
+    /// if none of them recognise any.
+    pub fn detect(&self, text: &str) -> &dyn LogFormat {
+        let mut best: (&dyn LogFormat, usize) = (&Generic, 0);
+        for format in &self.formats {
+            let count = text.lines().filter(|line| format.recognises(line)).count();
+            if count > best.1 {
+                best = (format.as_ref(), count);
+            }
+        }
+        best.0
+    }
+}
+
+/// The fallback format, which finds ESR values on any kind of line.
+///
+/// A line may either consist only of a hexadecimal number with a `0x` prefix, or contain the
+/// string "ESR" (in any case, possibly with a suffix like "_EL1") followed by a hexadecimal value,
+/// separated by `=`, `:` or whitespace. The value may or may not have a `0x` prefix in this case.
+/// Bare decimal numbers are ignored, as in logs they are more likely to be counters or timestamps.
+struct Generic;
+
+impl LogFormat for Generic {
+    fn name(&self) -> &str {
+        "generic"
+    }
+
+    fn recognises(&self, _line: &str) -> bool {
+        false
+    }
+
+    fn scan_line(&self, line: &str) -> Result<u64, &'static str> {
+        if let Some(value) = line.trim().strip_prefix("0x") {
+            if let Ok(value) = u64::from_str_radix(value, 16) {
+                return Ok(value);
+            }
+        }
+        labelled_esr(line)
+    }
+}
+
+/// Linux kernel console output, as printed by `dmesg`.
+///
+/// As well as the generic forms, this finds the ESR value in the `Internal error: Oops: ...` line
+/// printed when the kernel dies.
+struct Dmesg;
+
+impl LogFormat for Dmesg {
+    fn name(&self) -> &str {
+        "dmesg"
+    }
+
+    fn recognises(&self, line: &str) -> bool {
+        strip_timestamp(line).is_some()
+    }
+
+    fn scan_line(&self, line: &str) -> Result<u64, &'static str> {
+        let line = strip_timestamp(line).unwrap_or(line);
+        if let Some((_, rest)) = line.split_once("Internal error: ") {
+            // The ESR value is the last thing before the die counter, e.g.
+            // "Internal error: Oops: 0000000096000045 [#1] SMP".
+            let code = rest.split_once(" [#").map_or(rest, |(code, _)| code);
+            let value = code.rsplit(": ").next().unwrap_or(code);
+            return parse_hex(value.trim()).ok_or("no hexadecimal value in internal error");
+        }
+        Generic.scan_line(line)
+    }
+}
+
+/// QEMU's log of exceptions taken, as written with `-d int`.
+///
+/// These have lines like `...with ESR 0x25/0x96000045`, giving the exception class and then the
+/// full syndrome.
+struct Qemu;
+
+impl LogFormat for Qemu {
+    fn name(&self) -> &str {
+        "qemu"
+    }
+
+    fn recognises(&self, line: &str) -> bool {
+        line.starts_with("Taking exception ") || line.starts_with("...with ESR ")
+    }
+
+    fn scan_line(&self, line: &str) -> Result<u64, &'static str> {
+        if let Some(rest) = line.strip_prefix("...with ESR ") {
+            let syndrome = rest.split_once('/').map_or(rest, |(_, syndrome)| syndrome);
+            return parse_hex(syndrome.trim()).ok_or("no hexadecimal syndrome after ESR");
+        }
+        Generic.scan_line(line)
+    }
+}
+
+/// Xen hypervisor console output.
+///
+/// As well as the generic forms, this finds values labelled `HSR`, as Xen calls ESR_EL2.
+struct Xen;
+
+impl LogFormat for Xen {
+    fn name(&self) -> &str {
+        "xen"
+    }
+
+    fn recognises(&self, line: &str) -> bool {
+        line.starts_with("(XEN)")
+    }
+
+    fn scan_line(&self, line: &str) -> Result<u64, &'static str> {
+        let line = line.strip_prefix("(XEN)").unwrap_or(line);
+        if let Some(value) = value_after(line, "HSR") {
+            return Ok(value);
+        }
+        Generic.scan_line(line)
+    }
+}
+
+/// Android tombstones, as written by `debuggerd` when a process crashes.
+///
+/// Tombstones are full of bare hexadecimal values such as addresses, so only values labelled as the
+/// ESR are found.
+struct Tombstone;
+
+impl LogFormat for Tombstone {
+    fn name(&self) -> &str {
+        "tombstone"
+    }
+
+    fn recognises(&self, line: &str) -> bool {
+        line.contains("*** *** *** *** *** *** *** *** *** *** *** *** *** *** *** ***")
+            || line.contains("Build fingerprint: ")
+    }
+
+    fn scan_line(&self, line: &str) -> Result<u64, &'static str> {
+        labelled_esr(line)
+    }
+}
+
+/// U-Boot console output.
+///
+/// U-Boot reports exceptions with lines like `"Synchronous Abort" handler, esr 0x96000004`.
+struct UBoot;
+
+impl LogFormat for UBoot {
+    fn name(&self) -> &str {
+        "u-boot"
+    }
+
+    fn recognises(&self, line: &str) -> bool {
+        line.starts_with("U-Boot ") || line.contains("\" handler, esr ")
+    }
+
+    fn scan_line(&self, line: &str) -> Result<u64, &'static str> {
+        Generic.scan_line(line)
+    }
+}
+
+/// Returns the ESR value from a line containing the string "ESR" followed by a hexadecimal value,
+/// or the reason it doesn't contain one.
+fn labelled_esr(line: &str) -> Result<u64, &'static str> {
+    let upper = line.to_ascii_uppercase();
+    let start = upper
+        .find("ESR")
+        .ok_or("not a number and doesn't mention ESR")?;
+    // Skip any suffix such as "_EL1".
+    let rest = &line[start + 3..];
+    let rest = rest.trim_start_matches(|c: char| c == '_' || c.is_ascii_alphanumeric());
+    hex_prefix(rest).ok_or("no hexadecimal value after ESR")
+}
+
+/// Returns the hexadecimal value following the given label (in any case) on the line, if any.
+fn value_after(line: &str, label: &str) -> Option<u64> {
+    let start = line
+        .to_ascii_uppercase()
+        .find(&label.to_ascii_uppercase())?;
+    hex_prefix(&line[start + label.len()..])
+}
+
+/// Parses the hexadecimal value at the start of the given string, after any separators.
+fn hex_prefix(text: &str) -> Option<u64> {
+    let text = text.trim_start_matches(|c: char| c == '=' || c == ':' || c.is_whitespace());
+    let hex = text.strip_prefix("0x").unwrap_or(text);
+    let digits = hex
+        .find(|c: char| !c.is_ascii_hexdigit())
+        .map_or(hex, |end| &hex[..end]);
+    u64::from_str_radix(digits, 16).ok()
+}
+
+/// Parses a hexadecimal value with an optional `0x` prefix.
+fn parse_hex(text: &str) -> Option<u64> {
+    u64::from_str_radix(text.strip_prefix("0x").unwrap_or(text), 16).ok()
+}
+
+/// Returns the rest of the line after a kernel timestamp like `[    1.234]`, if it has one.
+fn strip_timestamp(line: &str) -> Option<&str> {
+    let (timestamp, rest) = line.trim_start().strip_prefix('[')?.split_once(']')?;
+    let timestamp = timestamp.trim();
+    if !timestamp.is_empty() && timestamp.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        Some(rest.trim_start())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect() {
+        let formats = LogFormats::default();
+        let detect = |text| formats.detect(text).name();
+        assert_eq!(detect("ESR = 0x96000045\n"), "generic");
+        assert_eq!(
+            detect("[    1.234] Mem abort info:\n[    1.235]   ESR = 0x96000045\n"),
+            "dmesg"
+        );
+        assert_eq!(
+            detect("Taking exception 4 [Data Abort] on CPU 0\n...with ESR 0x25/0x96000045\n"),
+            "qemu"
+        );
+        assert_eq!(detect("(XEN) CPU0: Unexpected Trap: Data Abort\n"), "xen");
+        assert_eq!(
+            detect(
+                "*** *** *** *** *** *** *** *** *** *** *** *** *** *** *** ***\n\
+                 Build fingerprint: 'google/foo/bar:12/ABC/123:user/release-keys'\n"
+            ),
+            "tombstone"
+        );
+        assert_eq!(
+            detect("U-Boot 2021.10\n\"Synchronous Abort\" handler, esr 0x96000004\n"),
+            "u-boot"
+        );
+    }
+
+    #[test]
+    fn scan_lines() {
+        assert_eq!(Generic.scan_line("  0x92000047 "), Ok(0x92000047));
+        // Bare decimal numbers are more likely to be counters or PIDs than ESR values.
+        assert!(Generic.scan_line("1234").is_err());
+        assert_eq!(
+            Dmesg.scan_line("[   12.345] Internal error: Oops: 0000000096000045 [#1] PREEMPT SMP"),
+            Ok(0x96000045)
+        );
+        assert_eq!(
+            Dmesg.scan_line("[   12.345] Internal error: Oops - BUG: 00000000f2000800 [#1] SMP"),
+            Ok(0xf2000800)
+        );
+        assert_eq!(Dmesg.scan_line("[    1.234] 0x92000047"), Ok(0x92000047));
+        assert_eq!(
+            Qemu.scan_line("...with ESR 0x25/0x96000045"),
+            Ok(0x96000045)
+        );
+        assert_eq!(
+            Xen.scan_line("(XEN) Unhandled trap, HSR=0x92000047"),
+            Ok(0x92000047)
+        );
+        assert_eq!(
+            Xen.scan_line("(XEN)      ESR_EL2: 0000000093830007"),
+            Ok(0x93830007)
+        );
+        assert_eq!(
+            Tombstone.scan_line("      esr 0000000092000047"),
+            Ok(0x92000047)
+        );
+        assert!(Tombstone.scan_line("    x0  0000007fc8a1b2c0").is_err());
+        assert_eq!(
+            UBoot.scan_line("\"Synchronous Abort\" handler, esr 0x96000004"),
+            Ok(0x96000004)
+        );
+    }
+
+    struct Custom;
+
+    impl LogFormat for Custom {
+        fn name(&self) -> &str {
+            "custom"
+        }
+
+        fn recognises(&self, line: &str) -> bool {
+            line.starts_with("custom:")
+        }
+
+        fn scan_line(&self, line: &str) -> Result<u64, &'static str> {
+            let value = line
+                .strip_prefix("custom: syndrome ")
+                .ok_or("no syndrome")?;
+            parse_hex(value).ok_or("invalid syndrome")
+        }
+    }
+
+    #[test]
+    fn register() {
+        let mut formats = LogFormats::default();
+        assert!(formats.get("custom").is_none());
+        formats.register(Box::new(Custom));
+        assert_eq!(formats.get("custom").unwrap().name(), "custom");
+        assert_eq!(
+            formats.names(),
+            vec![
+                "generic",
+                "dmesg",
+                "qemu",
+                "xen",
+                "tombstone",
+                "u-boot",
+                "custom"
+            ]
+        );
+        assert_eq!(
+            formats.detect("custom: syndrome 0x96000045\n").name(),
+            "custom"
+        );
+    }
+}
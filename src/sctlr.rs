@@ -0,0 +1,322 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{DecodeError, FieldInfo};
+
+/// Decodes the given System Control Register (EL2) value, or returns an error if it is not valid.
+///
+/// This assumes that HCR_EL2.E2H is 0. When it is 1, SCTLR_EL2 instead has the same layout as
+/// SCTLR_EL1, which this doesn't decode.
+pub fn decode_sctlr_el2(sctlr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get_bit(sctlr, "RES0", Some("Reserved"), 63).check_res0()?;
+    let spintmask = FieldInfo::get_bit(sctlr, "SPINTMASK", Some("SP Interrupt Mask enable"), 62)
+        .describe_bit(describe_spintmask);
+    let nmi = FieldInfo::get_bit(sctlr, "NMI", Some("Non-maskable Interrupt enable"), 61)
+        .describe_bit(describe_nmi);
+    let res0b = FieldInfo::get(sctlr, "RES0", Some("Reserved"), 54, 61).check_res0()?;
+
+    let mut fields = vec![res0, spintmask, nmi, res0b];
+    fields.extend(decode_common(sctlr)?);
+    Ok(fields)
+}
+
+/// Decodes the given System Control Register (EL3) value, or returns an error if it is not valid.
+pub fn decode_sctlr_el3(sctlr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(sctlr, "RES0", Some("Reserved"), 54, 64).check_res0()?;
+
+    let mut fields = vec![res0];
+    fields.extend(decode_common(sctlr)?);
+    Ok(fields)
+}
+
+/// Decodes bits 0 to 53, which have the same layout in SCTLR_EL2 (with HCR_EL2.E2H 0) and
+/// SCTLR_EL3.
+fn decode_common(sctlr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let tme = FieldInfo::get_bit(sctlr, "TME", Some("Transactional Memory enable"), 53)
+        .describe_bit(describe_tme);
+    let res0a = FieldInfo::get_bit(sctlr, "RES0", Some("Reserved"), 52).check_res0()?;
+    let tmt = FieldInfo::get_bit(
+        sctlr,
+        "TMT",
+        Some("Trivial implementation of Transactional Memory"),
+        51,
+    )
+    .describe_bit(describe_tmt);
+    let res0b = FieldInfo::get(sctlr, "RES0", Some("Reserved"), 45, 51).check_res0()?;
+    let dssbs = FieldInfo::get_bit(
+        sctlr,
+        "DSSBS",
+        Some("Default PSTATE.SSBS value on exception entry"),
+        44,
+    );
+    let ata = FieldInfo::get_bit(sctlr, "ATA", Some("Allocation Tag Access"), 43)
+        .describe_bit(describe_ata);
+    let res0c = FieldInfo::get_bit(sctlr, "RES0", Some("Reserved"), 42).check_res0()?;
+    let tcf =
+        FieldInfo::get(sctlr, "TCF", Some("Tag Check Fault"), 40, 42).describe(describe_tcf)?;
+    let res0d = FieldInfo::get(sctlr, "RES0", Some("Reserved"), 38, 40).check_res0()?;
+    let itfsb = FieldInfo::get_bit(
+        sctlr,
+        "ITFSB",
+        Some("Tag Check Faults synchronized on exception entry"),
+        37,
+    );
+    let bt = FieldInfo::get_bit(sctlr, "BT", Some("PAC Branch Type compatibility"), 36)
+        .describe_bit(describe_bt);
+    let res0e = FieldInfo::get(sctlr, "RES0", Some("Reserved"), 32, 36).check_res0()?;
+    let enia = FieldInfo::get_bit(
+        sctlr,
+        "EnIA",
+        Some("Enable APIAKey pointer authentication"),
+        31,
+    );
+    let enib = FieldInfo::get_bit(
+        sctlr,
+        "EnIB",
+        Some("Enable APIBKey pointer authentication"),
+        30,
+    );
+    let res1a = FieldInfo::get(sctlr, "RES1", Some("Reserved"), 28, 30);
+    let enda = FieldInfo::get_bit(
+        sctlr,
+        "EnDA",
+        Some("Enable APDAKey pointer authentication"),
+        27,
+    );
+    let res0f = FieldInfo::get_bit(sctlr, "RES0", Some("Reserved"), 26).check_res0()?;
+    let ee = FieldInfo::get_bit(sctlr, "EE", Some("Endianness of data accesses"), 25)
+        .describe_bit(describe_ee);
+    let res0g = FieldInfo::get_bit(sctlr, "RES0", Some("Reserved"), 24).check_res0()?;
+    let res1b = FieldInfo::get_bit(sctlr, "RES1", Some("Reserved"), 23);
+    let eis = FieldInfo::get_bit(
+        sctlr,
+        "EIS",
+        Some("Exception entry is context synchronizing"),
+        22,
+    );
+    let iesb = FieldInfo::get_bit(
+        sctlr,
+        "IESB",
+        Some("Implicit Error Synchronization event enable"),
+        21,
+    );
+    let res0h = FieldInfo::get_bit(sctlr, "RES0", Some("Reserved"), 20).check_res0()?;
+    let wxn = FieldInfo::get_bit(sctlr, "WXN", Some("Write permission implies XN"), 19)
+        .describe_bit(describe_wxn);
+    let res1c = FieldInfo::get_bit(sctlr, "RES1", Some("Reserved"), 18);
+    let res0i = FieldInfo::get_bit(sctlr, "RES0", Some("Reserved"), 17).check_res0()?;
+    let res1d = FieldInfo::get_bit(sctlr, "RES1", Some("Reserved"), 16);
+    let res0j = FieldInfo::get(sctlr, "RES0", Some("Reserved"), 14, 16).check_res0()?;
+    let endb = FieldInfo::get_bit(
+        sctlr,
+        "EnDB",
+        Some("Enable APDBKey pointer authentication"),
+        13,
+    );
+    let i = FieldInfo::get_bit(
+        sctlr,
+        "I",
+        Some("Instruction access Cacheability control"),
+        12,
+    )
+    .describe_bit(describe_i);
+    let eos = FieldInfo::get_bit(
+        sctlr,
+        "EOS",
+        Some("Exception exit is context synchronizing"),
+        11,
+    );
+    let res0k = FieldInfo::get(sctlr, "RES0", Some("Reserved"), 7, 11).check_res0()?;
+    let naa =
+        FieldInfo::get_bit(sctlr, "nAA", Some("Non-aligned access"), 6).describe_bit(describe_naa);
+    let res1e = FieldInfo::get(sctlr, "RES1", Some("Reserved"), 4, 6);
+    let sa = FieldInfo::get_bit(sctlr, "SA", Some("SP alignment check enable"), 3);
+    let c = FieldInfo::get_bit(
+        sctlr,
+        "C",
+        Some("Cacheability control for data accesses"),
+        2,
+    )
+    .describe_bit(describe_c);
+    let a = FieldInfo::get_bit(sctlr, "A", Some("Alignment check enable"), 1);
+    let m = FieldInfo::get_bit(sctlr, "M", Some("MMU enable"), 0).describe_bit(describe_m);
+
+    Ok(vec![
+        tme, res0a, tmt, res0b, dssbs, ata, res0c, tcf, res0d, itfsb, bt, res0e, enia, enib, res1a,
+        enda, res0f, ee, res0g, res1b, eis, iesb, res0h, wxn, res1c, res0i, res1d, res0j, endb, i,
+        eos, res0k, naa, res1e, sa, c, a, m,
+    ])
+}
+
+fn describe_spintmask(spintmask: bool) -> &'static str {
+    if spintmask {
+        "PSTATE.ALLINT is set on taking an exception to EL2 using SP_EL2"
+    } else {
+        "PSTATE.ALLINT isn't affected by the stack pointer in use"
+    }
+}
+
+fn describe_nmi(nmi: bool) -> &'static str {
+    if nmi {
+        "Superpriority is enabled, PSTATE.ALLINT masks interrupts"
+    } else {
+        "Superpriority is disabled"
+    }
+}
+
+fn describe_tme(tme: bool) -> &'static str {
+    if tme {
+        "TSTART is not trapped"
+    } else {
+        "TSTART is UNDEFINED"
+    }
+}
+
+fn describe_tmt(tmt: bool) -> &'static str {
+    if tmt {
+        "TSTART always fails"
+    } else {
+        "TSTART behaves as defined"
+    }
+}
+
+fn describe_ata(ata: bool) -> &'static str {
+    if ata {
+        "Access to Allocation Tags is enabled"
+    } else {
+        "Access to Allocation Tags is disabled"
+    }
+}
+
+fn describe_tcf(tcf: u64) -> Result<&'static str, DecodeError> {
+    Ok(match tcf {
+        0b00 => "Tag Check Faults have no effect",
+        0b01 => "Tag Check Faults cause a synchronous exception",
+        0b10 => "Tag Check Faults are asynchronously accumulated",
+        0b11 => {
+            "Tag Check Faults cause a synchronous exception on reads and are accumulated on writes"
+        }
+        _ => unreachable!(),
+    })
+}
+
+fn describe_bt(bt: bool) -> &'static str {
+    if bt {
+        "PACIASP and PACIBSP are not compatible with PSTATE.BTYPE 0b11"
+    } else {
+        "PACIASP and PACIBSP are compatible with PSTATE.BTYPE 0b11"
+    }
+}
+
+fn describe_ee(ee: bool) -> &'static str {
+    if ee {
+        "Big-endian"
+    } else {
+        "Little-endian"
+    }
+}
+
+fn describe_wxn(wxn: bool) -> &'static str {
+    if wxn {
+        "Writeable memory regions are never executable"
+    } else {
+        "Writeable memory regions may be executable"
+    }
+}
+
+fn describe_i(i: bool) -> &'static str {
+    if i {
+        "Instruction accesses are Cacheable where the translation tables allow"
+    } else {
+        "Instruction accesses are Non-cacheable"
+    }
+}
+
+fn describe_naa(naa: bool) -> &'static str {
+    if naa {
+        "Unaligned LDAPR, LDAR, LDLAR, STLR and STLLR accesses don't generate an alignment fault"
+    } else {
+        "Unaligned LDAPR, LDAR, LDLAR, STLR and STLLR accesses generate an alignment fault"
+    }
+}
+
+fn describe_c(c: bool) -> &'static str {
+    if c {
+        "Data accesses are Cacheable where the translation tables allow"
+    } else {
+        "Data accesses are Non-cacheable"
+    }
+}
+
+fn describe_m(m: bool) -> &'static str {
+    if m {
+        "Stage 1 address translation enabled"
+    } else {
+        "Stage 1 address translation disabled"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that the given fields cover all 64 bits without overlapping.
+    fn assert_tiled(fields: &[FieldInfo]) {
+        assert_eq!(fields.iter().map(|field| field.width).sum::<usize>(), 64);
+        assert_eq!(
+            fields.iter().fold(0u128, |used, field| used
+                | ((1u128 << field.width) - 1) << field.start),
+            u64::MAX as u128
+        );
+    }
+
+    #[test]
+    fn el2() {
+        let decoded = decode_sctlr_el2(0x30c51835).unwrap();
+        assert_tiled(&decoded);
+        let m = decoded.iter().find(|field| field.name == "M").unwrap();
+        assert_eq!(m.value, 1);
+        assert_eq!(
+            m.description,
+            Some("Stage 1 address translation enabled".to_string())
+        );
+        let nmi = decoded.iter().find(|field| field.name == "NMI").unwrap();
+        assert_eq!(nmi.value, 0);
+        assert!(matches!(
+            decode_sctlr_el2(0x8000_0000_30c5_0830),
+            Err(DecodeError::InvalidRes0 { .. })
+        ));
+    }
+
+    #[test]
+    fn el3() {
+        let decoded = decode_sctlr_el3(0x0000_0300_30c5_0830).unwrap();
+        assert_tiled(&decoded);
+        let tcf = decoded.iter().find(|field| field.name == "TCF").unwrap();
+        assert_eq!(
+            tcf.description,
+            Some(
+                "Tag Check Faults cause a synchronous exception on reads and are accumulated on \
+                 writes"
+                    .to_string()
+            )
+        );
+        assert!(decoded.iter().all(|field| field.name != "NMI"));
+        // NMI is RES0 in SCTLR_EL3.
+        assert!(matches!(
+            decode_sctlr_el3(0x2000_0000_30c5_0830),
+            Err(DecodeError::InvalidRes0 { .. })
+        ));
+    }
+}
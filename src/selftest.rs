@@ -0,0 +1,159 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A self-test of the decoder, which can be run on an installed build to check that it behaves as
+//! expected.
+
+use crate::{check_coverage, check_round_trip, parse_number, summarize};
+use std::fmt::{self, Display, Formatter};
+
+/// Golden summaries of ESR values, one per line, which should only change when decoding is
+/// deliberately improved.
+const GOLDEN_SUMMARIES: &str = include_str!("../testdata/golden-summaries.txt");
+
+/// Number of pseudo-random ISS values to check for each exception class.
+const SAMPLES_PER_CLASS: usize = 50;
+
+/// The results of running [`self_test`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SelfTestReport {
+    /// The results of each suite of checks.
+    pub suites: Vec<SuiteResult>,
+}
+
+impl SelfTestReport {
+    /// Returns whether all the checks passed.
+    pub fn passed(&self) -> bool {
+        self.suites.iter().all(|suite| suite.failures.is_empty())
+    }
+}
+
+impl Display for SelfTestReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for suite in &self.suites {
+            writeln!(
+                f,
+                "{}: {} checked, {} failed",
+                suite.name,
+                suite.checked,
+                suite.failures.len()
+            )?;
+            for failure in &suite.failures {
+                writeln!(f, "  {}", failure)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The results of one suite of checks in the self-test.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SuiteResult {
+    /// The name of the suite, e.g. "golden vectors".
+    pub name: &'static str,
+    /// The number of values which were checked.
+    pub checked: usize,
+    /// A description of each check which failed.
+    pub failures: Vec<String>,
+}
+
+/// Runs the decoder against its embedded corpus of golden vectors, and checks that decoding covers
+/// every bit and round-trips for a sample of values of every exception class.
+pub fn self_test() -> SelfTestReport {
+    let golden = golden_summaries()
+        .filter_map(|(esr, expected)| {
+            let summary = summarize(esr);
+            if summary == expected {
+                None
+            } else {
+                Some(format!(
+                    "ESR {:#x}: expected {:?} but got {:?}",
+                    esr, expected, summary
+                ))
+            }
+        })
+        .collect();
+    let samples = sample_values();
+    SelfTestReport {
+        suites: vec![
+            SuiteResult {
+                name: "golden vectors",
+                checked: golden_summaries().count(),
+                failures: golden,
+            },
+            SuiteResult {
+                name: "bit coverage",
+                checked: samples.len(),
+                failures: check_all(&samples, check_coverage),
+            },
+            SuiteResult {
+                name: "round trip",
+                checked: samples.len(),
+                failures: check_all(&samples, check_round_trip),
+            },
+        ],
+    }
+}
+
+/// Returns the ESR value and expected summary of each golden vector.
+pub(crate) fn golden_summaries() -> impl Iterator<Item = (u64, &'static str)> {
+    GOLDEN_SUMMARIES
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (esr, summary) = line.split_once(' ').expect("Invalid golden vector");
+            (parse_number(esr).expect("Invalid golden vector"), summary)
+        })
+}
+
+/// Runs the given check on each of the given values, and returns a description of each failure.
+fn check_all<E: Display>(values: &[u64], check: fn(u64) -> Result<(), E>) -> Vec<String> {
+    values
+        .iter()
+        .filter_map(|&esr| check(esr).err().map(|e| format!("ESR {:#x}: {}", esr, e)))
+        .collect()
+}
+
+/// Returns the golden vectors along with a deterministic sample of values for every exception
+/// class, with and without ISS2 and IL set.
+fn sample_values() -> Vec<u64> {
+    let mut state = 0x2545f4914f6cdd1du64;
+    let mut values: Vec<u64> = golden_summaries().map(|(esr, _)| esr).collect();
+    for ec in 0..0b111111u64 {
+        for iss2 in [0, 0b11111u64] {
+            for il in 0..2u64 {
+                for _ in 0..SAMPLES_PER_CLASS {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    let iss = state & 0x1ff_ffff;
+                    values.push(iss2 << 32 | ec << 26 | il << 25 | iss);
+                }
+            }
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes() {
+        let report = self_test();
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.suites[0].checked, 11);
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The revisions of Arm documents which the decoder tables were written from.
+
+/// The title of the Arm ARM, which most of the tables are written from.
+const ARM_ARM: &str = "Arm Architecture Reference Manual for A-profile architecture";
+
+/// A revision of an Arm document which one of the decoder's tables reflects.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SpecReference {
+    /// The register or encoding which the table decodes, e.g. "ESR".
+    pub table: &'static str,
+    /// The title of the document.
+    pub title: &'static str,
+    /// The document number and revision, e.g. "DDI 0487 K.a".
+    pub revision: &'static str,
+}
+
+/// The documents which each table of the decoder was written from.
+///
+/// Where the meaning of a field changed between versions of the architecture, the table reflects
+/// all of them, and the version to decode according to can be chosen with
+/// [`DecodeOptions::arch_version`](crate::DecodeOptions::arch_version).
+pub const SPEC_REFERENCES: &[SpecReference] = &[
+    SpecReference {
+        table: "CCSIDR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "CLIDR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "CPACR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "CPTR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "CTR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "DCZID",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "DFSR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "ESR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "GCR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "ID_AA64ISAR0",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "ID_AA64ISAR1",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "ID_AA64ISAR2",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "ID_AA64MMFR0",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "ID_AA64MMFR1",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "ID_AA64MMFR2",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "ID_AA64MMFR3",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "IFSR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "MIDR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "PAR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "PMCCFILTR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "PMCR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "SCTLR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "SMCR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "TFSR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "TRBSR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "TTBR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "ZCR",
+        title: ARM_ARM,
+        revision: "DDI 0487 K.a",
+    },
+    SpecReference {
+        table: "SMCCC",
+        title: "SMC Calling Convention",
+        revision: "DEN 0028 E v1.4",
+    },
+];
+
+/// Returns the document which the table for the given register or encoding was written from, if
+/// there is such a table.
+pub fn spec_reference(table: &str) -> Option<&'static SpecReference> {
+    SPEC_REFERENCES
+        .iter()
+        .find(|reference| reference.table.eq_ignore_ascii_case(table))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        assert_eq!(spec_reference("ESR").unwrap().revision, "DDI 0487 K.a");
+        assert_eq!(spec_reference("smccc").unwrap().revision, "DEN 0028 E v1.4");
+        assert_eq!(spec_reference("FAR"), None);
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics about a collection of ESR values.
+
+use crate::esr::far_valid;
+use crate::{decode, FieldInfo};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+/// Counts of various properties of a collection of ESR values.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    /// The total number of values added.
+    pub total: usize,
+    /// The number of values which couldn't be decoded.
+    pub invalid: usize,
+    /// The number of values for each exception class, with its description.
+    pub by_ec: BTreeMap<u64, (String, usize)>,
+    /// The number of data or instruction aborts for each fault status code, with its description.
+    pub by_fsc: BTreeMap<u64, (String, usize)>,
+    /// The number of data aborts caused by writes.
+    pub writes: usize,
+    /// The number of data aborts caused by reads.
+    pub reads: usize,
+    /// The number of aborts for which the FAR is valid.
+    pub far_valid: usize,
+    /// The number of aborts for which the FAR is not valid.
+    pub far_not_valid: usize,
+}
+
+impl Stats {
+    /// Decodes the given ESR value and adds it to the statistics.
+    pub fn add(&mut self, esr: u64) {
+        self.total += 1;
+        let fields = match decode(esr) {
+            Ok(fields) => fields,
+            Err(_) => {
+                self.invalid += 1;
+                return;
+            }
+        };
+        let ec = match FieldInfo::find(&fields, "EC") {
+            Some(ec) => ec,
+            None => return,
+        };
+        count(&mut self.by_ec, ec);
+        // Other exception classes such as watchpoints and SErrors have fields with the same names,
+        // but only instruction and data aborts should be counted as aborts.
+        if !matches!(ec.value, 0x20 | 0x21 | 0x24 | 0x25) {
+            return;
+        }
+        let iss = match FieldInfo::find(&fields, "ISS") {
+            Some(iss) => &iss.subfields,
+            None => return,
+        };
+        if let Some(fsc) = FieldInfo::find(iss, "DFSC").or_else(|| FieldInfo::find(iss, "IFSC")) {
+            count(&mut self.by_fsc, fsc);
+        }
+        if let Some(wnr) = FieldInfo::find(iss, "WnR") {
+            if wnr.as_bit() {
+                self.writes += 1;
+            } else {
+                self.reads += 1;
+            }
+        }
+        match far_valid(&fields) {
+            Some(true) => self.far_valid += 1,
+            Some(false) => self.far_not_valid += 1,
+            None => {}
+        }
+    }
+}
+
+impl Extend<u64> for Stats {
+    fn extend<T: IntoIterator<Item = u64>>(&mut self, iter: T) {
+        for esr in iter {
+            self.add(esr);
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "{} values, {} invalid", self.total, self.invalid)?;
+        writeln!(f, "By exception class:")?;
+        for (ec, (description, count)) in &self.by_ec {
+            writeln!(f, "  {:6} EC {:#04x}: {}", count, ec, description)?;
+        }
+        if !self.by_fsc.is_empty() {
+            writeln!(f, "By fault status code:")?;
+            for (fsc, (description, count)) in &self.by_fsc {
+                writeln!(f, "  {:6} FSC {:#04x}: {}", count, fsc, description)?;
+            }
+        }
+        if self.reads + self.writes > 0 {
+            writeln!(
+                f,
+                "Data aborts: {} reads, {} writes",
+                self.reads, self.writes
+            )?;
+        }
+        if self.far_valid + self.far_not_valid > 0 {
+            writeln!(
+                f,
+                "FAR valid for {} of {} aborts ({:.1}%)",
+                self.far_valid,
+                self.far_valid + self.far_not_valid,
+                100.0 * self.far_valid as f64 / (self.far_valid + self.far_not_valid) as f64
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn count(counts: &mut BTreeMap<u64, (String, usize)>, field: &FieldInfo) {
+    counts
+        .entry(field.value)
+        .or_insert_with(|| (field.description.clone().unwrap_or_default(), 0))
+        .1 += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let mut stats = Stats::default();
+        stats.extend(vec![
+            0x96000045, 0x92000007, 0x96000445, 0x56000000, 0xffffffff,
+        ]);
+        assert_eq!(stats.total, 5);
+        assert_eq!(stats.invalid, 1);
+        assert_eq!(stats.by_ec.len(), 3);
+        assert_eq!(stats.by_ec[&0x25].1, 2);
+        assert_eq!(stats.by_fsc[&0b000101].1, 2);
+        assert_eq!(stats.writes, 2);
+        assert_eq!(stats.reads, 1);
+        assert_eq!(stats.far_valid, 2);
+        assert_eq!(stats.far_not_valid, 1);
+        // A watchpoint has a DFSC and WnR, but isn't an abort.
+        let mut watchpoint_stats = stats.clone();
+        watchpoint_stats.add(0xd6000062);
+        assert_eq!(watchpoint_stats.by_ec[&0x35].1, 1);
+        assert_eq!(watchpoint_stats.by_fsc, stats.by_fsc);
+        assert_eq!(watchpoint_stats.writes, stats.writes);
+        assert_eq!(watchpoint_stats.far_valid, stats.far_valid);
+        assert_eq!(
+            stats.to_string(),
+            "5 values, 1 invalid\n\
+             By exception class:\n\
+            \x20      1 EC 0x15: SVC instruction execution in AArch64 state\n\
+            \x20      1 EC 0x24: Data Abort from a lower Exception level\n\
+            \x20      2 EC 0x25: Data Abort taken without a change in Exception level\n\
+             By fault status code:\n\
+            \x20      2 FSC 0x05: Translation fault, level 1.\n\
+            \x20      1 FSC 0x07: Translation fault, level 3.\n\
+             Data aborts: 1 reads, 2 writes\n\
+             FAR valid for 2 of 3 aborts (66.7%)\n"
+        );
+    }
+}
@@ -0,0 +1,327 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Importing system register definitions from Arm's machine-readable SysReg XML.
+
+use std::fmt::Write;
+use thiserror::Error;
+
+/// An error importing system register definitions from XML.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ImportError {
+    /// The XML couldn't be parsed.
+    #[error("Invalid XML: {0}")]
+    InvalidXml(String),
+    /// A field's bit positions couldn't be parsed.
+    #[error("Invalid bit position {value:?} for field {field} of {register}")]
+    InvalidBit {
+        register: String,
+        field: String,
+        value: String,
+    },
+}
+
+/// A system register definition imported from Arm's SysReg XML.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SysRegDefinition {
+    /// The short name of the register, e.g. "ESR_EL1".
+    pub name: String,
+    /// The long name of the register, e.g. "Exception Syndrome Register (EL1)".
+    pub long_name: Option<String>,
+    /// The encoding used to access the register with MRS or MSR, if it has a fixed one.
+    pub encoding: Option<SysRegEncoding>,
+    /// The fields of the register, from the most significant down.
+    pub fields: Vec<FieldDefinition>,
+}
+
+/// The encoding of an MRS or MSR instruction which accesses a system register.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SysRegEncoding {
+    pub op0: u64,
+    pub op1: u64,
+    pub crn: u64,
+    pub crm: u64,
+    pub op2: u64,
+}
+
+/// A field of a system register imported from Arm's SysReg XML.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldDefinition {
+    /// The name of the field, e.g. "EC", or "RES0" for reserved fields.
+    pub name: String,
+    /// The index of the lowest bit of the field.
+    pub start: usize,
+    /// The number of bits in the field.
+    pub width: usize,
+}
+
+/// Parses the AArch64 register definitions from one of the register files of Arm's SysReg XML,
+/// e.g. `AArch64-esr_el1.xml`.
+///
+/// Only the first fieldset of each register is used, as that is the layout which applies in the
+/// common case. Encodings with variable parts, such as those of indexed registers, are ignored.
+pub fn parse_sysreg_xml(xml: &str) -> Result<Vec<SysRegDefinition>, ImportError> {
+    let document =
+        roxmltree::Document::parse(xml).map_err(|e| ImportError::InvalidXml(e.to_string()))?;
+    document
+        .descendants()
+        .filter(|node| {
+            node.has_tag_name("register")
+                && node.attribute("execution_state") == Some("AArch64")
+                && node.attribute("is_register") != Some("False")
+        })
+        .map(|register| {
+            let name = child_text(register, "reg_short_name").unwrap_or_default();
+            let long_name = child_text(register, "reg_long_name");
+            let encoding = register
+                .descendants()
+                .filter(|node| node.has_tag_name("encoding"))
+                .find_map(parse_encoding);
+            let fields = match register
+                .descendants()
+                .find(|node| node.has_tag_name("fields"))
+            {
+                Some(fieldset) => fieldset
+                    .children()
+                    .filter(|node| node.has_tag_name("field"))
+                    .map(|field| parse_field(&name, field))
+                    .collect::<Result<_, _>>()?,
+                None => vec![],
+            };
+            Ok(SysRegDefinition {
+                name,
+                long_name,
+                encoding,
+                fields,
+            })
+        })
+        .collect()
+}
+
+/// Returns the trimmed text of the first child element of the given node with the given tag name.
+fn child_text(node: roxmltree::Node, tag: &str) -> Option<String> {
+    node.children()
+        .find(|child| child.has_tag_name(tag))
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_owned())
+}
+
+/// Parses an `<encoding>` element, if it has fixed values for all of op0, op1, CRn, CRm and op2.
+fn parse_encoding(encoding: roxmltree::Node) -> Option<SysRegEncoding> {
+    let get = |name: &str| {
+        let value = encoding
+            .children()
+            .find(|node| node.has_tag_name("enc") && node.attribute("n") == Some(name))?
+            .attribute("v")?;
+        u64::from_str_radix(value.strip_prefix("0b")?, 2).ok()
+    };
+    Some(SysRegEncoding {
+        op0: get("op0")?,
+        op1: get("op1")?,
+        crn: get("CRn")?,
+        crm: get("CRm")?,
+        op2: get("op2")?,
+    })
+}
+
+/// Parses a `<field>` element of the given register.
+fn parse_field(register: &str, field: roxmltree::Node) -> Result<FieldDefinition, ImportError> {
+    let name = child_text(field, "field_name")
+        .or_else(|| field.attribute("rwtype").map(ToOwned::to_owned))
+        .unwrap_or_else(|| "RES0".to_owned());
+    let bit = |tag: &str| {
+        let value = child_text(field, tag).unwrap_or_default();
+        value.parse::<usize>().map_err(|_| ImportError::InvalidBit {
+            register: register.to_owned(),
+            field: name.clone(),
+            value,
+        })
+    };
+    let msb = bit("field_msb")?;
+    let lsb = bit("field_lsb")?;
+    Ok(FieldDefinition {
+        name: name.clone(),
+        start: lsb,
+        width: msb + 1 - lsb,
+    })
+}
+
+/// Generates match arms for the system register name table used to decode trapped MSR and MRS
+/// instructions, for all the given registers which have fixed encodings.
+pub fn sysreg_name_arms(registers: &[SysRegDefinition]) -> String {
+    let mut arms = String::new();
+    for register in registers {
+        if let Some(e) = &register.encoding {
+            writeln!(
+                arms,
+                "({}, {}, {}, {}, {}) => \"{}\",",
+                e.op0, e.crn, e.op1, e.crm, e.op2, register.name
+            )
+            .unwrap();
+        }
+    }
+    arms
+}
+
+/// Generates the statements to decode the fields of the given register with `FieldInfo::get`,
+/// from a variable called `register`.
+pub fn field_decoder(register: &SysRegDefinition) -> String {
+    let mut code = String::new();
+    writeln!(code, "// {}", register.name).unwrap();
+    for field in &register.fields {
+        let end = field.start + field.width;
+        if field.width == 1 {
+            writeln!(
+                code,
+                "let {} = FieldInfo::get_bit(register, \"{}\", None, {});",
+                field.name.to_ascii_lowercase(),
+                field.name,
+                field.start
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                code,
+                "let {} = FieldInfo::get(register, \"{}\", None, {}, {});",
+                field.name.to_ascii_lowercase(),
+                field.name,
+                field.start,
+                end
+            )
+            .unwrap();
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<register_page>
+  <registers>
+    <register execution_state="AArch64" is_register="True" is_internal="True">
+      <reg_short_name>ESR_EL1</reg_short_name>
+      <reg_long_name>Exception Syndrome Register (EL1)</reg_long_name>
+      <reg_fieldsets>
+        <fields length="64">
+          <field id="0_63_37" is_variable_length="False" rwtype="RES0">
+            <field_msb>63</field_msb>
+            <field_lsb>37</field_lsb>
+          </field>
+          <field id="ISS2_36_32">
+            <field_name>ISS2</field_name>
+            <field_msb>36</field_msb>
+            <field_lsb>32</field_lsb>
+          </field>
+          <field id="EC_31_26">
+            <field_name>EC</field_name>
+            <field_msb>31</field_msb>
+            <field_lsb>26</field_lsb>
+          </field>
+          <field id="IL_25_25">
+            <field_name>IL</field_name>
+            <field_msb>25</field_msb>
+            <field_lsb>25</field_lsb>
+          </field>
+          <field id="ISS_24_0">
+            <field_name>ISS</field_name>
+            <field_msb>24</field_msb>
+            <field_lsb>0</field_lsb>
+          </field>
+        </fields>
+      </reg_fieldsets>
+      <access_mechanisms>
+        <access_mechanism accessor="MRS ESR_EL1" type="SystemAccessor">
+          <encoding>
+            <access_instruction>MRS &lt;Xt&gt;, ESR_EL1</access_instruction>
+            <enc n="op0" v="0b11"/>
+            <enc n="op1" v="0b000"/>
+            <enc n="CRn" v="0b0101"/>
+            <enc n="CRm" v="0b0010"/>
+            <enc n="op2" v="0b000"/>
+          </encoding>
+        </access_mechanism>
+      </access_mechanisms>
+    </register>
+    <register execution_state="AArch32" is_register="True">
+      <reg_short_name>DFSR</reg_short_name>
+    </register>
+  </registers>
+</register_page>
+"#;
+
+    #[test]
+    fn parse() {
+        let registers = parse_sysreg_xml(XML).unwrap();
+        assert_eq!(registers.len(), 1);
+        let esr = &registers[0];
+        assert_eq!(esr.name, "ESR_EL1");
+        assert_eq!(
+            esr.long_name.as_deref(),
+            Some("Exception Syndrome Register (EL1)")
+        );
+        assert_eq!(
+            esr.encoding,
+            Some(SysRegEncoding {
+                op0: 3,
+                op1: 0,
+                crn: 5,
+                crm: 2,
+                op2: 0
+            })
+        );
+        assert_eq!(esr.fields.len(), 5);
+        assert_eq!(
+            esr.fields[0],
+            FieldDefinition {
+                name: "RES0".to_string(),
+                start: 37,
+                width: 27,
+            }
+        );
+        assert_eq!(
+            esr.fields[3],
+            FieldDefinition {
+                name: "IL".to_string(),
+                start: 25,
+                width: 1,
+            }
+        );
+
+        assert!(matches!(
+            parse_sysreg_xml("<register"),
+            Err(ImportError::InvalidXml(_))
+        ));
+    }
+
+    #[test]
+    fn generate() {
+        let registers = parse_sysreg_xml(XML).unwrap();
+        assert_eq!(
+            sysreg_name_arms(&registers),
+            "(3, 5, 0, 2, 0) => \"ESR_EL1\",\n"
+        );
+        assert_eq!(
+            field_decoder(&registers[0]),
+            "// ESR_EL1\n\
+             let res0 = FieldInfo::get(register, \"RES0\", None, 37, 64);\n\
+             let iss2 = FieldInfo::get(register, \"ISS2\", None, 32, 37);\n\
+             let ec = FieldInfo::get(register, \"EC\", None, 26, 32);\n\
+             let il = FieldInfo::get_bit(register, \"IL\", None, 25);\n\
+             let iss = FieldInfo::get(register, \"ISS\", None, 0, 25);\n"
+        );
+    }
+}
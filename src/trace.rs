@@ -0,0 +1,230 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Consistency checking of exception traces from architecture models.
+
+use crate::esr::far_valid;
+use crate::{decode, parse_number, FieldInfo};
+use thiserror::Error;
+
+/// An error parsing an exception trace.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum TraceError {
+    /// The trace didn't have a header line.
+    #[error("Trace is empty")]
+    Empty,
+    /// The header line didn't include an ESR column.
+    #[error("Trace header has no ESR column")]
+    MissingEsrColumn,
+    /// A value in the trace couldn't be parsed.
+    #[error("Invalid {column} value {value:?} on line {line}")]
+    InvalidValue {
+        line: usize,
+        column: &'static str,
+        value: String,
+    },
+}
+
+/// A single exception event from a trace.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TraceEvent {
+    /// The line number of the event in the trace, starting from 1.
+    pub line: usize,
+    /// The ESR value reported for the exception.
+    pub esr: u64,
+    /// The exception class reported for the exception, if the trace includes it separately.
+    pub ec: Option<u64>,
+    /// The FAR value reported for the exception, if any.
+    pub far: Option<u64>,
+}
+
+/// A problem found with an event in a trace.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum TraceIssue {
+    /// The ESR value couldn't be decoded.
+    #[error("Invalid ESR {esr:#x}: {error}")]
+    InvalidEsr { esr: u64, error: String },
+    /// The EC column doesn't match the EC field of the ESR.
+    #[error("EC column is {ec:#x} but ESR has EC {esr_ec:#x}")]
+    EcMismatch { ec: u64, esr_ec: u64 },
+    /// The ESR says that the FAR is valid, but no FAR was reported.
+    #[error("ESR says FAR is valid, but no FAR value was reported")]
+    MissingFar,
+}
+
+/// An issue found with the event on a particular line of a trace.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("Line {line}: {issue}")]
+pub struct TraceAnomaly {
+    /// The line number of the event in the trace, starting from 1.
+    pub line: usize,
+    /// What is wrong with the event.
+    pub issue: TraceIssue,
+}
+
+/// Parses a CSV trace of exception events.
+///
+/// The first line must be a header naming the columns. There must be an `ESR` column, and there
+/// may also be `EC` and `FAR` columns; any other columns are ignored. Column names are not case
+/// sensitive. Values are parsed with [`parse_number`](crate::parse_number), and may be empty.
+pub fn parse_trace(csv: &str) -> Result<Vec<TraceEvent>, TraceError> {
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let (_, header) = lines.next().ok_or(TraceError::Empty)?;
+    let columns: Vec<String> = split_csv_line(header)
+        .map(|column| column.to_ascii_uppercase())
+        .collect();
+    let column_index = |name: &str| columns.iter().position(|column| column == name);
+    let esr_index = column_index("ESR").ok_or(TraceError::MissingEsrColumn)?;
+    let ec_index = column_index("EC");
+    let far_index = column_index("FAR");
+
+    lines
+        .map(|(index, line)| {
+            let line_number = index + 1;
+            let cells: Vec<&str> = split_csv_line(line).collect();
+            let get = |column_index: Option<usize>, column: &'static str| match column_index
+                .and_then(|i| cells.get(i))
+            {
+                Some(value) if !value.is_empty() => {
+                    parse_number(value)
+                        .map(Some)
+                        .map_err(|_| TraceError::InvalidValue {
+                            line: line_number,
+                            column,
+                            value: value.to_string(),
+                        })
+                }
+                _ => Ok(None),
+            };
+            Ok(TraceEvent {
+                line: line_number,
+                esr: get(Some(esr_index), "ESR")?.ok_or_else(|| TraceError::InvalidValue {
+                    line: line_number,
+                    column: "ESR",
+                    value: String::new(),
+                })?,
+                ec: get(ec_index, "EC")?,
+                far: get(far_index, "FAR")?,
+            })
+        })
+        .collect()
+}
+
+fn split_csv_line(line: &str) -> impl Iterator<Item = &str> {
+    line.split(',').map(|cell| cell.trim().trim_matches('"'))
+}
+
+/// Checks each event in the given trace for internal consistency, returning any anomalies found.
+pub fn check_trace(events: &[TraceEvent]) -> Vec<TraceAnomaly> {
+    let mut anomalies = vec![];
+    for event in events {
+        let mut report = |issue| {
+            anomalies.push(TraceAnomaly {
+                line: event.line,
+                issue,
+            })
+        };
+        let fields = match decode(event.esr) {
+            Ok(fields) => fields,
+            Err(e) => {
+                report(TraceIssue::InvalidEsr {
+                    esr: event.esr,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let esr_ec = FieldInfo::find(&fields, "EC").map_or(0, |ec| ec.value);
+        if let Some(ec) = event.ec {
+            if ec != esr_ec {
+                report(TraceIssue::EcMismatch { ec, esr_ec });
+            }
+        }
+        if far_valid(&fields) == Some(true) && event.far.is_none() {
+            report(TraceIssue::MissingFar);
+        }
+    }
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACE: &str = "time,EC,esr,far\n\
+                         100,0x25,0x96000045,0x1000\n\
+                         200,0x24,0x96000045,0x2000\n\
+                         300,0x15,0x56000000,\n\
+                         400,,0x92000045,\n\
+                         500,,0xffffffff,\n";
+
+    #[test]
+    fn parse() {
+        let events = parse_trace(TRACE).unwrap();
+        assert_eq!(events.len(), 5);
+        assert_eq!(
+            events[0],
+            TraceEvent {
+                line: 2,
+                esr: 0x96000045,
+                ec: Some(0x25),
+                far: Some(0x1000),
+            }
+        );
+        assert_eq!(events[2].far, None);
+        assert_eq!(events[3].ec, None);
+
+        assert_eq!(parse_trace(""), Err(TraceError::Empty));
+        assert_eq!(parse_trace("EC,FAR\n"), Err(TraceError::MissingEsrColumn));
+        assert_eq!(
+            parse_trace("ESR\nfoo\n"),
+            Err(TraceError::InvalidValue {
+                line: 2,
+                column: "ESR",
+                value: "foo".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn check() {
+        let anomalies = check_trace(&parse_trace(TRACE).unwrap());
+        assert_eq!(
+            anomalies,
+            vec![
+                TraceAnomaly {
+                    line: 3,
+                    issue: TraceIssue::EcMismatch {
+                        ec: 0x24,
+                        esr_ec: 0x25
+                    },
+                },
+                TraceAnomaly {
+                    line: 5,
+                    issue: TraceIssue::MissingFar,
+                },
+                TraceAnomaly {
+                    line: 6,
+                    issue: TraceIssue::InvalidEsr {
+                        esr: 0xffffffff,
+                        error: "Invalid EC 0x3f".to_string(),
+                    },
+                },
+            ]
+        );
+    }
+}
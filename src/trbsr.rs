@@ -0,0 +1,144 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::esr::describe_fsc;
+use super::{DecodeError, FieldInfo};
+
+/// Decodes the given Trace Buffer Status/syndrome Register value, which describes a Trace Buffer
+/// Extension management event, or returns an error if it is not valid.
+pub fn decode_trbsr(trbsr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0a = FieldInfo::get(trbsr, "RES0", Some("Reserved"), 56, 64).check_res0()?;
+    let mss2 = FieldInfo::get(
+        trbsr,
+        "MSS2",
+        Some("Management event Specific Syndrome 2"),
+        32,
+        56,
+    );
+    let ec = FieldInfo::get(trbsr, "EC", Some("Event Class"), 26, 32).describe(describe_ec)?;
+    let res0b = FieldInfo::get(trbsr, "RES0", Some("Reserved"), 23, 26).check_res0()?;
+    let irq = FieldInfo::get_bit(trbsr, "IRQ", Some("Maintenance interrupt status"), 22);
+    let trg = FieldInfo::get_bit(trbsr, "TRG", Some("Triggered"), 21);
+    let wrap = FieldInfo::get_bit(trbsr, "WRAP", Some("Wrapped"), 20);
+    let res0c = FieldInfo::get_bit(trbsr, "RES0", Some("Reserved"), 19).check_res0()?;
+    let ea = FieldInfo::get_bit(trbsr, "EA", Some("External Abort"), 18);
+    let s = FieldInfo::get_bit(trbsr, "S", Some("Stopped"), 17).describe_bit(describe_s);
+    let res0d = FieldInfo::get_bit(trbsr, "RES0", Some("Reserved"), 16).check_res0()?;
+    let mss = FieldInfo::get(
+        trbsr,
+        "MSS",
+        Some("Management event Specific Syndrome"),
+        0,
+        16,
+    );
+    let mss = FieldInfo {
+        subfields: decode_mss(ec.value, mss.value)?,
+        ..mss
+    };
+
+    Ok(vec![
+        res0a, mss2, ec, res0b, irq, trg, wrap, res0c, ea, s, res0d, mss,
+    ])
+}
+
+fn decode_mss(ec: u64, mss: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    match ec {
+        0b000000 => {
+            let res0 = FieldInfo::get(mss, "RES0", Some("Reserved"), 6, 16).check_res0()?;
+            let bsc = FieldInfo::get(mss, "BSC", Some("Buffer Status Code"), 0, 6)
+                .describe(describe_bsc)?;
+            Ok(vec![res0, bsc])
+        }
+        0b011110 | 0b100100 | 0b100101 => {
+            let res0 = FieldInfo::get(mss, "RES0", Some("Reserved"), 6, 16).check_res0()?;
+            let fsc = FieldInfo::get(mss, "FSC", Some("Fault Status Code"), 0, 6)
+                .describe(describe_fsc)?;
+            Ok(vec![res0, fsc])
+        }
+        _ => Ok(vec![]),
+    }
+}
+
+fn describe_ec(ec: u64) -> Result<&'static str, DecodeError> {
+    Ok(match ec {
+        0b000000 => "Other buffer management event",
+        0b011110 => "Granule Protection Check fault, other than GPF, on write to trace buffer",
+        0b011111 => "IMPLEMENTATION DEFINED buffer management event",
+        0b100100 => "Stage 1 Data Abort on write to trace buffer",
+        0b100101 => "Stage 2 Data Abort on write to trace buffer",
+        _ => return Err(DecodeError::InvalidEc { ec }),
+    })
+}
+
+fn describe_bsc(bsc: u64) -> Result<&'static str, DecodeError> {
+    Ok(match bsc {
+        0b000000 => "Collection not stopped, or stopped for another reason",
+        0b000001 => "Trace buffer filled",
+        0b000010 => "Trigger Event",
+        _ => "Reserved",
+    })
+}
+
+fn describe_s(s: bool) -> &'static str {
+    if s {
+        "Collection stopped"
+    } else {
+        "Collection not stopped"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_filled() {
+        let decoded = decode_trbsr(0x0042_0001).unwrap();
+        assert_eq!(
+            decoded[2].description.as_deref(),
+            Some("Other buffer management event")
+        );
+        assert!(decoded[4].as_bit());
+        assert!(decoded[9].as_bit());
+        assert_eq!(
+            decoded[11].subfields[1].description.as_deref(),
+            Some("Trace buffer filled")
+        );
+    }
+
+    #[test]
+    fn stage1_abort() {
+        let decoded = decode_trbsr(0x9042_0007).unwrap();
+        assert_eq!(
+            decoded[2].description.as_deref(),
+            Some("Stage 1 Data Abort on write to trace buffer")
+        );
+        assert_eq!(
+            decoded[11].subfields[1].description.as_deref(),
+            Some("Translation fault, level 3.")
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            decode_trbsr(0x0400_0000),
+            Err(DecodeError::InvalidEc { ec: 1 })
+        ));
+        assert!(matches!(
+            decode_trbsr(0x0080_0000),
+            Err(DecodeError::InvalidRes0 { res0: 0b001 })
+        ));
+    }
+}
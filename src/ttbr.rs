@@ -0,0 +1,81 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{DecodeError, FieldInfo};
+
+/// Decodes the given Translation Table Base Register value, such as TTBR0_EL1 or TTBR1_EL2.
+///
+/// The corresponding TCR value may be given to describe how much of the ASID is used, as TCR.AS
+/// selects between 8-bit and 16-bit ASIDs. The base address is given assuming 48-bit physical
+/// addresses; with 52-bit physical addresses bits 2 to 5 of BADDR hold bits 48 to 51 of it.
+pub fn decode_ttbr(ttbr: u64, tcr: Option<u64>) -> Result<Vec<FieldInfo>, DecodeError> {
+    let asid = FieldInfo::get(ttbr, "ASID", Some("Address Space Identifier"), 48, 64);
+    let asid_description = match tcr.map(|tcr| tcr >> 36 & 1 == 1) {
+        Some(true) => format!("16-bit ASID {:#x}", asid.value),
+        Some(false) => format!(
+            "8-bit ASID {:#x}, as TCR.AS is 0 the upper 8 bits are ignored",
+            asid.value & 0xff
+        ),
+        None => format!(
+            "ASID {:#x}, or {:#x} if TCR.AS is 0 and only the lower 8 bits are used",
+            asid.value,
+            asid.value & 0xff
+        ),
+    };
+    let asid = asid.with_description(asid_description);
+    let baddr = FieldInfo::get(ttbr, "BADDR", Some("Translation table base address"), 1, 48);
+    let baddr_description = format!("{:#x}", baddr.value << 1);
+    let baddr = baddr.with_description(baddr_description);
+    let cnp =
+        FieldInfo::get_bit(ttbr, "CnP", Some("Common not Private"), 0).describe_bit(describe_cnp);
+
+    Ok(vec![asid, baddr, cnp])
+}
+
+fn describe_cnp(cnp: bool) -> &'static str {
+    if cnp {
+        "Translation table entries may be shared with other PEs in the Inner Shareable domain"
+    } else {
+        "Translation table entries are private to this PE"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asid_width() {
+        let ttbr = 0x1234_0000_8123_4001;
+        let decoded = decode_ttbr(ttbr, None).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(
+            decoded[0].description,
+            Some(
+                "ASID 0x1234, or 0x34 if TCR.AS is 0 and only the lower 8 bits are used"
+                    .to_string()
+            )
+        );
+        assert_eq!(decoded[1].description, Some("0x81234000".to_string()));
+        assert_eq!(decoded[2].value, 1);
+        assert_eq!(
+            decode_ttbr(ttbr, Some(1 << 36)).unwrap()[0].description,
+            Some("16-bit ASID 0x1234".to_string())
+        );
+        assert_eq!(
+            decode_ttbr(ttbr, Some(0)).unwrap()[0].description,
+            Some("8-bit ASID 0x34, as TCR.AS is 0 the upper 8 bits are ignored".to_string())
+        );
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright 2021 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoders for the SVE and SME vector length control registers.
+
+use super::{DecodeError, FieldInfo, Unit};
+
+/// Decodes the given SVE Control Register value for any Exception level, or returns an error if it
+/// is not valid.
+///
+/// The vector length derived from LEN is the requested one; the effective length may be lower if
+/// the PE doesn't support it or a higher Exception level constrains it.
+pub fn decode_zcr(zcr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0 = FieldInfo::get(zcr, "RES0", Some("Reserved"), 9, 64).check_res0()?;
+    let mut fields = vec![res0];
+    fields.extend(decode_len(zcr));
+    Ok(fields)
+}
+
+/// Decodes the given SME Control Register value for any Exception level, or returns an error if it
+/// is not valid.
+///
+/// As for ZCR_ELx, the vector length derived from LEN is the requested streaming vector length.
+pub fn decode_smcr(smcr: u64) -> Result<Vec<FieldInfo>, DecodeError> {
+    let res0a = FieldInfo::get(smcr, "RES0", Some("Reserved"), 32, 64).check_res0()?;
+    let fa64 = FieldInfo::get_bit(smcr, "FA64", Some("Full A64 instruction set"), 31)
+        .describe_bit(describe_fa64);
+    let ezt0 = FieldInfo::get_bit(smcr, "EZT0", Some("Enable ZT0"), 30).describe_bit(describe_ezt0);
+    let res0b = FieldInfo::get(smcr, "RES0", Some("Reserved"), 9, 30).check_res0()?;
+    let mut fields = vec![res0a, fa64, ezt0, res0b];
+    fields.extend(decode_len(smcr));
+    Ok(fields)
+}
+
+/// Decodes the RAZ/WI and LEN fields shared by ZCR_ELx and SMCR_ELx.
+fn decode_len(value: u64) -> [FieldInfo; 2] {
+    let raz = FieldInfo::get(value, "RAZ/WI", Some("Read-as-zero, writes ignored"), 4, 9);
+    let len = FieldInfo::get(value, "LEN", Some("Requested vector length"), 0, 4);
+    let bits = (len.value + 1) * 128;
+    let len_description = format!("{} bits", bits);
+    let len = len
+        .with_description(len_description)
+        .with_derived(bits as i64, Unit::Bits);
+    [raz, len]
+}
+
+fn describe_fa64(fa64: bool) -> &'static str {
+    if fa64 {
+        "Full A64 instruction set permitted in Streaming SVE mode"
+    } else {
+        "Only the Streaming SVE subset of the A64 instruction set permitted in Streaming SVE mode"
+    }
+}
+
+fn describe_ezt0(ezt0: bool) -> &'static str {
+    if ezt0 {
+        "ZT0 accesses not trapped"
+    } else {
+        "ZT0 accesses trapped"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zcr() {
+        let decoded = decode_zcr(0x3).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded[2].description, Some("512 bits".to_string()));
+        assert_eq!(decoded[2].derived.as_ref().unwrap().value, 512);
+        assert!(matches!(
+            decode_zcr(1 << 9),
+            Err(DecodeError::InvalidRes0 { .. })
+        ));
+    }
+
+    #[test]
+    fn smcr() {
+        let decoded = decode_smcr(0xc000_000f).unwrap();
+        assert_eq!(decoded.len(), 6);
+        assert!(decoded[1].as_bit());
+        assert_eq!(
+            decoded[2].description,
+            Some("ZT0 accesses not trapped".to_string())
+        );
+        assert_eq!(decoded[5].description, Some("2048 bits".to_string()));
+    }
+}